@@ -40,6 +40,10 @@ fn main() -> Result<()> {
 
 include!("model_manager/ui_bootstrap.rs");
 include!("model_manager/config.rs");
+include!("../ime/config_writer.rs");
+include!("../ime/notify.rs");
 include!("model_manager/catalog.rs");
 include!("model_manager/download.rs");
+include!("model_manager/login_item.rs");
+include!("model_manager/compare.rs");
 include!("model_manager/app.rs");
@@ -8,6 +8,11 @@ struct ModelManagerApp {
     config: AppConfig,
     hotkey_status: String,
     hotkey_recording: bool,
+    login_item: bool,
+    compare_path: String,
+    compare_rows: Vec<CompareRow>,
+    compare_current: Option<String>,
+    compare_rx: Option<Receiver<CompareEvent>>,
 }
 
 impl ModelManagerApp {
@@ -29,6 +34,58 @@ impl ModelManagerApp {
             hotkey_status: format!("当前: {}", config.hotkey.label()),
             config,
             hotkey_recording: false,
+            login_item: login_item_enabled(),
+            compare_path: latest_history_clip()
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            compare_rows: Vec::new(),
+            compare_current: None,
+            compare_rx: None,
+        }
+    }
+
+    fn start_model_comparison(&mut self) {
+        if self.compare_rx.is_some() {
+            return;
+        }
+        let models: Vec<(String, PathBuf)> = asr_entries()
+            .into_iter()
+            .map(|entry| (entry.name.to_string(), entry.path(&self.model_dir)))
+            .filter(|(_, path)| path.exists())
+            .collect();
+        if models.is_empty() {
+            self.status = "未安装 ASR 模型，无法对比".to_string();
+            return;
+        }
+        let samples = match load_compare_sample(Path::new(self.compare_path.trim())) {
+            Ok(samples) => samples,
+            Err(e) => {
+                self.status = format!("模型对比失败: {e}");
+                return;
+            }
+        };
+
+        let (tx, rx) = mpsc::channel();
+        self.compare_rows.clear();
+        self.compare_rx = Some(rx);
+        self.status = format!("模型对比中（共 {} 个模型）", models.len());
+        run_model_comparison(samples, models, tx);
+    }
+
+    fn save_login_item(&mut self, enabled: bool) {
+        match set_login_item(enabled) {
+            Ok(()) => {
+                self.login_item = enabled;
+                self.status = if enabled {
+                    "已开启开机启动（下次登录生效）".to_string()
+                } else {
+                    "已关闭开机启动".to_string()
+                };
+            }
+            Err(e) => {
+                self.login_item = login_item_enabled();
+                self.status = format!("设置开机启动失败: {e}");
+            }
         }
     }
 
@@ -119,6 +176,7 @@ impl ModelManagerApp {
                     self.downloading.remove(&id);
                     self.progress.remove(&id);
                     self.status = format!("下载完成: {id}");
+                    post_notification("MoFA IME", &format!("模型下载完成: {id}"));
                 }
                 DownloadEvent::Error { id, message } => {
                     self.downloading.remove(&id);
@@ -127,6 +185,22 @@ impl ModelManagerApp {
                 }
             }
         }
+
+        let mut compare_done = false;
+        if let Some(rx) = &self.compare_rx {
+            while let Ok(evt) = rx.try_recv() {
+                match evt {
+                    CompareEvent::Started(model) => self.compare_current = Some(model),
+                    CompareEvent::Row(row) => self.compare_rows.push(row),
+                    CompareEvent::Finished => compare_done = true,
+                }
+            }
+        }
+        if compare_done {
+            self.compare_rx = None;
+            self.compare_current = None;
+            self.status = "模型对比完成".to_string();
+        }
     }
 
     fn open_model_dir(&mut self) {
@@ -218,6 +292,66 @@ impl ModelManagerApp {
         });
     }
 
+    fn compare_panel(&mut self, ui: &mut egui::Ui) {
+        let running = self.compare_rx.is_some();
+        ui.horizontal(|ui| {
+            ui.label("录音 (WAV):");
+            ui.add(egui::TextEdit::singleline(&mut self.compare_path).desired_width(360.0));
+            if centered_button(ui, "用最近一次录音").clicked() {
+                match latest_history_clip() {
+                    Some(path) => self.compare_path = path.to_string_lossy().into_owned(),
+                    None => {
+                        self.status = "没有保存的录音（需开启 history_audio）".to_string();
+                    }
+                }
+            }
+            let button = egui::Button::new(if running {
+                "对比中..."
+            } else {
+                "开始对比"
+            })
+            .min_size(egui::vec2(0.0, 30.0));
+            if ui.add_enabled(!running, button).clicked() {
+                self.start_model_comparison();
+            }
+        });
+        ui.small("依次用每个已安装的 ASR 模型转写同一段录音，逐个加载以控制内存。");
+        if let Some(model) = &self.compare_current {
+            ui.small(format!("正在转写: {model}"));
+        }
+
+        let fastest = fastest_compare_row(&self.compare_rows);
+        for (idx, row) in self.compare_rows.iter().enumerate() {
+            egui::Frame::group(ui.style())
+                .inner_margin(egui::Margin::same(8.0))
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.strong(&row.model);
+                        let latency = format!("{} ms", row.elapsed_ms);
+                        if fastest == Some(idx) {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(70, 140, 80),
+                                format!("{latency}（最快）"),
+                            );
+                        } else {
+                            ui.label(latency);
+                        }
+                    });
+                    match &row.text {
+                        Ok(text) if text.is_empty() => {
+                            ui.small("（无识别结果）");
+                        }
+                        Ok(text) => {
+                            ui.label(text);
+                        }
+                        Err(e) => {
+                            ui.colored_label(egui::Color32::from_rgb(150, 80, 80), e);
+                        }
+                    }
+                });
+        }
+    }
+
     fn section(&mut self, ui: &mut egui::Ui, title: &str, entries: &[ModelEntry]) {
         ui.heading(title);
         ui.add_space(6.0);
@@ -302,6 +436,14 @@ impl ModelManagerApp {
     }
 }
 
+/// 设置器顶部展示、可一键复制的路径，与主程序实际读写的位置一致。
+fn copyable_paths(model_dir: &Path, config_path: &Path) -> [(&'static str, String); 2] {
+    [
+        ("主程序模型目录", model_dir.to_string_lossy().into_owned()),
+        ("配置文件", config_path.to_string_lossy().into_owned()),
+    ]
+}
+
 fn common_hotkey_presets() -> &'static [(&'static str, HotkeySpec)] {
     const PRESETS: [(&str, HotkeySpec); 19] = [
         ("Alt+R", HotkeySpec { keycode: 15, modifiers: HOTKEY_MOD_ALT }),
@@ -338,7 +480,15 @@ impl eframe::App for ModelManagerApp {
 
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("MoFA IME 设置");
-            ui.small("主程序模型目录: ~/.mofa/models");
+            for (label, path) in copyable_paths(&self.model_dir, &hotkey_config_path()) {
+                ui.horizontal(|ui| {
+                    ui.small(format!("{label}: {path}"));
+                    if ui.small_button("复制路径").clicked() {
+                        ui.output_mut(|o| o.copied_text = path.clone());
+                        self.status = format!("已复制{label}路径");
+                    }
+                });
+            }
             ui.add_space(8.0);
 
             ui.horizontal(|ui| {
@@ -401,6 +551,11 @@ impl eframe::App for ModelManagerApp {
                             OutputModeCfg::Asr,
                             OutputModeCfg::Asr.label(),
                         );
+                        ui.selectable_value(
+                            &mut self.config.output_mode,
+                            OutputModeCfg::Bilingual,
+                            OutputModeCfg::Bilingual.label(),
+                        );
                     });
             });
             ui.horizontal(|ui| {
@@ -443,6 +598,9 @@ impl eframe::App for ModelManagerApp {
                             AsrChoice::Medium,
                             AsrChoice::Medium.label(),
                         );
+                        for choice in AsrChoice::ENGLISH_ONLY {
+                            ui.selectable_value(&mut self.config.asr_model, choice, choice.label());
+                        }
                     });
             });
 
@@ -452,6 +610,10 @@ impl eframe::App for ModelManagerApp {
                     self.config.show_floating_orb = show_orb;
                     setting_changed = true;
                 }
+                let mut login_item = self.login_item;
+                if ui.checkbox(&mut login_item, "开机启动").changed() {
+                    self.save_login_item(login_item);
+                }
             });
 
             if old_output != self.config.output_mode
@@ -476,6 +638,11 @@ impl eframe::App for ModelManagerApp {
                 ui.label(format!("状态: {}", self.status));
             });
 
+            ui.add_space(10.0);
+            egui::CollapsingHeader::new("模型对比")
+                .default_open(false)
+                .show(ui, |ui| self.compare_panel(ui));
+
             ui.add_space(10.0);
             ui.separator();
             ui.add_space(10.0);
@@ -490,3 +657,20 @@ impl eframe::App for ModelManagerApp {
         });
     }
 }
+
+#[cfg(test)]
+mod app_tests {
+    use super::*;
+
+    #[test]
+    fn copied_paths_match_resolved_paths() {
+        let model_dir = PathBuf::from("/Users/测试/.mofa/models");
+        let config_path = hotkey_config_path();
+        let [(model_label, model), (config_label, config)] =
+            copyable_paths(&model_dir, &config_path);
+        assert_eq!(model_label, "主程序模型目录");
+        assert_eq!(model, "/Users/测试/.mofa/models");
+        assert_eq!(config_label, "配置文件");
+        assert_eq!(PathBuf::from(config), config_path);
+    }
+}
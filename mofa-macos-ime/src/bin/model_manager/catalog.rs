@@ -189,15 +189,23 @@ enum AsrModel {
     WhisperBase,
     WhisperTiny,
     WhisperMedium,
+    WhisperSmallEn,
+    WhisperBaseEn,
+    WhisperTinyEn,
+    WhisperMediumEn,
 }
 
 impl AsrModel {
-    fn all() -> [Self; 4] {
+    fn all() -> [Self; 8] {
         [
             Self::WhisperSmall,
             Self::WhisperBase,
             Self::WhisperTiny,
             Self::WhisperMedium,
+            Self::WhisperSmallEn,
+            Self::WhisperBaseEn,
+            Self::WhisperTinyEn,
+            Self::WhisperMediumEn,
         ]
     }
 
@@ -207,6 +215,10 @@ impl AsrModel {
             Self::WhisperBase => "asr:ggml-base.bin",
             Self::WhisperSmall => "asr:ggml-small.bin",
             Self::WhisperMedium => "asr:ggml-medium.bin",
+            Self::WhisperTinyEn => "asr:ggml-tiny.en.bin",
+            Self::WhisperBaseEn => "asr:ggml-base.en.bin",
+            Self::WhisperSmallEn => "asr:ggml-small.en.bin",
+            Self::WhisperMediumEn => "asr:ggml-medium.en.bin",
         }
     }
 
@@ -216,6 +228,10 @@ impl AsrModel {
             Self::WhisperBase => "ggml-base.bin",
             Self::WhisperSmall => "ggml-small.bin",
             Self::WhisperMedium => "ggml-medium.bin",
+            Self::WhisperTinyEn => "ggml-tiny.en.bin",
+            Self::WhisperBaseEn => "ggml-base.en.bin",
+            Self::WhisperSmallEn => "ggml-small.en.bin",
+            Self::WhisperMediumEn => "ggml-medium.en.bin",
         }
     }
 
@@ -225,6 +241,10 @@ impl AsrModel {
             Self::WhisperBase => "Whisper Base",
             Self::WhisperSmall => "Whisper Small",
             Self::WhisperMedium => "Whisper Medium",
+            Self::WhisperTinyEn => "Whisper Tiny (English)",
+            Self::WhisperBaseEn => "Whisper Base (English)",
+            Self::WhisperSmallEn => "Whisper Small (English)",
+            Self::WhisperMediumEn => "Whisper Medium (English)",
         }
     }
 
@@ -234,15 +254,19 @@ impl AsrModel {
             Self::WhisperBase => "速度与精度平衡",
             Self::WhisperSmall => "当前主流程默认",
             Self::WhisperMedium => "精度更高，体积大",
+            Self::WhisperTinyEn
+            | Self::WhisperBaseEn
+            | Self::WhisperSmallEn
+            | Self::WhisperMediumEn => "纯英文，language=en 时自动优先使用",
         }
     }
 
     fn size_mb(self) -> u64 {
         match self {
-            Self::WhisperTiny => 72,
-            Self::WhisperBase => 142,
-            Self::WhisperSmall => 466,
-            Self::WhisperMedium => 1500,
+            Self::WhisperTiny | Self::WhisperTinyEn => 72,
+            Self::WhisperBase | Self::WhisperBaseEn => 142,
+            Self::WhisperSmall | Self::WhisperSmallEn => 466,
+            Self::WhisperMedium | Self::WhisperMediumEn => 1500,
         }
     }
 
@@ -252,6 +276,10 @@ impl AsrModel {
             Self::WhisperBase => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
             Self::WhisperSmall => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin",
             Self::WhisperMedium => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin",
+            Self::WhisperTinyEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.en.bin",
+            Self::WhisperBaseEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.en.bin",
+            Self::WhisperSmallEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.en.bin",
+            Self::WhisperMediumEn => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.en.bin",
         }
     }
 }
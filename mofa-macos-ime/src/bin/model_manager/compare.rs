@@ -0,0 +1,108 @@
+// 模型对比：同一段录音依次用每个已安装的 ASR 模型转写，对照文本与耗时。
+// 逐个加载、用完即释放，任何时刻只占一个模型的内存。
+
+#[derive(Clone, Debug)]
+struct CompareRow {
+    model: String,
+    /// 转写文本；失败时为错误信息
+    text: Result<String, String>,
+    elapsed_ms: u64,
+}
+
+enum CompareEvent {
+    Started(String),
+    Row(CompareRow),
+    Finished,
+}
+
+/// 汇总结果：用时最短的成功行下标（用于高亮），失败行不参与。
+fn fastest_compare_row(rows: &[CompareRow]) -> Option<usize> {
+    rows.iter()
+        .enumerate()
+        .filter(|(_, r)| r.text.is_ok())
+        .min_by_key(|(_, r)| r.elapsed_ms)
+        .map(|(idx, _)| idx)
+}
+
+/// 输入法开启 history_audio 时保存的最近一段录音。
+fn latest_history_clip() -> Option<PathBuf> {
+    let dir = dirs::home_dir()?.join(".mofa").join("history-audio");
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("wav"))
+        .max()
+}
+
+fn load_compare_sample(path: &Path) -> Result<Vec<f32>> {
+    let (samples, rate) = mofa_input::audio::read_wav(path)
+        .map_err(|e| anyhow::anyhow!("读取 WAV 失败 {}: {e}", path.display()))?;
+    if samples.is_empty() {
+        anyhow::bail!("音频为空: {}", path.display());
+    }
+    Ok(mofa_input::asr::audio::resample_to_16khz(&samples, rate))
+}
+
+fn run_model_comparison(
+    samples: Vec<f32>,
+    models: Vec<(String, PathBuf)>,
+    tx: Sender<CompareEvent>,
+) {
+    thread::spawn(move || {
+        for (name, path) in models {
+            let _ = tx.send(CompareEvent::Started(name.clone()));
+            let started = std::time::Instant::now();
+            let text = mofa_input::asr::AsrSession::new(&path)
+                .and_then(|session| session.transcribe(&samples))
+                .map(|t| t.trim().to_string())
+                .map_err(|e| e.to_string());
+            let _ = tx.send(CompareEvent::Row(CompareRow {
+                model: name,
+                text,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+            }));
+        }
+        let _ = tx.send(CompareEvent::Finished);
+    });
+}
+
+#[cfg(test)]
+mod compare_tests {
+    use super::*;
+
+    fn row(model: &str, text: Result<&str, &str>, elapsed_ms: u64) -> CompareRow {
+        CompareRow {
+            model: model.to_string(),
+            text: text.map(str::to_string).map_err(str::to_string),
+            elapsed_ms,
+        }
+    }
+
+    #[test]
+    fn fastest_row_ignores_failed_models() {
+        let rows = [
+            row("Small", Ok("你好世界"), 900),
+            row("Medium", Err("模型加载失败"), 10),
+            row("Base", Ok("你好，世界"), 300),
+        ];
+        assert_eq!(fastest_compare_row(&rows), Some(2));
+        assert_eq!(fastest_compare_row(&rows[1..2]), None);
+        assert_eq!(fastest_compare_row(&[]), None);
+    }
+
+    #[test]
+    fn comparison_reports_each_model_in_order_then_finishes() {
+        let (tx, rx) = mpsc::channel();
+        let missing = |name: &str| (name.to_string(), PathBuf::from("/nonexistent").join(name));
+        run_model_comparison(vec![0.0; 1600], vec![missing("Tiny"), missing("Base")], tx);
+
+        let events: Vec<CompareEvent> = rx.iter().collect();
+        assert_eq!(events.len(), 5);
+        assert!(matches!(&events[0], CompareEvent::Started(m) if m == "Tiny"));
+        assert!(matches!(&events[1], CompareEvent::Row(r) if r.model == "Tiny" && r.text.is_err()));
+        assert!(matches!(&events[2], CompareEvent::Started(m) if m == "Base"));
+        assert!(matches!(&events[3], CompareEvent::Row(r) if r.model == "Base"));
+        assert!(matches!(events[4], CompareEvent::Finished));
+    }
+}
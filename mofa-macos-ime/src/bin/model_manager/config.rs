@@ -116,6 +116,7 @@ impl HotkeySpec {
 enum OutputModeCfg {
     Llm,
     Asr,
+    Bilingual,
 }
 
 impl OutputModeCfg {
@@ -123,6 +124,7 @@ impl OutputModeCfg {
         match s.trim().to_ascii_lowercase().as_str() {
             "llm" => Some(Self::Llm),
             "asr" => Some(Self::Asr),
+            "bilingual" => Some(Self::Bilingual),
             _ => None,
         }
     }
@@ -131,6 +133,7 @@ impl OutputModeCfg {
         match self {
             Self::Llm => "llm",
             Self::Asr => "asr",
+            Self::Bilingual => "bilingual",
         }
     }
 
@@ -138,6 +141,7 @@ impl OutputModeCfg {
         match self {
             Self::Llm => "LLM 润色",
             Self::Asr => "ASR 原文",
+            Self::Bilingual => "原文 + 译文",
         }
     }
 }
@@ -271,9 +275,21 @@ enum AsrChoice {
     Base,
     Small,
     Medium,
+    TinyEn,
+    BaseEn,
+    SmallEn,
+    MediumEn,
 }
 
 impl AsrChoice {
+    /// 纯英文（`.en`）模型，`language=en` 时识别更快更准。
+    const ENGLISH_ONLY: [AsrChoice; 4] = [
+        AsrChoice::TinyEn,
+        AsrChoice::BaseEn,
+        AsrChoice::SmallEn,
+        AsrChoice::MediumEn,
+    ];
+
     fn from_token(s: &str) -> Option<Self> {
         match s.trim().to_ascii_lowercase().as_str() {
             "auto" => Some(Self::Auto),
@@ -281,6 +297,10 @@ impl AsrChoice {
             "ggml-base.bin" | "base" => Some(Self::Base),
             "ggml-small.bin" | "small" => Some(Self::Small),
             "ggml-medium.bin" | "medium" => Some(Self::Medium),
+            "ggml-tiny.en.bin" | "tiny.en" => Some(Self::TinyEn),
+            "ggml-base.en.bin" | "base.en" => Some(Self::BaseEn),
+            "ggml-small.en.bin" | "small.en" => Some(Self::SmallEn),
+            "ggml-medium.en.bin" | "medium.en" => Some(Self::MediumEn),
             _ => None,
         }
     }
@@ -292,6 +312,10 @@ impl AsrChoice {
             Self::Base => "ggml-base.bin",
             Self::Small => "ggml-small.bin",
             Self::Medium => "ggml-medium.bin",
+            Self::TinyEn => "ggml-tiny.en.bin",
+            Self::BaseEn => "ggml-base.en.bin",
+            Self::SmallEn => "ggml-small.en.bin",
+            Self::MediumEn => "ggml-medium.en.bin",
         }
     }
 
@@ -302,6 +326,10 @@ impl AsrChoice {
             Self::Base => "Whisper Base",
             Self::Small => "Whisper Small",
             Self::Medium => "Whisper Medium",
+            Self::TinyEn => "Whisper Tiny（英文）",
+            Self::BaseEn => "Whisper Base（英文）",
+            Self::SmallEn => "Whisper Small（英文）",
+            Self::MediumEn => "Whisper Medium（英文）",
         }
     }
 }
@@ -474,6 +502,7 @@ fn load_app_config() -> AppConfig {
         return AppConfig::default();
     };
 
+    let content = migrate_config(&content);
     let mut cfg = AppConfig::default();
     for line in content.lines() {
         let line = line.trim();
@@ -496,7 +525,7 @@ fn load_app_config() -> AppConfig {
             if let Some(choice) = AsrChoice::from_token(v) {
                 cfg.asr_model = choice;
             }
-        } else if let Some(v) = line.strip_prefix("show_floating_orb=") {
+        } else if let Some(v) = line.strip_prefix("floating_orb=") {
             cfg.show_floating_orb = v.trim().to_ascii_lowercase() == "true";
         }
     }
@@ -505,43 +534,14 @@ fn load_app_config() -> AppConfig {
 }
 
 fn save_app_config(cfg: &AppConfig) -> Result<()> {
-    let path = hotkey_config_path();
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .with_context(|| format!("创建配置目录失败: {}", parent.display()))?;
-    }
-    let mut lines: Vec<String> = match fs::read_to_string(&path) {
-        Ok(content) => content.lines().map(|line| line.to_string()).collect(),
-        Err(_) => Vec::new(),
-    };
     let pairs: Vec<(&str, String)> = vec![
         ("hotkey", cfg.hotkey.token()),
         ("output_mode", cfg.output_mode.token().to_string()),
         ("llm_model", cfg.llm_model.token().to_string()),
         ("asr_model", cfg.asr_model.token().to_string()),
-        ("show_floating_orb", cfg.show_floating_orb.to_string()),
+        ("floating_orb", cfg.show_floating_orb.to_string()),
     ];
-
-    for (key, value) in pairs {
-        let wanted = format!("{key}={value}");
-        let mut replaced = false;
-        for line in &mut lines {
-            if line.trim_start().starts_with(&format!("{key}=")) {
-                *line = wanted.clone();
-                replaced = true;
-                break;
-            }
-        }
-        if !replaced {
-            lines.push(wanted);
-        }
-    }
-    let mut out = lines.join("\n");
-    if !out.ends_with('\n') {
-        out.push('\n');
-    }
-    fs::write(&path, out).with_context(|| format!("写入配置失败: {}", path.display()))?;
-    Ok(())
+    write_config_values(&hotkey_config_path(), &pairs)
 }
 
 fn hotkey_modifiers_from_egui(modifiers: egui::Modifiers) -> u8 {
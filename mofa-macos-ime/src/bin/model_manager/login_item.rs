@@ -0,0 +1,100 @@
+// 开机启动：在 ~/Library/LaunchAgents 写入 LaunchAgent，登录时由 launchd 拉起输入法。
+
+const LAUNCH_AGENT_LABEL: &str = "org.mofa.macos-ime";
+
+fn launch_agent_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| {
+        h.join("Library/LaunchAgents")
+            .join(format!("{LAUNCH_AGENT_LABEL}.plist"))
+    })
+}
+
+/// 输入法主程序与设置器在同一目录。
+fn ime_executable_path() -> Result<PathBuf> {
+    let exe = std::env::current_exe().context("无法获取当前可执行文件路径")?;
+    let path = exe
+        .parent()
+        .map(|dir| dir.join("mofa-macos-ime"))
+        .context("无法获取可执行目录")?;
+    if !path.exists() {
+        anyhow::bail!("未找到输入法主程序: {}", path.display());
+    }
+    Ok(path)
+}
+
+fn escape_plist_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// 登录时启动一次；不设 KeepAlive，用户从托盘退出后不会被 launchd 重新拉起。
+fn launch_agent_plist(executable: &Path) -> String {
+    let program = escape_plist_text(&executable.to_string_lossy());
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{LAUNCH_AGENT_LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{program}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <false/>
+    <key>ProcessType</key>
+    <string>Interactive</string>
+</dict>
+</plist>
+"#
+    )
+}
+
+fn login_item_enabled() -> bool {
+    launch_agent_path().is_some_and(|p| p.exists())
+}
+
+/// 开启时写入（或覆盖为当前路径）plist，关闭时删除；下次登录生效。
+/// macOS 13 起首次写入会弹出“已添加登录项”通知，用户在 系统设置 -> 通用 -> 登录项 中关掉后 launchd 不再启动它。
+fn set_login_item(enabled: bool) -> Result<()> {
+    let path = launch_agent_path().context("无法定位家目录")?;
+    if !enabled {
+        match fs::remove_file(&path) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).with_context(|| format!("删除登录项失败: {}", path.display())),
+        }
+    }
+
+    let plist = launch_agent_plist(&ime_executable_path()?);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("创建目录失败: {}", dir.display()))?;
+    }
+    fs::write(&path, plist).with_context(|| format!("写入登录项失败: {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod login_item_tests {
+    use super::*;
+
+    #[test]
+    fn escape_plist_text_escapes_xml_specials() {
+        assert_eq!(escape_plist_text("a&b<c>"), "a&amp;b&lt;c&gt;");
+        assert_eq!(escape_plist_text("/usr/local/bin"), "/usr/local/bin");
+    }
+
+    #[test]
+    fn launch_agent_plist_runs_once_at_login() {
+        let plist = launch_agent_plist(Path::new("/Applications/MoFA & Co/mofa-macos-ime"));
+        assert!(plist.starts_with("<?xml"));
+        assert!(plist.contains("<string>org.mofa.macos-ime</string>"));
+        assert!(plist.contains("<string>/Applications/MoFA &amp; Co/mofa-macos-ime</string>"));
+        assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+        assert!(plist.contains("<key>KeepAlive</key>\n    <false/>"));
+    }
+}
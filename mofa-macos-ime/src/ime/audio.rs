@@ -1,29 +1,60 @@
 struct RecordingTicker {
     stop: Arc<AtomicBool>,
+    started: Instant,
+    show_delay_ms: u64,
     join: Option<std::thread::JoinHandle<()>>,
 }
 
+/// overlay_show_delay_ms：按住不足该时长时不显示悬浮窗，避免误触的短按闪一下。
+fn recording_overlay_due(held_ms: u64, show_delay_ms: u64) -> bool {
+    held_ms >= show_delay_ms
+}
+
 impl RecordingTicker {
-    fn start(samples: Arc<Mutex<Vec<f32>>>, sample_rate: u32, overlay: OverlayHandle) -> Self {
+    fn start(
+        samples: Arc<Mutex<Vec<f32>>>,
+        sample_rate: u32,
+        overlay: OverlayHandle,
+        show_delay_ms: u64,
+    ) -> Self {
         let stop = Arc::new(AtomicBool::new(false));
         let stop_flag = Arc::clone(&stop);
+        let started = Instant::now();
 
         let join = std::thread::spawn(move || {
+            let mut shown = false;
             while !stop_flag.load(Ordering::SeqCst) {
-                let len = samples.lock().map(|buf| buf.len()).unwrap_or(0);
-                let secs = len as f32 / sample_rate.max(1) as f32;
-                overlay.set_status("录音中");
-                overlay.set_preview(&format!("正在听写 {:.1}s", secs));
+                let held_ms = started.elapsed().as_millis() as u64;
+                if recording_overlay_due(held_ms, show_delay_ms) {
+                    if !shown {
+                        overlay.show_recording();
+                        shown = true;
+                    }
+                    let len = samples.lock().map(|buf| buf.len()).unwrap_or(0);
+                    let secs = len as f32 / sample_rate.max(1) as f32;
+                    overlay.set_status("录音中");
+                    overlay.set_preview(&format!("正在听写 {:.1}s", secs));
+                }
                 std::thread::sleep(Duration::from_millis(180));
             }
         });
 
         Self {
             stop,
+            started,
+            show_delay_ms,
             join: Some(join),
         }
     }
 
+    /// 悬浮窗是否已因按住足够久而出现。
+    fn overlay_shown(&self) -> bool {
+        recording_overlay_due(
+            self.started.elapsed().as_millis() as u64,
+            self.show_delay_ms,
+        )
+    }
+
     fn stop(mut self) {
         self.stop.store(true, Ordering::SeqCst);
         if let Some(join) = self.join.take() {
@@ -32,57 +63,109 @@ impl RecordingTicker {
     }
 }
 
-struct ActiveRecorder {
-    stream: cpal::Stream,
-    samples: Arc<Mutex<Vec<f32>>>,
-    sample_rate: u32,
+const APP_SWITCH_POLL_MS: u64 = 250;
+
+/// stop_on_app_switch：录音开始时的前台应用换成了别的应用才停止；
+/// 前台是输入法自己（点悬浮球、历史窗口）或取不到时不算切换。
+fn app_switch_should_stop(
+    recording_in: Option<&str>,
+    now: Option<&str>,
+    own: Option<&str>,
+) -> bool {
+    match now {
+        Some(now) => own != Some(now) && recording_in != Some(now),
+        None => false,
+    }
 }
 
-impl ActiveRecorder {
-    fn start() -> Result<Self> {
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or_else(|| anyhow!("未找到麦克风设备"))?;
+/// 前台应用的 bundle id；前台是本进程时为 None。
+fn frontmost_foreign_bundle_id() -> Option<String> {
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace == nil {
+            return None;
+        }
+        let app: id = msg_send![workspace, frontmostApplication];
+        if app == nil {
+            return None;
+        }
+        let pid: i32 = msg_send![app, processIdentifier];
+        if pid as u32 == std::process::id() {
+            return None;
+        }
+        let bundle_id: id = msg_send![app, bundleIdentifier];
+        nsstring_to_rust(bundle_id)
+    }
+}
 
-        let cfg = device.default_input_config()?;
-        let sample_rate = cfg.sample_rate().0;
-        let channels = cfg.channels() as usize;
-        let samples = Arc::new(Mutex::new(Vec::<f32>::new()));
+fn own_bundle_id() -> Option<String> {
+    unsafe {
+        let bundle: id = msg_send![class!(NSBundle), mainBundle];
+        if bundle == nil {
+            return None;
+        }
+        let bundle_id: id = msg_send![bundle, bundleIdentifier];
+        nsstring_to_rust(bundle_id)
+    }
+}
 
-        let stream = match cfg.sample_format() {
-            cpal::SampleFormat::F32 => {
-                let samples_buf = Arc::clone(&samples);
-                device.build_input_stream(
-                    &cfg.clone().into(),
-                    move |data: &[f32], _| append_mono_f32(&samples_buf, data, channels),
-                    move |err| eprintln!("[mofa-ime] 音频流错误: {err}"),
-                    None,
-                )?
-            }
-            cpal::SampleFormat::I16 => {
-                let samples_buf = Arc::clone(&samples);
-                device.build_input_stream(
-                    &cfg.clone().into(),
-                    move |data: &[i16], _| append_mono_i16(&samples_buf, data, channels),
-                    move |err| eprintln!("[mofa-ime] 音频流错误: {err}"),
-                    None,
-                )?
-            }
-            cpal::SampleFormat::U16 => {
-                let samples_buf = Arc::clone(&samples);
-                device.build_input_stream(
-                    &cfg.clone().into(),
-                    move |data: &[u16], _| append_mono_u16(&samples_buf, data, channels),
-                    move |err| eprintln!("[mofa-ime] 音频流错误: {err}"),
-                    None,
-                )?
+/// 录音期间轮询前台应用，切到别的应用时向工作线程发送一次结束信号，按已录内容照常处理。
+struct AppSwitchWatcher {
+    stop: Arc<AtomicBool>,
+    join: Option<std::thread::JoinHandle<()>>,
+}
+
+impl AppSwitchWatcher {
+    fn start(tx: Sender<HotkeySignal>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+        let join = std::thread::spawn(move || {
+            let own = own_bundle_id();
+            let recording_in = frontmost_foreign_bundle_id();
+            while !stop_flag.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(APP_SWITCH_POLL_MS));
+                let now = frontmost_foreign_bundle_id();
+                if app_switch_should_stop(recording_in.as_deref(), now.as_deref(), own.as_deref()) {
+                    eprintln!("[mofa-ime] 前台应用已切换到 {:?}，停止录音", now);
+                    let _ = tx.send(HotkeySignal::ForceStop);
+                    break;
+                }
             }
-            other => bail!("不支持的采样格式: {other:?}"),
-        };
+        });
+        Self {
+            stop,
+            join: Some(join),
+        }
+    }
 
-        stream.play()?;
+    fn stop(mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(join) = self.join.take() {
+            let _ = join.join();
+        }
+    }
+}
+
+/// 录音来源：真实麦克风由 `ActiveRecorder` 实现，测试时可换成返回固定缓冲的假来源。
+trait AudioSource: Sized {
+    fn start(mic: MicChannel) -> Result<Self>;
+    /// 录音过程中持续增长的 16k 单声道缓冲（供计时与增量转写读取）。
+    fn sample_buffer(&self) -> Arc<Mutex<Vec<f32>>>;
+    fn sample_rate(&self) -> u32;
+    /// 停止录音并返回 16k 单声道样本。
+    fn stop(self) -> Result<Vec<f32>>;
+}
+
+struct ActiveRecorder {
+    stream: cpal::Stream,
+    samples: Arc<Mutex<Vec<f32>>>,
+    sample_rate: u32,
+}
 
+impl AudioSource for ActiveRecorder {
+    fn start(mic: MicChannel) -> Result<Self> {
+        let samples = Arc::new(Mutex::new(Vec::<f32>::new()));
+        let (stream, sample_rate) = open_input_stream(mic, &samples, None)?;
         Ok(Self {
             stream,
             samples,
@@ -119,7 +202,257 @@ impl ActiveRecorder {
     }
 }
 
-fn append_mono_f32(buf: &Arc<Mutex<Vec<f32>>>, data: &[f32], channels: usize) {
+const CAPTURE_TARGET_RATE: u32 = 16_000;
+const PREROLL_MAX_MS: u64 = 1000;
+
+/// 打开默认麦克风，把单声道样本追加到 `samples`；`keep_ms` 为 Some 时只保留最近这段（预录）。
+fn open_input_stream(
+    mic: MicChannel,
+    samples: &Arc<Mutex<Vec<f32>>>,
+    keep_ms: Option<u64>,
+) -> Result<(cpal::Stream, u32)> {
+    let host = cpal::default_host();
+    let device = host
+        .default_input_device()
+        .ok_or_else(|| anyhow!("未找到麦克风设备"))?;
+
+    // 设备原生支持 16kHz 时直接按 16kHz 采集，省去重采样；capture_format 指定时只选该格式。
+    // 都没有时回退默认配置。
+    let format = CaptureFormat::from_index(CAPTURE_FORMAT.load(Ordering::SeqCst));
+    let cfg = match preferred_input_config(&device, format) {
+        Some(cfg) => cfg,
+        None => {
+            if format != CaptureFormat::Auto {
+                eprintln!("[mofa-ime] 麦克风不支持采样格式 {format:?}，使用默认格式");
+            }
+            device.default_input_config()?
+        }
+    };
+    let sample_rate = cfg.sample_rate().0;
+    let channels = cfg.channels() as usize;
+    let keep = keep_ms.map(|ms| preroll_capacity(sample_rate, ms));
+
+    let stream = match cfg.sample_format() {
+        cpal::SampleFormat::F32 => {
+            let samples_buf = Arc::clone(samples);
+            device.build_input_stream(
+                &cfg.clone().into(),
+                move |data: &[f32], _| {
+                    append_mono_f32(&samples_buf, data, channels, mic);
+                    keep_last_samples(&samples_buf, keep);
+                },
+                move |err| eprintln!("[mofa-ime] 音频流错误: {err}"),
+                None,
+            )?
+        }
+        cpal::SampleFormat::I16 => {
+            let samples_buf = Arc::clone(samples);
+            device.build_input_stream(
+                &cfg.clone().into(),
+                move |data: &[i16], _| {
+                    append_mono_i16(&samples_buf, data, channels, mic);
+                    keep_last_samples(&samples_buf, keep);
+                },
+                move |err| eprintln!("[mofa-ime] 音频流错误: {err}"),
+                None,
+            )?
+        }
+        cpal::SampleFormat::U16 => {
+            let samples_buf = Arc::clone(samples);
+            device.build_input_stream(
+                &cfg.clone().into(),
+                move |data: &[u16], _| {
+                    append_mono_u16(&samples_buf, data, channels, mic);
+                    keep_last_samples(&samples_buf, keep);
+                },
+                move |err| eprintln!("[mofa-ime] 音频流错误: {err}"),
+                None,
+            )?
+        }
+        other => bail!("不支持的采样格式: {other:?}"),
+    };
+
+    stream.play()?;
+    Ok((stream, sample_rate))
+}
+
+fn preroll_capacity(sample_rate: u32, ms: u64) -> usize {
+    (sample_rate as u64 * ms.min(PREROLL_MAX_MS) / 1000) as usize
+}
+
+fn keep_last_samples(buf: &Arc<Mutex<Vec<f32>>>, keep: Option<usize>) {
+    let Some(keep) = keep else {
+        return;
+    };
+    if let Ok(mut dst) = buf.lock() {
+        trim_to_last(&mut dst, keep);
+    }
+}
+
+/// 只保留末尾 `keep` 个样本。
+fn trim_to_last(buf: &mut Vec<f32>, keep: usize) {
+    if buf.len() > keep {
+        let excess = buf.len() - keep;
+        buf.drain(..excess);
+    }
+}
+
+/// 把预录样本拼到录音缓冲开头（此时录音流刚启动，缓冲里只有极少样本）。
+fn prepend_preroll(recording: &mut Vec<f32>, preroll: &[f32]) {
+    recording.splice(0..0, preroll.iter().copied());
+}
+
+/// preroll_ms>0 时常驻的麦克风采集，只保留最近一小段，按下热键时拼到录音开头，
+/// 避免按键生效前说出的第一个字被截掉。代价是麦克风一直开着（系统会持续显示麦克风指示）。
+struct PrerollCapture {
+    _stream: cpal::Stream,
+    samples: Arc<Mutex<Vec<f32>>>,
+    sample_rate: u32,
+    mic: MicChannel,
+    ms: u64,
+}
+
+impl PrerollCapture {
+    fn start(mic: MicChannel, ms: u64) -> Result<Self> {
+        let samples = Arc::new(Mutex::new(Vec::<f32>::new()));
+        let (stream, sample_rate) = open_input_stream(mic, &samples, Some(ms))?;
+        Ok(Self {
+            _stream: stream,
+            samples,
+            sample_rate,
+            mic,
+            ms,
+        })
+    }
+
+    /// 按配置开关/重建预录流；preroll_ms=0 时关闭麦克风。
+    fn sync(current: &mut Option<PrerollCapture>, mic: MicChannel, ms: u64) {
+        let ms = ms.min(PREROLL_MAX_MS);
+        if ms == 0 {
+            *current = None;
+            return;
+        }
+        if current.as_ref().is_some_and(|p| p.mic == mic && p.ms == ms) {
+            return;
+        }
+        *current = None;
+        match PrerollCapture::start(mic, ms) {
+            Ok(p) => *current = Some(p),
+            Err(e) => eprintln!("[mofa-ime] 预录采集启动失败: {e}"),
+        }
+    }
+
+    /// 把最近的预录样本拼到刚开始的录音前；采样率不一致时放弃拼接。
+    fn prepend_to(&self, recording: &Arc<Mutex<Vec<f32>>>, recording_rate: u32) {
+        if recording_rate != self.sample_rate {
+            return;
+        }
+        let preroll = match self.samples.lock() {
+            Ok(buf) => buf.clone(),
+            Err(_) => return,
+        };
+        if let Ok(mut dst) = recording.lock() {
+            prepend_preroll(&mut dst, &preroll);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct InputConfigRange {
+    channels: u16,
+    min_rate: u32,
+    max_rate: u32,
+    is_f32: bool,
+    is_i16: bool,
+    supported_format: bool,
+}
+
+impl InputConfigRange {
+    fn matches_format(&self, format: CaptureFormat) -> bool {
+        match format {
+            CaptureFormat::Auto => true,
+            CaptureFormat::F32 => self.is_f32,
+            CaptureFormat::I16 => self.is_i16,
+        }
+    }
+}
+
+/// 在设备支持的配置里挑一个覆盖 16kHz 的：优先单声道，其次 f32（指定格式时只看该格式）。
+fn pick_16k_input_range(ranges: &[InputConfigRange], format: CaptureFormat) -> Option<usize> {
+    ranges
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| {
+            r.supported_format
+                && r.channels > 0
+                && r.matches_format(format)
+                && r.min_rate <= CAPTURE_TARGET_RATE
+                && r.max_rate >= CAPTURE_TARGET_RATE
+        })
+        .min_by_key(|(_, r)| (r.channels, !r.is_f32))
+        .map(|(idx, _)| idx)
+}
+
+/// 指定格式但没有覆盖 16kHz 的配置时，退而取该格式下声道最少、采样率最高的一项（之后重采样）。
+/// 返回（下标, 采样率）；auto 或没有该格式时返回 None，由调用方用设备默认配置。
+fn pick_format_input_range(
+    ranges: &[InputConfigRange],
+    format: CaptureFormat,
+) -> Option<(usize, u32)> {
+    if let Some(idx) = pick_16k_input_range(ranges, format) {
+        return Some((idx, CAPTURE_TARGET_RATE));
+    }
+    if format == CaptureFormat::Auto {
+        return None;
+    }
+    ranges
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.supported_format && r.channels > 0 && r.matches_format(format))
+        .min_by_key(|(_, r)| (r.channels, std::cmp::Reverse(r.max_rate)))
+        .map(|(idx, r)| (idx, r.max_rate))
+}
+
+fn preferred_input_config(
+    device: &cpal::Device,
+    format: CaptureFormat,
+) -> Option<cpal::SupportedStreamConfig> {
+    let ranges: Vec<cpal::SupportedStreamConfigRange> =
+        device.supported_input_configs().ok()?.collect();
+    let summary: Vec<InputConfigRange> = ranges
+        .iter()
+        .map(|r| InputConfigRange {
+            channels: r.channels(),
+            min_rate: r.min_sample_rate().0,
+            max_rate: r.max_sample_rate().0,
+            is_f32: r.sample_format() == cpal::SampleFormat::F32,
+            is_i16: r.sample_format() == cpal::SampleFormat::I16,
+            supported_format: matches!(
+                r.sample_format(),
+                cpal::SampleFormat::F32 | cpal::SampleFormat::I16 | cpal::SampleFormat::U16
+            ),
+        })
+        .collect();
+    let (idx, rate) = pick_format_input_range(&summary, format)?;
+    Some(ranges[idx].clone().with_sample_rate(cpal::SampleRate(rate)))
+}
+
+/// 多声道帧取单声道：左/右声道直取，mix 为平均；单声道设备三者相同。
+fn downmix_frame(frame: &[f32], mic: MicChannel) -> f32 {
+    match mic {
+        MicChannel::Left => frame.first().copied().unwrap_or(0.0),
+        MicChannel::Right => frame.get(1).or(frame.first()).copied().unwrap_or(0.0),
+        MicChannel::Mix => {
+            if frame.is_empty() {
+                0.0
+            } else {
+                frame.iter().sum::<f32>() / frame.len() as f32
+            }
+        }
+    }
+}
+
+fn append_mono_f32(buf: &Arc<Mutex<Vec<f32>>>, data: &[f32], channels: usize, mic: MicChannel) {
     if channels == 0 {
         return;
     }
@@ -129,44 +462,45 @@ fn append_mono_f32(buf: &Arc<Mutex<Vec<f32>>>, data: &[f32], channels: usize) {
             return;
         }
         for frame in data.chunks(channels) {
-            let sum: f32 = frame.iter().copied().sum();
-            dst.push(sum / channels as f32);
+            dst.push(downmix_frame(frame, mic));
         }
     }
 }
 
-fn append_mono_i16(buf: &Arc<Mutex<Vec<f32>>>, data: &[i16], channels: usize) {
+fn append_mono_i16(buf: &Arc<Mutex<Vec<f32>>>, data: &[i16], channels: usize, mic: MicChannel) {
     if channels == 0 {
         return;
     }
     if let Ok(mut dst) = buf.lock() {
+        let mut converted = Vec::with_capacity(channels);
         for frame in data.chunks(channels) {
-            let mut sum = 0.0f32;
-            for s in frame {
-                sum += *s as f32 / i16::MAX as f32;
-            }
-            dst.push(sum / frame.len() as f32);
+            converted.clear();
+            converted.extend(frame.iter().map(|s| *s as f32 / i16::MAX as f32));
+            dst.push(downmix_frame(&converted, mic));
         }
     }
 }
 
-fn append_mono_u16(buf: &Arc<Mutex<Vec<f32>>>, data: &[u16], channels: usize) {
+fn append_mono_u16(buf: &Arc<Mutex<Vec<f32>>>, data: &[u16], channels: usize, mic: MicChannel) {
     if channels == 0 {
         return;
     }
     if let Ok(mut dst) = buf.lock() {
+        let mut converted = Vec::with_capacity(channels);
         for frame in data.chunks(channels) {
-            let mut sum = 0.0f32;
-            for s in frame {
-                sum += (*s as f32 / u16::MAX as f32) * 2.0 - 1.0;
-            }
-            dst.push(sum / frame.len() as f32);
+            converted.clear();
+            converted.extend(
+                frame
+                    .iter()
+                    .map(|s| (*s as f32 / u16::MAX as f32) * 2.0 - 1.0),
+            );
+            dst.push(downmix_frame(&converted, mic));
         }
     }
 }
 
 fn resample_to_16k(samples: &[f32], from_rate: u32) -> Vec<f32> {
-    const TARGET: u32 = 16_000;
+    const TARGET: u32 = CAPTURE_TARGET_RATE;
     if from_rate == TARGET || samples.is_empty() {
         return samples.to_vec();
     }
@@ -188,3 +522,313 @@ fn resample_to_16k(samples: &[f32], from_rate: u32) -> Vec<f32> {
 
     out
 }
+
+/// 可转写的音频文件：WAV 直接读取，m4a/caf/aac（如语音备忘录）交给 AVFoundation 解码。
+const DECODABLE_AUDIO_EXTENSIONS: &[&str] = &["wav", "m4a", "caf", "aac"];
+const DECODE_CHUNK_FRAMES: u32 = 1 << 16;
+
+fn is_decodable_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| {
+            DECODABLE_AUDIO_EXTENSIONS
+                .iter()
+                .any(|ext| e.eq_ignore_ascii_case(ext))
+        })
+        .unwrap_or(false)
+}
+
+/// 读取音频文件为单声道 f32 样本与原始采样率（多声道取平均）。
+fn read_audio_file(path: &Path) -> Result<(Vec<f32>, u32)> {
+    let is_wav = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    if is_wav {
+        return Ok(mofa_input::audio::read_wav(path)?);
+    }
+    let _pool = unsafe { NSAutoreleasePool::new(nil) };
+    unsafe { decode_with_avfoundation(path) }
+}
+
+/// 平面格式（每声道一段）的样本平均为单声道，按最短声道对齐。
+fn downmix_planar(channels: &[&[f32]]) -> Vec<f32> {
+    let frames = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+    match channels {
+        [] => Vec::new(),
+        [mono] => mono[..frames].to_vec(),
+        _ => (0..frames)
+            .map(|i| channels.iter().map(|c| c[i]).sum::<f32>() / channels.len() as f32)
+            .collect(),
+    }
+}
+
+/// 用 AVAudioFile 分块解码（processingFormat 固定为非交错 float32），内存只多占一个块。
+unsafe fn decode_with_avfoundation(path: &Path) -> Result<(Vec<f32>, u32)> {
+    let (Some(file_cls), Some(buffer_cls)) =
+        (Class::get("AVAudioFile"), Class::get("AVAudioPCMBuffer"))
+    else {
+        bail!("AVFoundation 不可用，无法解码 {:?}", path);
+    };
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| anyhow!("路径不是有效 UTF-8: {:?}", path))?;
+    let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_string(path_str)];
+
+    let mut err: id = nil;
+    let file: id = msg_send![file_cls, alloc];
+    let file: id = msg_send![file, initForReading: url error: &mut err];
+    if file == nil {
+        bail!("无法打开音频文件 {:?}: {}", path, ns_error_text(err));
+    }
+    let file: id = msg_send![file, autorelease];
+    let format: id = msg_send![file, processingFormat];
+    let sample_rate: f64 = msg_send![format, sampleRate];
+    let channels: u32 = msg_send![format, channelCount];
+    let total_frames: i64 = msg_send![file, length];
+    if channels == 0 || sample_rate <= 0.0 {
+        bail!("不支持的音频格式 {:?}", path);
+    }
+
+    let buffer: id = msg_send![buffer_cls, alloc];
+    let buffer: id = msg_send![
+        buffer,
+        initWithPCMFormat: format
+        frameCapacity: DECODE_CHUNK_FRAMES
+    ];
+    if buffer == nil {
+        bail!("无法分配解码缓冲 {:?}", path);
+    }
+    let buffer: id = msg_send![buffer, autorelease];
+
+    let mut samples = Vec::with_capacity(usize::try_from(total_frames).unwrap_or(0));
+    loop {
+        let position: i64 = msg_send![file, framePosition];
+        if position >= total_frames {
+            break;
+        }
+        let ok: BOOL = msg_send![file, readIntoBuffer: buffer error: &mut err];
+        if ok == NO {
+            bail!("解码失败 {:?}: {}", path, ns_error_text(err));
+        }
+        let frames: u32 = msg_send![buffer, frameLength];
+        if frames == 0 {
+            break;
+        }
+        let data: *const *const f32 = msg_send![buffer, floatChannelData];
+        if data.is_null() {
+            bail!("解码结果不是 float32: {:?}", path);
+        }
+        let planes: Vec<&[f32]> = (0..channels as usize)
+            .map(|c| std::slice::from_raw_parts(*data.add(c), frames as usize))
+            .collect();
+        samples.extend(downmix_planar(&planes));
+    }
+    Ok((samples, sample_rate.round() as u32))
+}
+
+unsafe fn ns_error_text(err: id) -> String {
+    let desc: id = if err == nil {
+        nil
+    } else {
+        msg_send![err, localizedDescription]
+    };
+    nsstring_to_rust(desc).unwrap_or_else(|| "未知错误".to_string())
+}
+
+#[link(name = "AVFoundation", kind = "framework")]
+extern "C" {}
+
+#[cfg(test)]
+mod audio_tests {
+    use super::*;
+
+    fn range(channels: u16, min_rate: u32, max_rate: u32, is_f32: bool) -> InputConfigRange {
+        InputConfigRange {
+            channels,
+            min_rate,
+            max_rate,
+            is_f32,
+            is_i16: !is_f32,
+            supported_format: true,
+        }
+    }
+
+    #[test]
+    fn prefers_mono_f32_config_covering_16k() {
+        let ranges = [
+            range(2, 8_000, 48_000, true),
+            range(1, 16_000, 16_000, false),
+            range(1, 8_000, 48_000, true),
+        ];
+        assert_eq!(pick_16k_input_range(&ranges, CaptureFormat::Auto), Some(2));
+    }
+
+    #[test]
+    fn falls_back_when_no_config_covers_16k() {
+        let mut unsupported = range(1, 8_000, 48_000, true);
+        unsupported.supported_format = false;
+        let ranges = [range(2, 44_100, 48_000, true), unsupported];
+        assert_eq!(pick_16k_input_range(&ranges, CaptureFormat::Auto), None);
+        assert_eq!(pick_format_input_range(&ranges, CaptureFormat::Auto), None);
+    }
+
+    #[test]
+    fn requested_format_narrows_the_16k_choice() {
+        let ranges = [
+            range(1, 8_000, 48_000, true),
+            range(2, 16_000, 16_000, false),
+        ];
+        assert_eq!(pick_16k_input_range(&ranges, CaptureFormat::F32), Some(0));
+        assert_eq!(pick_16k_input_range(&ranges, CaptureFormat::I16), Some(1));
+        assert_eq!(
+            pick_format_input_range(&ranges, CaptureFormat::I16),
+            Some((1, CAPTURE_TARGET_RATE))
+        );
+    }
+
+    #[test]
+    fn requested_format_without_16k_uses_its_highest_rate() {
+        let ranges = [
+            range(2, 44_100, 48_000, false),
+            range(1, 44_100, 44_100, false),
+            range(1, 8_000, 48_000, true),
+        ];
+        // i16 没有覆盖 16kHz 的配置：取声道最少的一项，之后重采样。
+        assert_eq!(
+            pick_format_input_range(&ranges, CaptureFormat::I16),
+            Some((1, 44_100))
+        );
+        let f32_only = [range(1, 44_100, 48_000, true)];
+        assert_eq!(pick_format_input_range(&f32_only, CaptureFormat::I16), None);
+        assert_eq!(
+            pick_format_input_range(&f32_only, CaptureFormat::F32),
+            Some((0, 48_000))
+        );
+    }
+
+    #[test]
+    fn capture_format_round_trips_through_index() {
+        for format in CaptureFormat::ALL {
+            assert_eq!(CaptureFormat::from_index(format.index()), format);
+        }
+        assert_eq!(CaptureFormat::from_token(" I16 "), Some(CaptureFormat::I16));
+        assert_eq!(CaptureFormat::from_token("u8"), None);
+    }
+
+    #[test]
+    fn downmix_picks_the_configured_channel() {
+        let frame = [0.2, 0.6];
+        assert_eq!(downmix_frame(&frame, MicChannel::Left), 0.2);
+        assert_eq!(downmix_frame(&frame, MicChannel::Right), 0.6);
+        assert!((downmix_frame(&frame, MicChannel::Mix) - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn right_channel_on_mono_frame_uses_the_only_channel() {
+        assert_eq!(downmix_frame(&[0.5], MicChannel::Right), 0.5);
+        assert_eq!(downmix_frame(&[], MicChannel::Mix), 0.0);
+    }
+
+    #[test]
+    fn append_mono_i16_downmixes_each_frame() {
+        let buf = Arc::new(Mutex::new(Vec::new()));
+        append_mono_i16(&buf, &[0, i16::MAX, 0, i16::MAX], 2, MicChannel::Right);
+        assert_eq!(*buf.lock().unwrap(), [1.0, 1.0]);
+    }
+
+    #[test]
+    fn preroll_is_prepended_before_the_recording() {
+        let mut recording = vec![3.0, 4.0];
+        prepend_preroll(&mut recording, &[1.0, 2.0]);
+        assert_eq!(recording, vec![1.0, 2.0, 3.0, 4.0]);
+        prepend_preroll(&mut recording, &[]);
+        assert_eq!(recording, vec![1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn preroll_ring_keeps_only_the_latest_samples() {
+        let mut buf: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        trim_to_last(&mut buf, 3);
+        assert_eq!(buf, vec![7.0, 8.0, 9.0]);
+        trim_to_last(&mut buf, 5);
+        assert_eq!(buf, vec![7.0, 8.0, 9.0]);
+    }
+
+    #[test]
+    fn preroll_capacity_follows_rate_and_is_capped() {
+        assert_eq!(preroll_capacity(16_000, 300), 4_800);
+        assert_eq!(preroll_capacity(48_000, 250), 12_000);
+        assert_eq!(preroll_capacity(16_000, 0), 0);
+        assert_eq!(
+            preroll_capacity(16_000, 60_000),
+            preroll_capacity(16_000, PREROLL_MAX_MS)
+        );
+    }
+
+    #[test]
+    fn planar_channels_are_averaged_to_mono() {
+        let left = [1.0, 0.5, 0.0];
+        let right = [0.0, 0.5, 1.0, 9.0];
+        assert_eq!(downmix_planar(&[&left, &right]), vec![0.5, 0.5, 0.5]);
+        assert_eq!(downmix_planar(&[&left]), left.to_vec());
+        assert!(downmix_planar(&[]).is_empty());
+    }
+
+    #[test]
+    fn decodes_m4a_through_avfoundation() {
+        // 用系统自带的 afconvert 现场生成一段 1 秒、44.1kHz 的 AAC。
+        let dir = std::env::temp_dir().join(format!("mofa-m4a-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let wav = dir.join("tone.wav");
+        let m4a = dir.join("tone.m4a");
+        let tone: Vec<f32> = (0..44_100)
+            .map(|i| 0.3 * (i as f32 * 440.0 * std::f32::consts::TAU / 44_100.0).sin())
+            .collect();
+        mofa_input::audio::write_wav(&wav, &tone, 44_100).unwrap();
+        let converted = Command::new("/usr/bin/afconvert")
+            .args(["-f", "m4af", "-d", "aac"])
+            .arg(&wav)
+            .arg(&m4a)
+            .status()
+            .is_ok_and(|s| s.success());
+        if !converted {
+            let _ = fs::remove_dir_all(&dir);
+            return;
+        }
+
+        let (samples, rate) = read_audio_file(&m4a).unwrap();
+        assert_eq!(rate, 44_100);
+        // AAC 编码会在首尾补帧，长度允许几帧的误差。
+        assert!(
+            samples.len().abs_diff(tone.len()) < 4_096,
+            "{}",
+            samples.len()
+        );
+        assert!(samples.iter().any(|s| s.abs() > 0.1));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn overlay_waits_for_the_show_delay() {
+        // 默认 0：按下即显示。
+        assert!(recording_overlay_due(0, 0));
+        // 短按在延迟前松开：不显示；长按超过延迟：显示。
+        assert!(!recording_overlay_due(120, 300));
+        assert!(recording_overlay_due(300, 300));
+        assert!(recording_overlay_due(1_500, 300));
+    }
+
+    #[test]
+    fn app_switch_stops_only_for_another_app() {
+        let own = Some("org.mofa.macos-ime");
+        let notes = Some("com.apple.Notes");
+        assert!(app_switch_should_stop(notes, Some("com.apple.Safari"), own));
+        assert!(!app_switch_should_stop(notes, notes, own));
+        // 点悬浮球或历史窗口会让输入法自己成为前台，不算切换。
+        assert!(!app_switch_should_stop(notes, own, own));
+        assert!(!app_switch_should_stop(notes, None, own));
+        // 录音开始时取不到前台应用：之后出现任何其它应用都算切换。
+        assert!(app_switch_should_stop(None, notes, own));
+    }
+}
@@ -0,0 +1,198 @@
+// 字幕模式（caption_mode）：连续听写的结果不注入，而是滚动显示在一条大号字幕条上，便于演示或共享屏幕。
+
+const CAPTION_MAX_WORDS: usize = 40;
+const CAPTION_HEIGHT: f64 = 120.0;
+const CAPTION_BOTTOM_MARGIN: f64 = 60.0;
+const CAPTION_WIDTH_RATIO: f64 = 0.8;
+const CAPTION_FONT_SIZE: f64 = 30.0;
+
+// 字幕条窗口与文字标签，首次显示时在主线程创建。
+static CAPTION_WINDOW: AtomicUsize = AtomicUsize::new(0);
+static CAPTION_LABEL: AtomicUsize = AtomicUsize::new(0);
+static CAPTION_VISIBLE: AtomicBool = AtomicBool::new(false);
+
+fn caption_transcript() -> &'static Mutex<String> {
+    static TRANSCRIPT: OnceLock<Mutex<String>> = OnceLock::new();
+    TRANSCRIPT.get_or_init(|| Mutex::new(String::new()))
+}
+
+/// 只保留最后 `max_words` 个词：英文按连续字母数字计词，中日韩文字每个字算一个词，标点不计；
+/// 截掉前文时以省略号开头。
+fn caption_tail(text: &str, max_words: usize) -> String {
+    let text = text.trim();
+    let mut words = 0usize;
+    let mut in_word = false;
+    let mut start = 0usize;
+    for (idx, ch) in text.char_indices().rev() {
+        let counts = if is_cjk_char(ch) {
+            in_word = false;
+            true
+        } else if ch.is_alphanumeric() {
+            !std::mem::replace(&mut in_word, true)
+        } else {
+            in_word = false;
+            false
+        };
+        if counts {
+            if words == max_words {
+                return format!("…{}", text[start..].trim_start());
+            }
+            words += 1;
+        }
+        start = idx;
+    }
+    text.to_string()
+}
+
+/// 把新的一句接到滚动字幕后面（英文之间补空格），再截到最后 `max_words` 个词。
+fn caption_append(existing: &str, next: &str, max_words: usize) -> String {
+    let existing = existing.trim_start_matches('…');
+    let joined = format!(
+        "{existing}{}{next}",
+        continuous_separator(existing.chars().last(), next)
+    );
+    caption_tail(&joined, max_words)
+}
+
+/// 追加一句并刷新字幕条。
+fn caption_push(text: &str) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    let shown = {
+        let mut transcript = caption_transcript().lock().unwrap();
+        *transcript = caption_append(&transcript, text, CAPTION_MAX_WORDS);
+        transcript.clone()
+    };
+    CAPTION_VISIBLE.store(true, Ordering::SeqCst);
+    Queue::main().exec_async(move || unsafe {
+        let (window, label) = match ensure_caption_window() {
+            Ok(pair) => pair,
+            Err(e) => {
+                eprintln!("[mofa-ime] 创建字幕条失败: {e}");
+                return;
+            }
+        };
+        let _: () = msg_send![label, setStringValue: ns_string(&shown)];
+        window.orderFrontRegardless();
+    });
+}
+
+/// 关闭字幕模式时收起字幕条并清空滚动文本。
+fn hide_caption() {
+    if !CAPTION_VISIBLE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    caption_transcript().lock().unwrap().clear();
+    Queue::main().exec_async(|| unsafe {
+        let window = CAPTION_WINDOW.load(Ordering::SeqCst) as id;
+        if window != nil {
+            window.orderOut_(nil);
+        }
+    });
+}
+
+unsafe fn ensure_caption_window() -> Result<(id, id)> {
+    let window = CAPTION_WINDOW.load(Ordering::SeqCst) as id;
+    let label = CAPTION_LABEL.load(Ordering::SeqCst) as id;
+    if window != nil && label != nil {
+        return Ok((window, label));
+    }
+
+    let frame = visible_frame();
+    let width = (frame.size.width * CAPTION_WIDTH_RATIO).floor();
+    let rect = NSRect::new(
+        NSPoint::new(
+            frame.origin.x + (frame.size.width - width) * 0.5,
+            frame.origin.y + CAPTION_BOTTOM_MARGIN,
+        ),
+        NSSize::new(width, CAPTION_HEIGHT),
+    );
+    let window = NSWindow::alloc(nil).initWithContentRect_styleMask_backing_defer_(
+        rect,
+        NSWindowStyleMask::NSBorderlessWindowMask,
+        NSBackingStoreBuffered,
+        NO,
+    );
+    if window == nil {
+        bail!("无法创建字幕窗口");
+    }
+    let clear_color: id = msg_send![class!(NSColor), clearColor];
+    window.setBackgroundColor_(clear_color);
+    window.setOpaque_(NO);
+    window.setHasShadow_(NO);
+    window.setHidesOnDeactivate_(NO);
+    window.setLevel_((NSMainMenuWindowLevel + 1) as i64);
+    window.setCollectionBehavior_(
+        NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
+            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorFullScreenAuxiliary,
+    );
+    let _: () = msg_send![window, setReleasedWhenClosed: NO];
+    // 可按住拖到不遮挡幻灯片内容的位置。
+    let _: () = msg_send![window, setMovableByWindowBackground: YES];
+
+    let content = window.contentView();
+    if content == nil {
+        bail!("字幕窗口 contentView 为空");
+    }
+    let _: () = msg_send![content, setWantsLayer: YES];
+    let layer: id = msg_send![content, layer];
+    if layer != nil {
+        let bg: id = msg_send![class!(NSColor), colorWithCalibratedWhite: 0.0f64 alpha: 0.78f64];
+        let bg_cg: id = msg_send![bg, CGColor];
+        let _: () = msg_send![layer, setCornerRadius: 14.0f64];
+        let _: () = msg_send![layer, setMasksToBounds: YES];
+        let _: () = msg_send![layer, setBackgroundColor: bg_cg];
+    }
+
+    let label = NSTextField::initWithFrame_(
+        NSTextField::alloc(nil),
+        NSRect::new(
+            NSPoint::new(24.0, 12.0),
+            NSSize::new(width - 48.0, CAPTION_HEIGHT - 24.0),
+        ),
+    );
+    let _: () = msg_send![label, setEditable: NO];
+    let _: () = msg_send![label, setSelectable: NO];
+    let _: () = msg_send![label, setBezeled: NO];
+    let _: () = msg_send![label, setBordered: NO];
+    let _: () = msg_send![label, setDrawsBackground: NO];
+    let font: id = msg_send![class!(NSFont), boldSystemFontOfSize: CAPTION_FONT_SIZE];
+    let _: () = msg_send![label, setFont: font];
+    let color: id = msg_send![class!(NSColor), whiteColor];
+    let _: () = msg_send![label, setTextColor: color];
+    // 按词换行、居中；超出两行时由 caption_tail 截掉前文。
+    let _: () = msg_send![label, setLineBreakMode: 0usize];
+    let _: () = msg_send![label, setAlignment: 1usize];
+    let _: () = msg_send![label, setMaximumNumberOfLines: 2isize];
+    content.addSubview_(label);
+
+    CAPTION_WINDOW.store(window as usize, Ordering::SeqCst);
+    CAPTION_LABEL.store(label as usize, Ordering::SeqCst);
+    Ok((window, label))
+}
+
+#[cfg(test)]
+mod caption_tests {
+    use super::*;
+
+    #[test]
+    fn caption_keeps_only_the_last_words() {
+        assert_eq!(caption_tail("one two three four", 2), "…three four");
+        assert_eq!(caption_tail("你好世界", 2), "…世界");
+        assert_eq!(caption_tail("我们用 Rust 写代码", 4), "…Rust 写代码");
+        // 标点不计词；不足上限时原样保留。
+        assert_eq!(caption_tail("  a, b, c.  ", 3), "a, b, c.");
+    }
+
+    #[test]
+    fn caption_append_rolls_the_transcript() {
+        assert_eq!(caption_append("", "Hello", 3), "Hello");
+        assert_eq!(
+            caption_append("…two three", "four five", 3),
+            "…three four five"
+        );
+        assert_eq!(caption_append("你好。", "再见", 10), "你好。再见");
+    }
+}
@@ -0,0 +1,300 @@
+// 命令行模式：批量转写等无界面任务。
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TranscriptFormat {
+    Txt,
+    Srt,
+    Vtt,
+}
+
+impl TranscriptFormat {
+    fn from_token(token: &str) -> Option<Self> {
+        match token.trim().to_ascii_lowercase().as_str() {
+            "txt" | "text" => Some(Self::Txt),
+            "srt" => Some(Self::Srt),
+            "vtt" | "webvtt" => Some(Self::Vtt),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Txt => "txt",
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+        }
+    }
+}
+
+enum CliCommand {
+    TranscribeDir {
+        dir: PathBuf,
+        out: Option<PathBuf>,
+        format: TranscriptFormat,
+    },
+    Diagnostics {
+        zip: bool,
+    },
+}
+
+/// 命令行模式认识的参数；一个都没有时按正常界面启动。
+const CLI_FLAGS: &[&str] = &[
+    "--transcribe-dir",
+    "--out",
+    "--format",
+    "--diagnostics",
+    "--zip",
+];
+
+/// 解析命令行参数。只有出现 `CLI_FLAGS` 中的参数才进入命令行模式，
+/// 否则返回 `None`，Finder/LaunchServices 附带的 `-psn_0_12345`、`-NSFoo value` 等参数原样忽略。
+fn parse_cli_command(args: &[String]) -> Result<Option<CliCommand>> {
+    if !args.iter().skip(1).any(|a| CLI_FLAGS.contains(&a.as_str())) {
+        return Ok(None);
+    }
+
+    let mut transcribe_dir: Option<PathBuf> = None;
+    let mut out: Option<PathBuf> = None;
+    let mut format = TranscriptFormat::Txt;
+    let mut diagnostics = false;
+    let mut zip = false;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--transcribe-dir" => {
+                let v = iter.next().context("--transcribe-dir 需要目录参数")?;
+                transcribe_dir = Some(PathBuf::from(v));
+            }
+            "--out" => {
+                let v = iter.next().context("--out 需要路径参数")?;
+                out = Some(PathBuf::from(v));
+            }
+            "--format" => {
+                let v = iter.next().context("--format 需要 txt/srt/vtt")?;
+                format = TranscriptFormat::from_token(v)
+                    .ok_or_else(|| anyhow!("不支持的输出格式: {v}"))?;
+            }
+            "--diagnostics" => diagnostics = true,
+            "--zip" => zip = true,
+            other => bail!("未知参数: {other}"),
+        }
+    }
+
+    if diagnostics {
+        if transcribe_dir.is_some() {
+            bail!("--diagnostics 不能与 --transcribe-dir 同时使用");
+        }
+        return Ok(Some(CliCommand::Diagnostics { zip }));
+    }
+    if zip {
+        bail!("--zip 需与 --diagnostics 一起使用");
+    }
+
+    match transcribe_dir {
+        Some(dir) => Ok(Some(CliCommand::TranscribeDir { dir, out, format })),
+        None if out.is_some() => bail!("--out 需与 --transcribe-dir 一起使用"),
+        None if format != TranscriptFormat::Txt => bail!("--format 需与 --transcribe-dir 一起使用"),
+        None => Ok(None),
+    }
+}
+
+fn run_cli_command(cmd: CliCommand) -> Result<()> {
+    match cmd {
+        CliCommand::TranscribeDir { dir, out, format } => {
+            transcribe_dir(&dir, out.as_deref(), format)
+        }
+        CliCommand::Diagnostics { zip } => run_diagnostics(zip),
+    }
+}
+
+fn is_cli_audio_file(path: &Path) -> bool {
+    is_decodable_audio_file(path)
+}
+
+fn transcribe_file(
+    asr: &mofa_input::asr::AsrSession,
+    path: &Path,
+    format: TranscriptFormat,
+) -> Result<String> {
+    let (samples, sample_rate) = read_audio_file(path)?;
+    let samples = resample_to_16k(&samples, sample_rate);
+    match format {
+        TranscriptFormat::Txt => Ok(normalize_transcript(&asr.transcribe(&samples)?)),
+        TranscriptFormat::Srt => Ok(mofa_input::asr::segments_to_srt(
+            &asr.transcribe_segments(&samples)?,
+        )),
+        TranscriptFormat::Vtt => Ok(mofa_input::asr::segments_to_vtt(
+            &asr.transcribe_segments(&samples)?,
+        )),
+    }
+}
+
+/// 转写结果的写入路径：只替换最后一个扩展名，`memo.2024.01.wav` 写成 `memo.2024.01.txt`。
+fn transcript_output_path(
+    audio: &Path,
+    out_dir: Option<&Path>,
+    format: TranscriptFormat,
+) -> PathBuf {
+    match out_dir {
+        Some(out_dir) => {
+            let mut name = audio.file_stem().unwrap_or_default().to_os_string();
+            name.push(".");
+            name.push(format.extension());
+            out_dir.join(name)
+        }
+        None => audio.with_extension(format.extension()),
+    }
+}
+
+/// 逐个转写目录下的音频（顺序处理，内存只保留当前文件）。
+/// `out` 为空时在音频旁写同名 `.txt`/`.srt`/`.vtt`；以 `.json` 结尾时汇总为一个 JSON；否则视为输出目录。
+/// 单个文件失败不中断，但只要有失败就返回错误。
+fn transcribe_dir(dir: &Path, out: Option<&Path>, format: TranscriptFormat) -> Result<()> {
+    let cfg = load_app_config();
+    let model_path = choose_asr_model(&model_base_dir(), cfg.asr_model, cfg.language)
+        .ok_or_else(|| anyhow!("未发现可用 ASR 模型，请先在 MoFA IME 设置中下载"))?;
+    let asr = mofa_input::asr::AsrSession::new(&model_path)?;
+
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("无法读取目录 {:?}", dir))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file())
+        .collect();
+    files.sort();
+
+    let json_out = out.filter(|p| {
+        p.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("json"))
+            .unwrap_or(false)
+    });
+    if let Some(out_dir) = out.filter(|_| json_out.is_none()) {
+        fs::create_dir_all(out_dir)?;
+    }
+
+    let mut results = Vec::new();
+    let mut failed = 0usize;
+    let mut total = 0usize;
+    for path in files {
+        if !is_cli_audio_file(&path) {
+            continue;
+        }
+        total += 1;
+        eprintln!("[mofa-ime] 转写 {:?}", path);
+        let text = match transcribe_file(&asr, &path, format) {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!("[mofa-ime] 转写失败 {:?}: {e}", path);
+                failed += 1;
+                continue;
+            }
+        };
+
+        if json_out.is_some() {
+            results.push(serde_json::json!({
+                "file": path.to_string_lossy(),
+                "text": text,
+            }));
+            continue;
+        }
+        let txt_path = transcript_output_path(&path, out, format);
+        let content = if format == TranscriptFormat::Txt {
+            format!("{text}\n")
+        } else {
+            text
+        };
+        fs::write(&txt_path, content).with_context(|| format!("写入失败 {:?}", txt_path))?;
+    }
+
+    if let Some(json_path) = json_out {
+        let content = serde_json::to_string_pretty(&results)?;
+        fs::write(json_path, content).with_context(|| format!("写入失败 {:?}", json_path))?;
+    }
+    // 其余文件照常写完，最后以非零状态退出，脚本能发现有文件没转出来。
+    if failed > 0 {
+        bail!("{failed}/{total} 个文件转写失败");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    #[test]
+    fn output_path_keeps_dots_in_the_stem() {
+        let audio = Path::new("/memos/memo.2024.01.wav");
+        assert_eq!(
+            transcript_output_path(audio, None, TranscriptFormat::Txt),
+            Path::new("/memos/memo.2024.01.txt")
+        );
+        assert_eq!(
+            transcript_output_path(audio, Some(Path::new("/out")), TranscriptFormat::Srt),
+            Path::new("/out/memo.2024.01.srt")
+        );
+    }
+
+    /// 需要已下载的 ASR 模型；未安装时跳过。
+    #[test]
+    fn transcribes_each_wav_in_a_directory() {
+        let cfg = load_app_config();
+        if choose_asr_model(&model_base_dir(), cfg.asr_model, cfg.language).is_none() {
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("mofa-cli-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let tone: Vec<f32> = (0..16_000)
+            .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / 16_000.0).sin() * 0.1)
+            .collect();
+        for name in ["a.wav", "b.2024.wav"] {
+            mofa_input::audio::write_wav(&dir.join(name), &tone, 16_000).unwrap();
+        }
+
+        transcribe_dir(&dir, None, TranscriptFormat::Txt).unwrap();
+
+        assert!(dir.join("a.txt").exists());
+        assert!(dir.join("b.2024.txt").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn launcher_arguments_start_the_app() {
+        let launched = args(&[
+            "mofa-macos-ime",
+            "-psn_0_12345",
+            "-NSDocumentRevisionsDebugMode",
+            "YES",
+        ]);
+        assert!(parse_cli_command(&launched).unwrap().is_none());
+        assert!(parse_cli_command(&args(&["mofa-macos-ime"]))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn cli_flags_enter_cli_mode_and_reject_unknown_arguments() {
+        let cmd = parse_cli_command(&args(&[
+            "mofa-macos-ime",
+            "--transcribe-dir",
+            "/memos",
+            "--format",
+            "srt",
+        ]))
+        .unwrap();
+        assert!(matches!(
+            cmd,
+            Some(CliCommand::TranscribeDir {
+                format: TranscriptFormat::Srt,
+                out: None,
+                ..
+            })
+        ));
+        assert!(parse_cli_command(&args(&["mofa-macos-ime", "--diagnostics", "--bogus"])).is_err());
+        assert!(parse_cli_command(&args(&["mofa-macos-ime", "--zip"])).is_err());
+    }
+}
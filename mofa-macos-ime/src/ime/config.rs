@@ -1,4 +1,39 @@
 static HOTKEY_STORE: OnceLock<Arc<std::sync::atomic::AtomicUsize>> = OnceLock::new();
+// Fn 按住超过该阈值才开始录音，短按不触发录音；0 表示按下即录音。
+static FN_HOLD_THRESHOLD_MS: AtomicU64 = AtomicU64::new(0);
+// 键盘事件输入速度（字/秒），0 为不限速；由配置监视线程更新。
+static TYPE_SPEED_CPS: AtomicU64 = AtomicU64::new(0);
+// 键盘事件注入改用私有事件源并带来源标记，尽量绕开系统文本替换；由配置监视线程更新。
+static BYPASS_TEXT_REPLACEMENT: AtomicBool = AtomicBool::new(false);
+// AX 插入失败后的额外重试次数，0 为只试一次；由配置监视线程更新。
+static AX_RETRIES: AtomicU64 = AtomicU64::new(0);
+// 注入前焦点不在输入框时等待多久再复查一次（毫秒），0 为不等待；由配置监视线程更新。
+static INJECT_FOCUS_SETTLE_MS: AtomicU64 = AtomicU64::new(50);
+// 粘贴注入时剪贴板里是图片的处理方式（ClipboardImagePolicy::index），由配置监视线程更新。
+static CLIPBOARD_IMAGE_POLICY: AtomicU64 = AtomicU64::new(0);
+// 剪贴板历史中图片的总字节上限，超出时从最旧的图片开始移除；由配置监视线程更新。
+static CLIPBOARD_IMAGE_MAX_BYTES: AtomicU64 = AtomicU64::new(64 * 1024 * 1024);
+// 热键录音方式（HotkeyMode::index），由配置监视线程更新。
+static HOTKEY_MODE: AtomicU64 = AtomicU64::new(0);
+// 录音采样格式偏好（CaptureFormat::index），由配置监视线程更新。
+static CAPTURE_FORMAT: AtomicU64 = AtomicU64::new(0);
+// 隐私模式：不记历史/剪贴板、不显示或记录转写内容；由配置监视线程更新。
+static PRIVACY_MODE: AtomicBool = AtomicBool::new(false);
+// 悬浮窗固定位置（pack_overlay_origin），OVERLAY_ORIGIN_UNSET 表示按焦点动态摆放；由配置监视线程更新。
+static OVERLAY_FIXED_ORIGIN: AtomicU64 = AtomicU64::new(OVERLAY_ORIGIN_UNSET);
+const OVERLAY_ORIGIN_UNSET: u64 = u64::MAX;
+// 切换模型热键（HotkeySpec::pack），HOTKEY_UNSET 表示未配置；由配置监视线程更新。
+static CYCLE_LLM_HOTKEY: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(HOTKEY_UNSET);
+static CYCLE_ASR_HOTKEY: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(HOTKEY_UNSET);
+// 改写上一次输入的热键，同样由配置监视线程更新。
+static REPOLISH_HOTKEY: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(HOTKEY_UNSET);
+// 转写剪贴板中音频文件的热键。
+static CLIPBOARD_TRANSCRIBE_HOTKEY: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(HOTKEY_UNSET);
+const HOTKEY_UNSET: usize = usize::MAX;
 const HOTKEY_FN_CODE: u16 = u16::MAX;
 const HOTKEY_MOD_CMD: u8 = 1 << 0;
 const HOTKEY_MOD_CTRL: u8 = 1 << 1;
@@ -82,13 +117,18 @@ impl HotkeySpec {
 enum OutputMode {
     Llm,
     Asr,
+    /// ASR 原文后附 LLM 译文：`原文 (translation)`
+    Bilingual,
 }
 
 impl OutputMode {
+    const ALL: [OutputMode; 3] = [OutputMode::Llm, OutputMode::Asr, OutputMode::Bilingual];
+
     fn from_token(s: &str) -> Option<Self> {
         match s.trim().to_ascii_lowercase().as_str() {
             "llm" => Some(Self::Llm),
             "asr" => Some(Self::Asr),
+            "bilingual" => Some(Self::Bilingual),
             _ => None,
         }
     }
@@ -97,6 +137,7 @@ impl OutputMode {
         match self {
             Self::Llm => "llm",
             Self::Asr => "asr",
+            Self::Bilingual => "bilingual",
         }
     }
 
@@ -104,10 +145,204 @@ impl OutputMode {
         match self {
             Self::Llm => "LLM 润色",
             Self::Asr => "ASR 原文",
+            Self::Bilingual => "原文 + 译文",
+        }
+    }
+}
+
+/// 强制 Whisper 识别语言；auto 为自动检测（支持中英混说）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AsrLanguage {
+    Auto,
+    Zh,
+    En,
+}
+
+impl AsrLanguage {
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "zh" => Some(Self::Zh),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    fn token(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Zh => "zh",
+            Self::En => "en",
+        }
+    }
+
+    /// 传给 Whisper 的语言代码。
+    fn code(self) -> Option<&'static str> {
+        match self {
+            Self::Auto => None,
+            Self::Zh => Some("zh"),
+            Self::En => Some("en"),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "自动",
+            Self::Zh => "中文",
+            Self::En => "英文",
+        }
+    }
+
+    /// 托盘切换顺序：中文 → 英文 → 自动 → 中文。
+    fn next(self) -> Self {
+        match self {
+            Self::Zh => Self::En,
+            Self::En => Self::Auto,
+            Self::Auto => Self::Zh,
+        }
+    }
+}
+
+/// 结果去向：注入到前台应用，或追加写入文件（日记等场景）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputSink {
+    Inject,
+    File,
+}
+
+impl OutputSink {
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "inject" => Some(Self::Inject),
+            "file" => Some(Self::File),
+            _ => None,
         }
     }
 }
 
+/// 注入方式：auto 按内置顺序尝试，其余固定只用一种（如只粘贴，避免逐字输入触发对方“正在输入”）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InjectStrategy {
+    Auto,
+    Ax,
+    Paste,
+    Type,
+}
+
+impl InjectStrategy {
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "ax" => Some(Self::Ax),
+            "paste" => Some(Self::Paste),
+            "type" => Some(Self::Type),
+            _ => None,
+        }
+    }
+}
+
+/// 最终结果的展示方式：悬浮窗、系统通知，或两者都用。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ResultDisplay {
+    Overlay,
+    Notification,
+    Both,
+}
+
+impl ResultDisplay {
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "overlay" => Some(Self::Overlay),
+            "notification" => Some(Self::Notification),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+
+    fn uses_overlay(self) -> bool {
+        self != Self::Notification
+    }
+
+    fn uses_notification(self) -> bool {
+        self != Self::Overlay
+    }
+}
+
+/// 粘贴注入会占用剪贴板：剪贴板里原本是图片时，粘贴后恢复、改用键盘输入，或直接覆盖。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ClipboardImagePolicy {
+    Restore,
+    Type,
+    Overwrite,
+}
+
+impl ClipboardImagePolicy {
+    const ALL: [ClipboardImagePolicy; 3] = [
+        ClipboardImagePolicy::Restore,
+        ClipboardImagePolicy::Type,
+        ClipboardImagePolicy::Overwrite,
+    ];
+
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "restore" => Some(Self::Restore),
+            "type" => Some(Self::Type),
+            "overwrite" => Some(Self::Overwrite),
+            _ => None,
+        }
+    }
+
+    fn index(self) -> u64 {
+        Self::ALL.iter().position(|p| *p == self).unwrap_or(0) as u64
+    }
+
+    fn from_index(idx: u64) -> Self {
+        Self::ALL
+            .get(idx as usize)
+            .copied()
+            .unwrap_or(ClipboardImagePolicy::Restore)
+    }
+}
+
+/// 热键录音方式：按住说话；按一下开始、再按一下结束；或按住说话、按住超过阈值后松开也继续录音。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HotkeyMode {
+    Hold,
+    Toggle,
+    Hybrid,
+}
+
+impl HotkeyMode {
+    const ALL: [HotkeyMode; 3] = [HotkeyMode::Hold, HotkeyMode::Toggle, HotkeyMode::Hybrid];
+
+    fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "hold" => Some(Self::Hold),
+            "toggle" => Some(Self::Toggle),
+            "hybrid" => Some(Self::Hybrid),
+            _ => None,
+        }
+    }
+
+    fn index(self) -> u64 {
+        Self::ALL.iter().position(|m| *m == self).unwrap_or(0) as u64
+    }
+
+    fn from_index(idx: u64) -> Self {
+        Self::ALL
+            .get(idx as usize)
+            .copied()
+            .unwrap_or(HotkeyMode::Hold)
+    }
+}
+
+fn hotkey_mode() -> HotkeyMode {
+    HotkeyMode::from_index(HOTKEY_MODE.load(Ordering::SeqCst))
+}
+
+/// `output_file=` 未设置时的默认路径模板，`{date}` 替换为本地日期。
+const DEFAULT_OUTPUT_FILE: &str = "~/.mofa/journal/{date}.md";
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum LlmModelChoice {
     Auto,
@@ -132,6 +367,28 @@ enum LlmModelChoice {
 }
 
 impl LlmModelChoice {
+    const ALL: [LlmModelChoice; 19] = [
+        LlmModelChoice::Auto,
+        LlmModelChoice::Qwen05,
+        LlmModelChoice::Qwen15,
+        LlmModelChoice::Qwen3,
+        LlmModelChoice::Qwen4,
+        LlmModelChoice::Qwen7,
+        LlmModelChoice::Qwen8,
+        LlmModelChoice::Qwen14,
+        LlmModelChoice::Qwen14Q3,
+        LlmModelChoice::Qwen30A3B,
+        LlmModelChoice::Qwen32,
+        LlmModelChoice::Qwen32Q3,
+        LlmModelChoice::Qwen72,
+        LlmModelChoice::QwenCoder05,
+        LlmModelChoice::QwenCoder15,
+        LlmModelChoice::QwenCoder3,
+        LlmModelChoice::QwenCoder7,
+        LlmModelChoice::QwenCoder14,
+        LlmModelChoice::QwenCoder32,
+    ];
+
     fn from_token(s: &str) -> Option<Self> {
         match s.trim().to_ascii_lowercase().as_str() {
             "auto" => Some(Self::Auto),
@@ -237,9 +494,26 @@ enum AsrModelChoice {
     Base,
     Small,
     Medium,
+    /// whisper.cpp 的纯英文（`.en`）模型
+    TinyEn,
+    BaseEn,
+    SmallEn,
+    MediumEn,
 }
 
 impl AsrModelChoice {
+    const ALL: [AsrModelChoice; 9] = [
+        AsrModelChoice::Auto,
+        AsrModelChoice::Tiny,
+        AsrModelChoice::Base,
+        AsrModelChoice::Small,
+        AsrModelChoice::Medium,
+        AsrModelChoice::TinyEn,
+        AsrModelChoice::BaseEn,
+        AsrModelChoice::SmallEn,
+        AsrModelChoice::MediumEn,
+    ];
+
     fn from_token(s: &str) -> Option<Self> {
         match s.trim().to_ascii_lowercase().as_str() {
             "auto" => Some(Self::Auto),
@@ -247,6 +521,10 @@ impl AsrModelChoice {
             "ggml-base.bin" | "base" => Some(Self::Base),
             "ggml-small.bin" | "small" => Some(Self::Small),
             "ggml-medium.bin" | "medium" => Some(Self::Medium),
+            "ggml-tiny.en.bin" | "tiny.en" => Some(Self::TinyEn),
+            "ggml-base.en.bin" | "base.en" => Some(Self::BaseEn),
+            "ggml-small.en.bin" | "small.en" => Some(Self::SmallEn),
+            "ggml-medium.en.bin" | "medium.en" => Some(Self::MediumEn),
             _ => None,
         }
     }
@@ -258,6 +536,10 @@ impl AsrModelChoice {
             Self::Base => "ggml-base.bin",
             Self::Small => "ggml-small.bin",
             Self::Medium => "ggml-medium.bin",
+            Self::TinyEn => "ggml-tiny.en.bin",
+            Self::BaseEn => "ggml-base.en.bin",
+            Self::SmallEn => "ggml-small.en.bin",
+            Self::MediumEn => "ggml-medium.en.bin",
         }
     }
 
@@ -268,6 +550,10 @@ impl AsrModelChoice {
             Self::Base => Some("ggml-base.bin"),
             Self::Small => Some("ggml-small.bin"),
             Self::Medium => Some("ggml-medium.bin"),
+            Self::TinyEn => Some("ggml-tiny.en.bin"),
+            Self::BaseEn => Some("ggml-base.en.bin"),
+            Self::SmallEn => Some("ggml-small.en.bin"),
+            Self::MediumEn => Some("ggml-medium.en.bin"),
         }
     }
 
@@ -278,27 +564,294 @@ impl AsrModelChoice {
             Self::Base => "Whisper Base",
             Self::Small => "Whisper Small",
             Self::Medium => "Whisper Medium",
+            Self::TinyEn => "Whisper Tiny（英文）",
+            Self::BaseEn => "Whisper Base（英文）",
+            Self::SmallEn => "Whisper Small（英文）",
+            Self::MediumEn => "Whisper Medium（英文）",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MicChannel {
+    Mix,
+    Left,
+    Right,
+}
+
+impl MicChannel {
+    fn from_token(token: &str) -> Option<Self> {
+        match token.trim().to_ascii_lowercase().as_str() {
+            "mix" => Some(Self::Mix),
+            "left" | "l" => Some(Self::Left),
+            "right" | "r" => Some(Self::Right),
+            _ => None,
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+/// 向麦克风请求的采样格式：auto 沿用设备默认（16kHz 时优先 f32），其余只选该格式的配置。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CaptureFormat {
+    Auto,
+    F32,
+    I16,
+}
+
+impl CaptureFormat {
+    const ALL: [CaptureFormat; 3] = [CaptureFormat::Auto, CaptureFormat::F32, CaptureFormat::I16];
+
+    fn from_token(token: &str) -> Option<Self> {
+        match token.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "f32" => Some(Self::F32),
+            "i16" => Some(Self::I16),
+            _ => None,
+        }
+    }
+
+    fn index(self) -> u64 {
+        Self::ALL.iter().position(|f| *f == self).unwrap_or(0) as u64
+    }
+
+    fn from_index(idx: u64) -> Self {
+        Self::ALL
+            .get(idx as usize)
+            .copied()
+            .unwrap_or(CaptureFormat::Auto)
+    }
+}
+
+/// 按前台应用（bundle id）覆盖的配置，写法：`app.<bundle_id>.<key>=value`。
+#[derive(Clone, Debug, Default)]
+struct AppProfile {
+    bundle_id: String,
+    auto_send: Option<bool>,
+    inject_strategy: Option<InjectStrategy>,
+}
+
+fn parse_app_profile_line(profiles: &mut Vec<AppProfile>, rest: &str) {
+    let Some((lhs, value)) = rest.split_once('=') else {
+        return;
+    };
+    let Some((bundle_id, key)) = lhs.trim().rsplit_once('.') else {
+        return;
+    };
+    if bundle_id.is_empty() {
+        return;
+    }
+    let idx = match profiles.iter().position(|p| p.bundle_id == bundle_id) {
+        Some(idx) => idx,
+        None => {
+            profiles.push(AppProfile {
+                bundle_id: bundle_id.to_string(),
+                ..AppProfile::default()
+            });
+            profiles.len() - 1
+        }
+    };
+    let profile = &mut profiles[idx];
+    let value = value.trim().to_ascii_lowercase();
+    match key {
+        "auto_send" => profile.auto_send = Some(value == "true"),
+        "inject_strategy" => {
+            if let Some(strategy) = InjectStrategy::from_token(&value) {
+                profile.inject_strategy = Some(strategy);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Clone, Debug)]
 struct AppConfig {
     hotkey: HotkeySpec,
+    hotkey_mode: HotkeyMode,
     output_mode: OutputMode,
     llm_model: LlmModelChoice,
     asr_model: AsrModelChoice,
     show_floating_orb: bool,
+    fn_hold_ms: u64,
+    trigger_pipe: Option<PathBuf>,
+    auto_send: bool,
+    app_profiles: Vec<AppProfile>,
+    confirm_before_inject: bool,
+    coding_mode: bool,
+    llm_fallback: bool,
+    warmup: bool,
+    strict_filter: bool,
+    mic_channel: MicChannel,
+    capture_format: CaptureFormat,
+    denoise: bool,
+    /// 转写前把句间过长的停顿压缩到 max_internal_silence_ms。
+    compress_silence: bool,
+    max_internal_silence_ms: u32,
+    error_beep: bool,
+    incremental_inject: bool,
+    /// 连续听写：每次停顿自动转写并注入这一句，录音不中断。
+    continuous: bool,
+    /// 连续听写中静音持续这么久才算一句说完，短于它的自然停顿不切句。
+    silence_hang_ms: u64,
+    /// 字幕模式：连续听写结果显示在大号字幕条上，不注入。
+    caption_mode: bool,
+    show_overlay: bool,
+    record_cooldown_ms: u64,
+    overlay_show_delay_ms: u64,
+    /// 录音期间切换到其它应用时自动结束录音。
+    stop_on_app_switch: bool,
+    /// 悬浮窗左上角的固定屏幕坐标（主显示器左上角为原点），两者都设置才生效。
+    overlay_fixed_x: Option<i16>,
+    overlay_fixed_y: Option<i16>,
+    overlay_interactive: bool,
+    overlay_over_fullscreen: bool,
+    llm_role_models: Vec<(LlmRole, LlmModelChoice)>,
+    llm_max_output_chars: usize,
+    debug_drops: bool,
+    asr_length_adaptive: bool,
+    type_speed_cps: u64,
+    /// 键盘事件注入时尽量绕开系统文本替换/自动更正（粘贴注入不受影响）。
+    disable_text_replacement_during_inject: bool,
+    release_grace_ms: u64,
+    output_sink: OutputSink,
+    output_file: String,
+    cycle_llm_hotkey: Option<HotkeySpec>,
+    cycle_asr_hotkey: Option<HotkeySpec>,
+    repolish_hotkey: Option<HotkeySpec>,
+    clipboard_transcribe_hotkey: Option<HotkeySpec>,
+    repolish_window_ms: u64,
+    inject_strategy: InjectStrategy,
+    result_display: ResultDisplay,
+    privacy_mode: bool,
+    history_audio: bool,
+    light_polish: bool,
+    strip_markdown: bool,
+    /// 中文与英文/数字相接处补空格（“使用 Rust 开发”）。
+    auto_space_cjk: bool,
+    orb_auto_hide_ms: u64,
+    inject_target_bundle: Option<String>,
+    inject_target_restore_focus: bool,
+    overlay_style: OverlayStyle,
+    ax_retries: u64,
+    inject_focus_settle_ms: u64,
+    inject_prefix: String,
+    /// 悬浮窗空闲时的预览提示；为空时按识别语言用默认文案。
+    preview_placeholder: Option<String>,
+    show_pinyin: bool,
+    /// language=auto 时在悬浮窗状态行显示 Whisper 判定的语言（“识别 [zh]”）。
+    show_detected_language: bool,
+    llm_lazy: bool,
+    preroll_ms: u64,
+    clipboard_image: ClipboardImagePolicy,
+    clipboard_image_max_mb: u64,
+    language: AsrLanguage,
+    llm_min_words: usize,
+    memory_budget_gb: Option<u64>,
+}
+
+impl AppConfig {
+    fn profile_for(&self, bundle_id: Option<&str>) -> Option<&AppProfile> {
+        let bundle_id = bundle_id?;
+        self.app_profiles.iter().find(|p| p.bundle_id == bundle_id)
+    }
+
+    /// 前台应用的注入方式：应用配置优先，否则用全局 `inject_strategy`。
+    fn inject_strategy_for(&self, bundle_id: Option<&str>) -> InjectStrategy {
+        self.profile_for(bundle_id)
+            .and_then(|p| p.inject_strategy)
+            .unwrap_or(self.inject_strategy)
+    }
+
+    fn llm_model_for(&self, role: LlmRole) -> Option<LlmModelChoice> {
+        self.llm_role_models
+            .iter()
+            .find(|(r, _)| *r == role)
+            .map(|(_, choice)| *choice)
+    }
+
+    /// 本次结果是否要经过 LLM（润色、双语翻译或 ASR 模式下的轻量整理）。
+    fn uses_llm(&self) -> bool {
+        self.output_mode != OutputMode::Asr || self.light_polish
+    }
+
+    fn overlay_fixed_origin(&self) -> Option<(i16, i16)> {
+        Some((self.overlay_fixed_x?, self.overlay_fixed_y?))
+    }
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             hotkey: HotkeySpec::fn_key(),
+            hotkey_mode: HotkeyMode::Hold,
             output_mode: OutputMode::Llm,
             llm_model: LlmModelChoice::Auto,
             asr_model: AsrModelChoice::Auto,
             show_floating_orb: true,
+            fn_hold_ms: 0,
+            trigger_pipe: None,
+            auto_send: false,
+            app_profiles: Vec::new(),
+            confirm_before_inject: false,
+            coding_mode: false,
+            llm_fallback: true,
+            warmup: false,
+            strict_filter: false,
+            mic_channel: MicChannel::Mix,
+            capture_format: CaptureFormat::Auto,
+            denoise: false,
+            compress_silence: false,
+            max_internal_silence_ms: 800,
+            error_beep: false,
+            incremental_inject: false,
+            continuous: false,
+            silence_hang_ms: 600,
+            caption_mode: false,
+            show_overlay: true,
+            record_cooldown_ms: 150,
+            overlay_show_delay_ms: 0,
+            stop_on_app_switch: false,
+            overlay_fixed_x: None,
+            overlay_fixed_y: None,
+            overlay_interactive: false,
+            overlay_over_fullscreen: false,
+            llm_role_models: Vec::new(),
+            llm_max_output_chars: 0,
+            debug_drops: false,
+            asr_length_adaptive: false,
+            type_speed_cps: 0,
+            disable_text_replacement_during_inject: false,
+            release_grace_ms: 0,
+            output_sink: OutputSink::Inject,
+            output_file: DEFAULT_OUTPUT_FILE.to_string(),
+            cycle_llm_hotkey: None,
+            cycle_asr_hotkey: None,
+            repolish_hotkey: None,
+            clipboard_transcribe_hotkey: None,
+            repolish_window_ms: 15_000,
+            inject_strategy: InjectStrategy::Auto,
+            result_display: ResultDisplay::Overlay,
+            privacy_mode: false,
+            history_audio: false,
+            light_polish: false,
+            strip_markdown: false,
+            auto_space_cjk: false,
+            orb_auto_hide_ms: 0,
+            inject_target_bundle: None,
+            inject_target_restore_focus: true,
+            overlay_style: OverlayStyle::default(),
+            ax_retries: 0,
+            inject_focus_settle_ms: 50,
+            inject_prefix: String::new(),
+            preview_placeholder: None,
+            show_pinyin: false,
+            show_detected_language: false,
+            llm_lazy: false,
+            preroll_ms: 0,
+            clipboard_image: ClipboardImagePolicy::Restore,
+            clipboard_image_max_mb: 64,
+            language: AsrLanguage::Auto,
+            llm_min_words: 0,
+            memory_budget_gb: None,
         }
     }
 }
@@ -376,12 +929,66 @@ fn hotkey_config_path() -> PathBuf {
         .unwrap_or_else(|| PathBuf::from("./mofa-macos-ime.conf"))
 }
 
+fn symbol_map_path() -> PathBuf {
+    dirs::home_dir()
+        .map(|h| h.join(".mofa/symbol-map.txt"))
+        .unwrap_or_else(|| PathBuf::from("./symbol-map.txt"))
+}
+
+fn expansions_path() -> PathBuf {
+    dirs::home_dir()
+        .map(|h| h.join(".mofa/expansions.txt"))
+        .unwrap_or_else(|| PathBuf::from("./expansions.txt"))
+}
+
+fn snippets_path() -> PathBuf {
+    dirs::home_dir()
+        .map(|h| h.join(".mofa/snippets.txt"))
+        .unwrap_or_else(|| PathBuf::from("./snippets.txt"))
+}
+
+/// 用户的缩写展开表 `~/.mofa/expansions.txt`（每行 `短语 => 展开内容`），没有文件时为空。
+fn load_expansions() -> mofa_input::text::ExpansionMap {
+    fs::read_to_string(expansions_path())
+        .map(|content| mofa_input::text::ExpansionMap::parse(&content))
+        .unwrap_or_default()
+}
+
+/// 内置口述符号表，叠加用户的 `~/.mofa/symbol-map.txt`（每行 `短语=符号`）。
+fn load_symbol_map() -> mofa_input::text::SymbolMap {
+    let mut map = mofa_input::text::SymbolMap::builtin();
+    if let Ok(content) = fs::read_to_string(symbol_map_path()) {
+        map.extend(&mofa_input::text::SymbolMap::parse(&content));
+    }
+    map
+}
+
+/// 解析配置中的路径值，支持 `~/` 开头；空值视为未设置。
+fn expand_config_path(value: &str) -> Option<PathBuf> {
+    let v = value.trim();
+    if v.is_empty() {
+        return None;
+    }
+    if let Some(rest) = v.strip_prefix("~/") {
+        return dirs::home_dir().map(|h| h.join(rest));
+    }
+    Some(PathBuf::from(v))
+}
+
 fn load_app_config() -> AppConfig {
     let path = hotkey_config_path();
     let Ok(content) = fs::read_to_string(path) else {
         return AppConfig::default();
     };
+    parse_app_config(&content)
+}
 
+/// 解析配置文件内容：旧版本键名先迁移，未知键与非法值忽略（保留默认）。
+fn parse_app_config(content: &str) -> AppConfig {
+    if config_file_version(content) > CONFIG_VERSION {
+        eprintln!("[mofa-ime] 配置文件版本高于当前程序，未识别的键将被忽略");
+    }
+    let content = migrate_config(content);
     let mut cfg = AppConfig::default();
     for line in content.lines() {
         let line = line.trim();
@@ -392,29 +999,274 @@ fn load_app_config() -> AppConfig {
             if let Some(spec) = HotkeySpec::parse(v) {
                 cfg.hotkey = spec;
             }
+        } else if let Some(v) = line.strip_prefix("hotkey_mode=") {
+            if let Some(mode) = HotkeyMode::from_token(v) {
+                cfg.hotkey_mode = mode;
+            }
+        } else if let Some(v) = line.strip_prefix("cycle_llm_hotkey=") {
+            // 切换热键只支持组合键，Fn 留给录音。
+            cfg.cycle_llm_hotkey = HotkeySpec::parse(v).filter(|spec| !spec.is_fn());
+        } else if let Some(v) = line.strip_prefix("cycle_asr_hotkey=") {
+            cfg.cycle_asr_hotkey = HotkeySpec::parse(v).filter(|spec| !spec.is_fn());
+        } else if let Some(v) = line.strip_prefix("repolish_hotkey=") {
+            cfg.repolish_hotkey = HotkeySpec::parse(v).filter(|spec| !spec.is_fn());
+        } else if let Some(v) = line.strip_prefix("clipboard_transcribe_hotkey=") {
+            cfg.clipboard_transcribe_hotkey = HotkeySpec::parse(v).filter(|spec| !spec.is_fn());
+        } else if let Some(v) = line.strip_prefix("repolish_window_ms=") {
+            if let Ok(ms) = v.trim().parse::<u64>() {
+                cfg.repolish_window_ms = ms.min(120_000);
+            }
+        } else if let Some(v) = line.strip_prefix("language=") {
+            if let Some(language) = AsrLanguage::from_token(v) {
+                cfg.language = language;
+            }
         } else if let Some(v) = line.strip_prefix("output_mode=") {
             if let Some(mode) = OutputMode::from_token(v) {
                 cfg.output_mode = mode;
             }
+        } else if let Some(v) = line.strip_prefix("inject_prefix=") {
+            // 行首尾空白已去掉；需要以空格结尾时用双引号包住，如 `"[%H:%M] "`。
+            let v = v.trim();
+            let v = v
+                .strip_prefix('"')
+                .and_then(|s| s.strip_suffix('"'))
+                .unwrap_or(v);
+            cfg.inject_prefix = v.to_string();
+        } else if let Some(v) = line.strip_prefix("preview_placeholder=") {
+            let v = v.trim();
+            let v = v
+                .strip_prefix('"')
+                .and_then(|s| s.strip_suffix('"'))
+                .unwrap_or(v);
+            cfg.preview_placeholder = (!v.trim().is_empty()).then(|| v.to_string());
+        } else if let Some(v) = line.strip_prefix("ax_retries=") {
+            if let Ok(n) = v.trim().parse::<u64>() {
+                cfg.ax_retries = n.min(5);
+            }
+        } else if let Some(v) = line.strip_prefix("inject_focus_settle_ms=") {
+            if let Ok(n) = v.trim().parse::<u64>() {
+                cfg.inject_focus_settle_ms = n.min(1000);
+            }
+        } else if let Some(v) = line.strip_prefix("clipboard_image=") {
+            if let Some(policy) = ClipboardImagePolicy::from_token(v) {
+                cfg.clipboard_image = policy;
+            }
+        } else if let Some(v) = line.strip_prefix("clipboard_image_max_mb=") {
+            if let Ok(n) = v.trim().parse::<u64>() {
+                cfg.clipboard_image_max_mb = n.clamp(1, 1024);
+            }
+        } else if let Some(v) = line.strip_prefix("inject_strategy=") {
+            if let Some(strategy) = InjectStrategy::from_token(v) {
+                cfg.inject_strategy = strategy;
+            }
+        } else if let Some(v) = line.strip_prefix("history_audio=") {
+            cfg.history_audio = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("privacy_mode=") {
+            cfg.privacy_mode = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("result_display=") {
+            if let Some(display) = ResultDisplay::from_token(v) {
+                cfg.result_display = display;
+            }
+        } else if let Some(v) = line.strip_prefix("inject_target_bundle=") {
+            let v = v.trim();
+            cfg.inject_target_bundle = (!v.is_empty()).then(|| v.to_string());
+        } else if let Some(v) = line.strip_prefix("inject_target_restore_focus=") {
+            cfg.inject_target_restore_focus =
+                !matches!(v.trim().to_ascii_lowercase().as_str(), "off" | "false");
+        } else if let Some(v) = line.strip_prefix("output_sink=") {
+            if let Some(sink) = OutputSink::from_token(v) {
+                cfg.output_sink = sink;
+            }
+        } else if let Some(v) = line.strip_prefix("output_file=") {
+            if !v.trim().is_empty() {
+                cfg.output_file = v.trim().to_string();
+            }
+        } else if let Some(v) = line.strip_prefix("show_pinyin=") {
+            cfg.show_pinyin = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("show_detected_language=") {
+            cfg.show_detected_language = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("llm_lazy=") {
+            cfg.llm_lazy = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("strip_markdown=") {
+            cfg.strip_markdown = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("auto_space_cjk=") {
+            cfg.auto_space_cjk = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("light_polish=") {
+            cfg.light_polish = v.trim().to_ascii_lowercase() == "true";
         } else if let Some(v) = line.strip_prefix("llm_model=") {
             if let Some(choice) = LlmModelChoice::from_token(v) {
                 cfg.llm_model = choice;
             }
+        } else if let Some(v) = line.strip_prefix("llm_polish_model=") {
+            if let Some(choice) = LlmModelChoice::from_token(v) {
+                cfg.llm_role_models.push((LlmRole::Polish, choice));
+            }
+        } else if let Some(v) = line.strip_prefix("llm_translate_model=") {
+            if let Some(choice) = LlmModelChoice::from_token(v) {
+                cfg.llm_role_models.push((LlmRole::Translate, choice));
+            }
+        } else if let Some(v) = line.strip_prefix("llm_max_output_chars=") {
+            if let Ok(n) = v.trim().parse::<usize>() {
+                cfg.llm_max_output_chars = n;
+            }
+        } else if let Some(v) = line.strip_prefix("memory_budget_gb=") {
+            // 0 或无法解析时回到按总内存 40% 估算。
+            cfg.memory_budget_gb = v
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .filter(|gb| *gb > 0)
+                .map(|gb| gb.min(1024));
+        } else if let Some(v) = line.strip_prefix("llm_min_words=") {
+            if let Ok(n) = v.trim().parse::<usize>() {
+                cfg.llm_min_words = n.min(1000);
+            }
         } else if let Some(v) = line.strip_prefix("asr_model=") {
             if let Some(choice) = AsrModelChoice::from_token(v) {
                 cfg.asr_model = choice;
             }
-        } else if let Some(v) = line.strip_prefix("show_floating_orb=") {
+        } else if let Some(v) = line.strip_prefix("floating_orb=") {
             cfg.show_floating_orb = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("fn_hold_ms=") {
+            if let Ok(ms) = v.trim().parse::<u64>() {
+                cfg.fn_hold_ms = ms.min(2000);
+            }
+        } else if let Some(v) = line.strip_prefix("release_grace_ms=") {
+            if let Ok(ms) = v.trim().parse::<u64>() {
+                cfg.release_grace_ms = ms.min(1000);
+            }
+        } else if let Some(v) = line.strip_prefix("overlay_radius=") {
+            if let Ok(r) = v.trim().parse::<f64>() {
+                cfg.overlay_style.corner_radius = clamp_overlay_radius(r, OVERLAY_HEIGHT);
+            }
+        } else if let Some(v) = line.strip_prefix("overlay_opacity=") {
+            if let Ok(o) = v.trim().parse::<f64>() {
+                cfg.overlay_style.opacity = clamp_overlay_opacity(o);
+            }
+        } else if let Some(v) = line.strip_prefix("overlay_high_contrast=") {
+            cfg.overlay_style.high_contrast = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("orb_auto_hide_ms=") {
+            if let Ok(ms) = v.trim().parse::<u64>() {
+                cfg.orb_auto_hide_ms = ms;
+            }
+        } else if let Some(v) = line.strip_prefix("preroll_ms=") {
+            if let Ok(ms) = v.trim().parse::<u64>() {
+                cfg.preroll_ms = ms.min(PREROLL_MAX_MS);
+            }
+        } else if let Some(v) = line.strip_prefix("record_cooldown_ms=") {
+            if let Ok(ms) = v.trim().parse::<u64>() {
+                cfg.record_cooldown_ms = ms.min(2000);
+            }
+        } else if let Some(v) = line.strip_prefix("overlay_show_delay_ms=") {
+            if let Ok(ms) = v.trim().parse::<u64>() {
+                cfg.overlay_show_delay_ms = ms.min(2000);
+            }
+        } else if let Some(v) = line.strip_prefix("stop_on_app_switch=") {
+            cfg.stop_on_app_switch = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("overlay_fixed_x=") {
+            cfg.overlay_fixed_x = v.trim().parse::<i16>().ok();
+        } else if let Some(v) = line.strip_prefix("overlay_fixed_y=") {
+            cfg.overlay_fixed_y = v.trim().parse::<i16>().ok();
+        } else if let Some(v) = line.strip_prefix("type_speed_cps=") {
+            if let Ok(cps) = v.trim().parse::<u64>() {
+                cfg.type_speed_cps = cps;
+            }
+        } else if let Some(v) = line.strip_prefix("disable_text_replacement_during_inject=") {
+            cfg.disable_text_replacement_during_inject = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("trigger_pipe=") {
+            cfg.trigger_pipe = expand_config_path(v);
+        } else if let Some(v) = line.strip_prefix("auto_send=") {
+            cfg.auto_send = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("confirm_before_inject=") {
+            cfg.confirm_before_inject = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("coding_mode=") {
+            cfg.coding_mode = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("llm_fallback=") {
+            cfg.llm_fallback = !matches!(v.trim().to_ascii_lowercase().as_str(), "off" | "false");
+        } else if let Some(v) = line.strip_prefix("warmup=") {
+            cfg.warmup = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("strict_filter=") {
+            cfg.strict_filter = matches!(v.trim().to_ascii_lowercase().as_str(), "on" | "true");
+        } else if let Some(v) = line.strip_prefix("mic_channel=") {
+            if let Some(mic) = MicChannel::from_token(v) {
+                cfg.mic_channel = mic;
+            }
+        } else if let Some(v) = line.strip_prefix("capture_format=") {
+            if let Some(format) = CaptureFormat::from_token(v) {
+                cfg.capture_format = format;
+            }
+        } else if let Some(v) = line.strip_prefix("denoise=") {
+            cfg.denoise = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("compress_silence=") {
+            cfg.compress_silence = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("max_internal_silence_ms=") {
+            if let Ok(ms) = v.trim().parse::<u32>() {
+                cfg.max_internal_silence_ms = ms.clamp(300, 5000);
+            }
+        } else if let Some(v) = line.strip_prefix("error_beep=") {
+            cfg.error_beep = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("incremental_inject=") {
+            cfg.incremental_inject = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("continuous=") {
+            cfg.continuous = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("silence_hang_ms=") {
+            if let Ok(ms) = v.trim().parse::<u64>() {
+                cfg.silence_hang_ms = ms.clamp(200, 3000);
+            }
+        } else if let Some(v) = line.strip_prefix("caption_mode=") {
+            cfg.caption_mode = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("overlay=") {
+            cfg.show_overlay = !matches!(v.trim().to_ascii_lowercase().as_str(), "off" | "false");
+        } else if let Some(v) = line.strip_prefix("overlay_interactive=") {
+            cfg.overlay_interactive = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("overlay_over_fullscreen=") {
+            cfg.overlay_over_fullscreen = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("asr_length_adaptive=") {
+            cfg.asr_length_adaptive = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(v) = line.strip_prefix("debug_drops=") {
+            cfg.debug_drops = v.trim().to_ascii_lowercase() == "true";
+        } else if let Some(rest) = line.strip_prefix("app.") {
+            parse_app_profile_line(&mut cfg.app_profiles, rest);
         }
     }
 
     cfg
 }
 
+fn store_cycle_hotkeys(cfg: &AppConfig) {
+    let pack = |spec: Option<HotkeySpec>| spec.map(HotkeySpec::pack).unwrap_or(HOTKEY_UNSET);
+    CYCLE_LLM_HOTKEY.store(pack(cfg.cycle_llm_hotkey), Ordering::SeqCst);
+    CYCLE_ASR_HOTKEY.store(pack(cfg.cycle_asr_hotkey), Ordering::SeqCst);
+    REPOLISH_HOTKEY.store(pack(cfg.repolish_hotkey), Ordering::SeqCst);
+    CLIPBOARD_TRANSCRIBE_HOTKEY.store(pack(cfg.clipboard_transcribe_hotkey), Ordering::SeqCst);
+}
+
 fn spawn_hotkey_config_watcher(store: Arc<std::sync::atomic::AtomicUsize>) {
     std::thread::spawn(move || loop {
-        let loaded = load_app_config().hotkey;
+        if shutdown_requested() {
+            break;
+        }
+        let cfg = load_app_config();
+        FN_HOLD_THRESHOLD_MS.store(cfg.fn_hold_ms, Ordering::SeqCst);
+        TYPE_SPEED_CPS.store(cfg.type_speed_cps, Ordering::SeqCst);
+        BYPASS_TEXT_REPLACEMENT.store(cfg.disable_text_replacement_during_inject, Ordering::SeqCst);
+        AX_RETRIES.store(cfg.ax_retries, Ordering::SeqCst);
+        INJECT_FOCUS_SETTLE_MS.store(cfg.inject_focus_settle_ms, Ordering::SeqCst);
+        CLIPBOARD_IMAGE_POLICY.store(cfg.clipboard_image.index(), Ordering::SeqCst);
+        CLIPBOARD_IMAGE_MAX_BYTES.store(cfg.clipboard_image_max_mb * 1024 * 1024, Ordering::SeqCst);
+        PRIVACY_MODE.store(cfg.privacy_mode, Ordering::SeqCst);
+        CAPTURE_FORMAT.store(cfg.capture_format.index(), Ordering::SeqCst);
+        HOTKEY_MODE.store(cfg.hotkey_mode.index(), Ordering::SeqCst);
+        OVERLAY_FIXED_ORIGIN.store(
+            pack_overlay_origin(cfg.overlay_fixed_origin()),
+            Ordering::SeqCst,
+        );
+        store_cycle_hotkeys(&cfg);
+        rebuild_overlay_if_changed(&cfg);
+        if !cfg.caption_mode {
+            hide_caption();
+        }
+        let loaded = cfg.hotkey;
         let current = HotkeySpec::unpack(store.load(Ordering::SeqCst));
         if loaded != current {
             store.store(loaded.pack(), Ordering::SeqCst);
@@ -423,6 +1275,38 @@ fn spawn_hotkey_config_watcher(store: Arc<std::sync::atomic::AtomicUsize>) {
     });
 }
 
+fn pack_overlay_origin(origin: Option<(i16, i16)>) -> u64 {
+    match origin {
+        Some((x, y)) => ((x as u16 as u64) << 16) | y as u16 as u64,
+        None => OVERLAY_ORIGIN_UNSET,
+    }
+}
+
+fn overlay_fixed_origin() -> Option<(f64, f64)> {
+    let packed = OVERLAY_FIXED_ORIGIN.load(Ordering::SeqCst);
+    if packed == OVERLAY_ORIGIN_UNSET {
+        return None;
+    }
+    let x = (packed >> 16) as u16 as i16;
+    let y = packed as u16 as i16;
+    Some((x as f64, y as f64))
+}
+
+const PRIVACY_PLACEHOLDER: &str = "（隐私模式，不显示内容）";
+
+fn privacy_mode() -> bool {
+    PRIVACY_MODE.load(Ordering::SeqCst)
+}
+
+/// 转写内容送往界面、通知或日志前经过这里：隐私模式下只给占位文字。
+fn privacy_redact(text: &str, privacy: bool) -> &str {
+    if privacy {
+        PRIVACY_PLACEHOLDER
+    } else {
+        text
+    }
+}
+
 // Global state for floating orb visibility
 static ORB_VISIBLE: OnceLock<Arc<AtomicBool>> = OnceLock::new();
 
@@ -442,7 +1326,7 @@ pub fn spawn_orb_config_watcher(overlay: OverlayHandle) {
     std::thread::spawn(move || {
         let orb_state = get_orb_visible();
         let mut last_visible = orb_state.load(Ordering::SeqCst);
-        loop {
+        while !shutdown_requested() {
             let cfg = load_app_config();
             let current_visible = cfg.show_floating_orb;
             orb_state.store(current_visible, Ordering::SeqCst);
@@ -461,3 +1345,66 @@ pub fn spawn_orb_config_watcher(overlay: OverlayHandle) {
         }
     });
 }
+
+#[cfg(test)]
+mod config_tests {
+    use super::*;
+
+    #[test]
+    fn error_beep_is_opt_in() {
+        assert!(!parse_app_config("").error_beep);
+        assert!(parse_app_config("error_beep=true\n").error_beep);
+        assert!(parse_app_config("error_beep=TRUE").error_beep);
+        assert!(!parse_app_config("error_beep=yes").error_beep);
+    }
+
+    #[test]
+    fn record_cooldown_defaults_and_is_capped() {
+        assert_eq!(parse_app_config("").record_cooldown_ms, 150);
+        assert_eq!(
+            parse_app_config("record_cooldown_ms=400").record_cooldown_ms,
+            400
+        );
+        assert_eq!(
+            parse_app_config("record_cooldown_ms=9000").record_cooldown_ms,
+            2000
+        );
+    }
+
+    #[test]
+    fn v1_orb_setting_survives_the_rename() {
+        assert!(!parse_app_config("show_floating_orb=false").show_floating_orb);
+        assert!(!parse_app_config("version=2\nfloating_orb=false").show_floating_orb);
+        assert!(parse_app_config("version=2\nshow_floating_orb=false").show_floating_orb);
+    }
+
+    #[test]
+    fn inject_strategy_resolves_per_app_then_global() {
+        let cfg = parse_app_config(
+            "inject_strategy=type\n\
+             app.com.microsoft.VSCode.inject_strategy=paste\n\
+             app.com.apple.Notes.inject_strategy=AX\n\
+             app.com.tinyspeck.slackmacgap.auto_send=true\n\
+             app.com.example.Broken.inject_strategy=bogus\n",
+        );
+        let resolve = |bundle_id| cfg.inject_strategy_for(bundle_id);
+        assert_eq!(resolve(Some("com.microsoft.VSCode")), InjectStrategy::Paste);
+        assert_eq!(resolve(Some("com.apple.Notes")), InjectStrategy::Ax);
+        // 有配置但没写 inject_strategy、值无效、未配置的应用与取不到前台应用时沿用全局
+        assert_eq!(
+            resolve(Some("com.tinyspeck.slackmacgap")),
+            InjectStrategy::Type
+        );
+        assert_eq!(resolve(Some("com.example.Broken")), InjectStrategy::Type);
+        assert_eq!(resolve(Some("com.apple.Safari")), InjectStrategy::Type);
+        assert_eq!(resolve(None), InjectStrategy::Type);
+    }
+
+    #[test]
+    fn strict_filter_is_opt_in() {
+        assert!(!parse_app_config("").strict_filter);
+        assert!(parse_app_config("strict_filter=on\n").strict_filter);
+        assert!(parse_app_config("strict_filter=true\n").strict_filter);
+        assert!(!parse_app_config("strict_filter=off\n").strict_filter);
+    }
+}
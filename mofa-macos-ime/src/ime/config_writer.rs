@@ -0,0 +1,175 @@
+// 配置写入：输入法托盘与设置器共用，按键替换原有行，保留注释与未识别的键。
+
+/// 配置文件格式版本；未写 `version=` 的旧文件视为 1。
+const CONFIG_VERSION: u32 = 2;
+
+/// (引入版本, 旧键, 新键)：低于引入版本的配置在加载时改名，值原样保留。
+/// v2：`show_floating_orb` 改为 `floating_orb`，与 `overlay=` 的写法一致。
+const CONFIG_KEY_RENAMES: &[(u32, &str, &str)] = &[(2, "show_floating_orb", "floating_orb")];
+
+fn config_file_version(content: &str) -> u32 {
+    content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("version="))
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .unwrap_or(1)
+}
+
+/// 把旧版本配置升级到当前布局：按改名表替换键名，已存在新键时以新键为准。
+fn migrate_config(content: &str) -> String {
+    let version = config_file_version(content);
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+    for &(since, old_key, new_key) in CONFIG_KEY_RENAMES {
+        if version >= since {
+            continue;
+        }
+        let old_prefix = format!("{old_key}=");
+        let new_prefix = format!("{new_key}=");
+        let has_new = lines
+            .iter()
+            .any(|l| l.trim_start().starts_with(&new_prefix));
+        lines.retain_mut(|line| {
+            let Some(value) = line.trim_start().strip_prefix(&old_prefix) else {
+                return true;
+            };
+            if has_new {
+                return false;
+            }
+            *line = format!("{new_prefix}{value}");
+            true
+        });
+    }
+    lines.join("\n")
+}
+
+/// 把 `key=value` 写入配置文本：已有的键原地替换，缺失的追加到末尾，并更新 `version=`。
+fn upsert_config_lines(content: &str, pairs: &[(&str, String)]) -> String {
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+    let version_line = format!("version={CONFIG_VERSION}");
+    match lines
+        .iter_mut()
+        .find(|l| l.trim_start().starts_with("version="))
+    {
+        Some(line) => *line = version_line,
+        None => lines.insert(0, version_line),
+    }
+
+    for (key, value) in pairs {
+        let prefix = format!("{key}=");
+        let wanted = format!("{key}={value}");
+        match lines
+            .iter_mut()
+            .find(|l| l.trim_start().starts_with(&prefix))
+        {
+            Some(line) => *line = wanted,
+            None => lines.push(wanted),
+        }
+    }
+    let mut out = lines.join("\n");
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// 读取（并迁移）现有配置后写回指定的键值；先写临时文件再改名，读取方不会看到写了一半的配置。
+fn write_config_values(path: &Path, pairs: &[(&str, String)]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("创建配置目录失败: {}", parent.display()))?;
+    }
+    let content = fs::read_to_string(path)
+        .map(|c| migrate_config(&c))
+        .unwrap_or_default();
+    let out = upsert_config_lines(&content, pairs);
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, out).with_context(|| format!("写入配置失败: {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("替换配置失败: {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod config_writer_tests {
+    use super::*;
+
+    const V1_FILE: &str =
+        "# 我的设置\nhotkey=fn\noutput_mode=llm\nshow_floating_orb=false\nlegacy_key=1";
+
+    #[test]
+    fn unversioned_file_is_treated_as_v1() {
+        assert_eq!(config_file_version(V1_FILE), 1);
+        assert_eq!(config_file_version("version=2\nhotkey=fn"), 2);
+        assert_eq!(config_file_version("version=abc"), 1);
+    }
+
+    #[test]
+    fn v1_file_migrates_without_losing_values() {
+        let migrated = migrate_config(V1_FILE);
+        assert_eq!(
+            migrated,
+            "# 我的设置\nhotkey=fn\noutput_mode=llm\nfloating_orb=false\nlegacy_key=1"
+        );
+        // 写回时带上当前版本号，再次加载不会重复迁移。
+        let saved = upsert_config_lines(&migrated, &[]);
+        assert!(saved.starts_with(&format!("version={CONFIG_VERSION}\n")));
+        assert_eq!(migrate_config(&saved), saved.trim_end());
+    }
+
+    #[test]
+    fn existing_new_key_wins_over_renamed_old_key() {
+        let migrated = migrate_config("show_floating_orb=false\nfloating_orb=true");
+        assert_eq!(migrated, "floating_orb=true");
+    }
+
+    #[test]
+    fn current_version_file_is_left_alone() {
+        let content = "version=2\nshow_floating_orb=false";
+        assert_eq!(migrate_config(content), content);
+    }
+
+    #[test]
+    fn upsert_replaces_existing_keys_in_place() {
+        let content = "version=2\n# 注释\noutput_mode=llm\nhotkey=fn\n";
+        let out = upsert_config_lines(content, &[("output_mode", "asr".to_string())]);
+        assert_eq!(out, "version=2\n# 注释\noutput_mode=asr\nhotkey=fn\n");
+    }
+
+    #[test]
+    fn upsert_appends_missing_keys_and_stamps_version() {
+        let out = upsert_config_lines("# 注释\nunknown=1", &[("asr_model", "small".to_string())]);
+        assert_eq!(
+            out,
+            format!("version={CONFIG_VERSION}\n# 注释\nunknown=1\nasr_model=small\n")
+        );
+        let out = upsert_config_lines("version=1\nhotkey=fn", &[]);
+        assert_eq!(out, format!("version={CONFIG_VERSION}\nhotkey=fn\n"));
+        assert_eq!(
+            upsert_config_lines("", &[("llm_model", "auto".to_string())]),
+            format!("version={CONFIG_VERSION}\nllm_model=auto\n")
+        );
+    }
+
+    #[test]
+    fn upsert_matches_whole_keys_only() {
+        let content = "version=2\nllm_model_dir=/models\n";
+        let out = upsert_config_lines(content, &[("llm_model", "auto".to_string())]);
+        assert_eq!(out, "version=2\nllm_model_dir=/models\nllm_model=auto\n");
+    }
+
+    #[test]
+    fn write_config_values_migrates_and_leaves_no_temp_file() {
+        let dir = std::env::temp_dir().join(format!("mofa-config-writer-{}", std::process::id()));
+        let path = dir.join("config.conf");
+        let _ = fs::remove_dir_all(&dir);
+        write_config_values(&path, &[("hotkey", "fn".to_string())]).unwrap();
+        fs::write(&path, V1_FILE).unwrap();
+        write_config_values(&path, &[("output_mode", "asr".to_string())]).unwrap();
+
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("output_mode=asr\n"));
+        assert!(saved.contains("floating_orb=false\n"));
+        assert!(!saved.contains("show_floating_orb"));
+        assert!(!path.with_extension("tmp").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
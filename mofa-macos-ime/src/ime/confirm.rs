@@ -0,0 +1,315 @@
+// 发送前确认：在可编辑浮层中修改文本，Enter 发送、Esc 放弃。
+
+const CONFIRM_WIDTH: f64 = OVERLAY_WIDTH;
+const CONFIRM_HEIGHT: f64 = 64.0;
+const CONFIRM_TIMEOUT_SECS: u64 = 120;
+
+static CONFIRM_WINDOW_PTR: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+static CONFIRM_FIELD_PTR: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+static CONFIRM_TX: Mutex<Option<Sender<Option<String>>>> = Mutex::new(None);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConfirmKey {
+    Enter,
+    Escape,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConfirmState {
+    Editing,
+    Accepted,
+    Discarded,
+}
+
+/// 确认框状态机：只有编辑中才响应按键，结果一旦确定不再改变。
+fn confirm_transition(state: ConfirmState, key: ConfirmKey) -> ConfirmState {
+    match (state, key) {
+        (ConfirmState::Editing, ConfirmKey::Enter) => ConfirmState::Accepted,
+        (ConfirmState::Editing, ConfirmKey::Escape) => ConfirmState::Discarded,
+        (decided, _) => decided,
+    }
+}
+
+/// 阻塞等待用户确认，返回编辑后的文本；放弃或超时返回 None。
+/// 结束后把焦点交还给原来的前台应用。
+fn confirm_text_before_inject(text: &str) -> Option<String> {
+    let previous_app = unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let app: id = if workspace == nil {
+            nil
+        } else {
+            msg_send![workspace, frontmostApplication]
+        };
+        if app != nil {
+            let _: id = msg_send![app, retain];
+        }
+        app as usize
+    };
+
+    let (tx, rx) = mpsc::channel::<Option<String>>();
+    *CONFIRM_TX.lock().unwrap() = Some(tx);
+
+    let initial = text.to_string();
+    Queue::main().exec_async(move || unsafe {
+        show_confirm_window(&initial);
+    });
+
+    let result = rx
+        .recv_timeout(Duration::from_secs(CONFIRM_TIMEOUT_SECS))
+        .unwrap_or(None);
+    CONFIRM_TX.lock().unwrap().take();
+
+    Queue::main().exec_async(move || unsafe {
+        let window = CONFIRM_WINDOW_PTR.load(Ordering::SeqCst) as id;
+        if window != nil {
+            window.orderOut_(nil);
+        }
+        let app = previous_app as id;
+        if app != nil {
+            // NSApplicationActivateIgnoringOtherApps
+            let _: BOOL = msg_send![app, activateWithOptions: 1usize << 1];
+            let _: () = msg_send![app, release];
+        }
+    });
+    // 等待原应用重新拿到焦点，再进行注入。
+    std::thread::sleep(Duration::from_millis(180));
+
+    result
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+fn finish_confirm(key: ConfirmKey) {
+    let state = confirm_transition(ConfirmState::Editing, key);
+    let text = unsafe {
+        let field = CONFIRM_FIELD_PTR.load(Ordering::SeqCst) as id;
+        if field == nil {
+            None
+        } else {
+            let value: id = msg_send![field, stringValue];
+            nsstring_to_rust(value)
+        }
+    };
+    let result = match state {
+        ConfirmState::Accepted => text,
+        ConfirmState::Discarded | ConfirmState::Editing => None,
+    };
+    if let Some(tx) = CONFIRM_TX.lock().unwrap().take() {
+        let _ = tx.send(result);
+    }
+}
+
+unsafe fn show_confirm_window(text: &str) {
+    let mut window = CONFIRM_WINDOW_PTR.load(Ordering::SeqCst) as id;
+    if window == nil {
+        match install_confirm_window() {
+            Ok(w) => window = w,
+            Err(e) => {
+                eprintln!("[mofa-ime] 创建确认窗口失败: {e}");
+                finish_confirm(ConfirmKey::Escape);
+                return;
+            }
+        }
+    }
+
+    let frame = visible_frame();
+    let x = frame.origin.x + (frame.size.width - CONFIRM_WIDTH) / 2.0;
+    let y = frame.origin.y + OVERLAY_BOTTOM_MARGIN;
+    let _: () = msg_send![window, setFrameOrigin: NSPoint::new(x, y)];
+
+    let field = CONFIRM_FIELD_PTR.load(Ordering::SeqCst) as id;
+    if field != nil {
+        let _: () = msg_send![field, setStringValue: ns_string(text)];
+    }
+    let _: () = msg_send![window, makeKeyAndOrderFront: nil];
+    if field != nil {
+        let _: BOOL = msg_send![window, makeFirstResponder: field];
+    }
+}
+
+fn confirm_panel_class() -> &'static Class {
+    static CLASS: OnceLock<usize> = OnceLock::new();
+    let ptr = *CLASS.get_or_init(|| {
+        let superclass = class!(NSPanel);
+        let mut decl = ClassDecl::new("MofaConfirmPanel", superclass).unwrap();
+
+        // 无边框窗口默认不能成为 key window，输入框拿不到键盘。
+        extern "C" fn can_become_key(_this: &Object, _sel: Sel) -> BOOL {
+            YES
+        }
+
+        unsafe {
+            decl.add_method(
+                sel!(canBecomeKeyWindow),
+                can_become_key as extern "C" fn(&Object, Sel) -> BOOL,
+            );
+        }
+        decl.register() as *const Class as usize
+    });
+    unsafe { &*(ptr as *const Class) }
+}
+
+fn create_confirm_field_delegate() -> id {
+    static CLASS: OnceLock<usize> = OnceLock::new();
+    let ptr = *CLASS.get_or_init(|| {
+        let superclass = class!(NSObject);
+        let mut decl = ClassDecl::new("MofaConfirmFieldDelegate", superclass).unwrap();
+
+        extern "C" fn do_command(
+            _this: &Object,
+            _sel: Sel,
+            _control: id,
+            _text_view: id,
+            command: Sel,
+        ) -> BOOL {
+            if command == sel!(insertNewline:) {
+                finish_confirm(ConfirmKey::Enter);
+                YES
+            } else if command == sel!(cancelOperation:) {
+                finish_confirm(ConfirmKey::Escape);
+                YES
+            } else {
+                NO
+            }
+        }
+
+        unsafe {
+            decl.add_method(
+                sel!(control:textView:doCommandBySelector:),
+                do_command as extern "C" fn(&Object, Sel, id, id, Sel) -> BOOL,
+            );
+        }
+        decl.register() as *const Class as usize
+    });
+
+    unsafe {
+        let class = &*(ptr as *const Class);
+        let delegate: id = msg_send![class, alloc];
+        msg_send![delegate, init]
+    }
+}
+
+unsafe fn install_confirm_window() -> Result<id> {
+    // NSWindowStyleMaskBorderless | NSWindowStyleMaskNonactivatingPanel
+    const STYLE_NONACTIVATING_PANEL: usize = 1 << 7;
+
+    let rect = NSRect::new(
+        NSPoint::new(0.0, 0.0),
+        NSSize::new(CONFIRM_WIDTH, CONFIRM_HEIGHT),
+    );
+    let window: id = msg_send![confirm_panel_class(), alloc];
+    let window: id = msg_send![
+        window,
+        initWithContentRect: rect
+        styleMask: STYLE_NONACTIVATING_PANEL
+        backing: NSBackingStoreBuffered
+        defer: NO
+    ];
+    if window == nil {
+        bail!("无法创建确认窗口");
+    }
+
+    let clear_color: id = msg_send![class!(NSColor), clearColor];
+    window.setBackgroundColor_(clear_color);
+    window.setOpaque_(NO);
+    window.setHasShadow_(YES);
+    window.setHidesOnDeactivate_(NO);
+    window.setLevel_((NSMainMenuWindowLevel + 1) as i64);
+    window.setCollectionBehavior_(
+        NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
+            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorTransient,
+    );
+    let _: () = msg_send![window, setReleasedWhenClosed: NO];
+
+    let content = window.contentView();
+    if content == nil {
+        bail!("确认窗口 contentView 为空");
+    }
+    let _: () = msg_send![content, setWantsLayer: YES];
+    let content_layer: id = msg_send![content, layer];
+    if content_layer != nil {
+        let content_bg: id = msg_send![
+            class!(NSColor),
+            colorWithCalibratedWhite: 0.16f64
+            alpha: 0.96f64
+        ];
+        let content_bg_cg: id = msg_send![content_bg, CGColor];
+        let _: () = msg_send![content_layer, setCornerRadius: 15.0f64];
+        let _: () = msg_send![content_layer, setMasksToBounds: YES];
+        let _: () = msg_send![content_layer, setBackgroundColor: content_bg_cg];
+    }
+
+    let field = NSTextField::initWithFrame_(
+        NSTextField::alloc(nil),
+        NSRect::new(
+            NSPoint::new(16.0, 24.0),
+            NSSize::new(CONFIRM_WIDTH - 32.0, 26.0),
+        ),
+    );
+    let _: () = msg_send![field, setEditable: YES];
+    let _: () = msg_send![field, setSelectable: YES];
+    let _: () = msg_send![field, setBezeled: NO];
+    let _: () = msg_send![field, setBordered: NO];
+    let _: () = msg_send![field, setDrawsBackground: NO];
+    let _: () = msg_send![field, setFocusRingType: 1usize];
+    let field_font: id = msg_send![class!(NSFont), systemFontOfSize: 15.0f64];
+    let _: () = msg_send![field, setFont: field_font];
+    let field_color: id = msg_send![class!(NSColor), whiteColor];
+    let _: () = msg_send![field, setTextColor: field_color];
+    let _: () = msg_send![field, setDelegate: create_confirm_field_delegate()];
+    content.addSubview_(field);
+
+    let hint = NSTextField::initWithFrame_(
+        NSTextField::alloc(nil),
+        NSRect::new(
+            NSPoint::new(16.0, 6.0),
+            NSSize::new(CONFIRM_WIDTH - 32.0, 16.0),
+        ),
+    );
+    let _: () = msg_send![hint, setEditable: NO];
+    let _: () = msg_send![hint, setSelectable: NO];
+    let _: () = msg_send![hint, setBezeled: NO];
+    let _: () = msg_send![hint, setBordered: NO];
+    let _: () = msg_send![hint, setDrawsBackground: NO];
+    let hint_font: id = msg_send![class!(NSFont), systemFontOfSize: 11.0f64];
+    let _: () = msg_send![hint, setFont: hint_font];
+    let hint_color: id = msg_send![class!(NSColor), colorWithCalibratedWhite: 0.7f64 alpha: 1.0f64];
+    let _: () = msg_send![hint, setTextColor: hint_color];
+    let _: () = msg_send![hint, setStringValue: ns_string("Enter 发送 · Esc 放弃")];
+    content.addSubview_(hint);
+
+    CONFIRM_WINDOW_PTR.store(window as usize, Ordering::SeqCst);
+    CONFIRM_FIELD_PTR.store(field as usize, Ordering::SeqCst);
+    Ok(window)
+}
+
+#[cfg(test)]
+mod confirm_tests {
+    use super::*;
+
+    #[test]
+    fn enter_accepts_and_escape_discards_while_editing() {
+        assert_eq!(
+            confirm_transition(ConfirmState::Editing, ConfirmKey::Enter),
+            ConfirmState::Accepted
+        );
+        assert_eq!(
+            confirm_transition(ConfirmState::Editing, ConfirmKey::Escape),
+            ConfirmState::Discarded
+        );
+    }
+
+    #[test]
+    fn decided_state_ignores_further_keys() {
+        for key in [ConfirmKey::Enter, ConfirmKey::Escape] {
+            assert_eq!(
+                confirm_transition(ConfirmState::Accepted, key),
+                ConfirmState::Accepted
+            );
+            assert_eq!(
+                confirm_transition(ConfirmState::Discarded, key),
+                ConfirmState::Discarded
+            );
+        }
+    }
+}
@@ -0,0 +1,310 @@
+// 诊断信息：`--diagnostics` 汇总配置、模型与系统环境，便于反馈问题；不含任何录音或文本内容。
+
+const DIAGNOSTICS_LOG_LINES: usize = 200;
+/// 值可能包含用户输入内容的配置键（自由文本或文件路径），导出时整体隐去。
+const DIAGNOSTICS_REDACTED_KEYS: &[&str] = &["inject_prefix", "preview_placeholder", "output_file"];
+/// 日志中携带转写原文的字段（如 debug_drops 的 `text=...`），导出时隐去该字段及其后的内容。
+const DIAGNOSTICS_REDACTED_LOG_FIELDS: &[&str] = &["text="];
+
+struct Diagnostics {
+    app_version: &'static str,
+    macos_version: Option<String>,
+    chip: Option<String>,
+    memory_gb: Option<u64>,
+    accessibility: bool,
+    input_monitoring: bool,
+    microphone: &'static str,
+    config: Vec<String>,
+    models: Vec<(String, u64)>,
+    log_tail: String,
+}
+
+impl Diagnostics {
+    fn collect() -> Self {
+        let home = dirs::home_dir();
+        let config = fs::read_to_string(hotkey_config_path())
+            .map(|c| sanitize_config_lines(&c, home.as_deref()))
+            .unwrap_or_default();
+        let log_tail = ime_log_path()
+            .map(|p| read_log_tail(&p, DIAGNOSTICS_LOG_LINES))
+            .map(|t| sanitize_log_tail(&t, home.as_deref()))
+            .unwrap_or_default();
+        Self {
+            app_version: env!("CARGO_PKG_VERSION"),
+            macos_version: macos_product_version(),
+            chip: sysctl_string("machdep.cpu.brand_string"),
+            memory_gb: total_memory_gb(),
+            accessibility: unsafe { AXIsProcessTrusted() } != 0,
+            input_monitoring: unsafe { CGPreflightListenEventAccess() },
+            microphone: microphone_permission(),
+            config,
+            models: installed_model_files(&model_base_dir()),
+            log_tail,
+        }
+    }
+
+    fn render(&self) -> String {
+        let unknown = || "未知".to_string();
+        let granted = |ok: bool| if ok { "已授权" } else { "未授权" };
+        let mut out = String::new();
+        out.push_str(&format!("MoFA IME {}\n", self.app_version));
+        out.push_str(&format!(
+            "macOS: {}\n",
+            self.macos_version.clone().unwrap_or_else(unknown)
+        ));
+        out.push_str(&format!(
+            "芯片: {}\n",
+            self.chip.clone().unwrap_or_else(unknown)
+        ));
+        out.push_str(&format!(
+            "内存: {}\n",
+            self.memory_gb
+                .map(|g| format!("{g} GB"))
+                .unwrap_or_else(unknown)
+        ));
+        out.push_str(&format!("辅助功能权限: {}\n", granted(self.accessibility)));
+        out.push_str(&format!(
+            "输入监控权限: {}\n",
+            granted(self.input_monitoring)
+        ));
+        out.push_str(&format!("麦克风权限: {}\n", self.microphone));
+
+        out.push_str("\n[配置]\n");
+        if self.config.is_empty() {
+            out.push_str("（无配置文件）\n");
+        }
+        for line in &self.config {
+            out.push_str(line);
+            out.push('\n');
+        }
+
+        out.push_str("\n[模型]\n");
+        if self.models.is_empty() {
+            out.push_str("（未安装模型）\n");
+        }
+        for (name, bytes) in &self.models {
+            out.push_str(&format!("{name}  {} MB\n", bytes / 1024 / 1024));
+        }
+
+        out.push_str(&format!("\n[日志（最后 {DIAGNOSTICS_LOG_LINES} 行）]\n"));
+        out.push_str(&self.log_tail);
+        out
+    }
+}
+
+/// 配置去掉注释与空行，敏感键隐去取值，家目录替换为 `~`。
+fn sanitize_config_lines(content: &str, home: Option<&Path>) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.split_once('=') {
+            Some((key, _)) if DIAGNOSTICS_REDACTED_KEYS.contains(&key.trim()) => {
+                format!("{}=<已隐去>", key.trim())
+            }
+            _ => redact_home(line, home),
+        })
+        .collect()
+}
+
+/// 日志逐行隐去转写原文字段，家目录替换为 `~`。
+fn sanitize_log_tail(text: &str, home: Option<&Path>) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines() {
+        let cut = DIAGNOSTICS_REDACTED_LOG_FIELDS
+            .iter()
+            .filter_map(|field| line.find(field).map(|at| at + field.len()))
+            .min();
+        match cut {
+            Some(at) => {
+                out.push_str(&redact_home(&line[..at], home));
+                out.push_str("<已隐去>");
+            }
+            None => out.push_str(&redact_home(line, home)),
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn redact_home(text: &str, home: Option<&Path>) -> String {
+    match home.and_then(|h| h.to_str()).filter(|h| !h.is_empty()) {
+        Some(h) => text.replace(h, "~"),
+        None => text.to_string(),
+    }
+}
+
+/// 模型目录下的文件名与大小（按文件名排序）。
+fn installed_model_files(base: &Path) -> Vec<(String, u64)> {
+    let Ok(entries) = fs::read_dir(base) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(String, u64)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let meta = e.metadata().ok().filter(|m| m.is_file())?;
+            Some((e.file_name().to_string_lossy().into_owned(), meta.len()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn macos_product_version() -> Option<String> {
+    let out = Command::new("/usr/bin/sw_vers")
+        .arg("-productVersion")
+        .output()
+        .ok()?;
+    let version = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+fn sysctl_string(name: &str) -> Option<String> {
+    let name = CString::new(name).ok()?;
+    let mut buf = [0u8; 256];
+    let mut size = buf.len();
+    let ret = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            buf.as_mut_ptr() as *mut c_void,
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if ret != 0 {
+        return None;
+    }
+    let value = CStr::from_bytes_until_nul(&buf[..size.min(buf.len())])
+        .ok()?
+        .to_string_lossy()
+        .trim()
+        .to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// AVCaptureDevice 的麦克风授权状态；未加载 AVFoundation 时为“未知”。
+fn microphone_permission() -> &'static str {
+    let Some(cls) = Class::get("AVCaptureDevice") else {
+        return "未知";
+    };
+    // AVMediaTypeAudio == @"soun"
+    let status: isize =
+        unsafe { msg_send![cls, authorizationStatusForMediaType: ns_string("soun")] };
+    match status {
+        0 => "未询问",
+        1 => "受限",
+        2 => "已拒绝",
+        3 => "已授权",
+        _ => "未知",
+    }
+}
+
+/// 打印诊断信息；`zip` 时另存为 `~/.mofa/diagnostics.zip`。
+fn run_diagnostics(zip: bool) -> Result<()> {
+    let _pool = unsafe { NSAutoreleasePool::new(nil) };
+    let report = Diagnostics::collect().render();
+    println!("{report}");
+    if !zip {
+        return Ok(());
+    }
+
+    let dir = dirs::home_dir()
+        .map(|h| h.join(".mofa"))
+        .ok_or_else(|| anyhow!("无法定位家目录"))?;
+    fs::create_dir_all(&dir).with_context(|| format!("创建目录失败 {:?}", dir))?;
+    let txt_path = dir.join("diagnostics.txt");
+    let zip_path = dir.join("diagnostics.zip");
+    fs::write(&txt_path, &report).with_context(|| format!("写入失败 {:?}", txt_path))?;
+    let _ = fs::remove_file(&zip_path);
+    let status = Command::new("/usr/bin/zip")
+        .arg("-jq")
+        .arg(&zip_path)
+        .arg(&txt_path)
+        .status()
+        .context("调用 zip 失败")?;
+    let _ = fs::remove_file(&txt_path);
+    if !status.success() {
+        bail!("打包诊断信息失败: {status}");
+    }
+    eprintln!("[mofa-ime] 诊断信息已保存到 {:?}", zip_path);
+    Ok(())
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGPreflightListenEventAccess() -> bool;
+}
+
+#[cfg(test)]
+mod diagnostics_tests {
+    use super::*;
+
+    const HOME: &str = "/Users/alice";
+
+    fn sample(config: &str, log: &str) -> Diagnostics {
+        let home = Some(Path::new(HOME));
+        Diagnostics {
+            app_version: "0.1.0",
+            macos_version: Some("14.5".to_string()),
+            chip: Some("Apple M2".to_string()),
+            memory_gb: Some(16),
+            accessibility: true,
+            input_monitoring: false,
+            microphone: "已授权",
+            config: sanitize_config_lines(config, home),
+            models: vec![("ggml-base.bin".to_string(), 147 * 1024 * 1024)],
+            log_tail: sanitize_log_tail(log, home),
+        }
+    }
+
+    #[test]
+    fn report_includes_environment_models_and_config() {
+        let report = sample("hotkey=fn\noutput_mode=llm", "[mofa-ime] 已就绪\n").render();
+        for expected in [
+            "MoFA IME 0.1.0",
+            "macOS: 14.5",
+            "芯片: Apple M2",
+            "内存: 16 GB",
+            "辅助功能权限: 已授权",
+            "输入监控权限: 未授权",
+            "麦克风权限: 已授权",
+            "hotkey=fn\noutput_mode=llm\n",
+            "ggml-base.bin  147 MB",
+            "[mofa-ime] 已就绪",
+        ] {
+            assert!(
+                report.contains(expected),
+                "missing {expected:?} in\n{report}"
+            );
+        }
+    }
+
+    #[test]
+    fn report_excludes_secrets_and_transcripts() {
+        let config = "# 我的备注\n\
+                      inject_prefix=[机密项目] \n\
+                      preview_placeholder=给老板的话\n\
+                      output_file=/Users/alice/日记/{date}.md\n\
+                      trigger_pipe=/Users/alice/.mofa/trigger";
+        let log = "[mofa-ime] 丢弃 Transcript: 识别结果为空 text=\"我的银行卡密码\"\n\
+                   [mofa-ime] 模型目录 /Users/alice/.mofa/models\n";
+        let report = sample(config, log).render();
+        for secret in [
+            "机密项目",
+            "给老板的话",
+            "日记",
+            "我的备注",
+            "银行卡密码",
+            HOME,
+        ] {
+            assert!(!report.contains(secret), "{secret:?} leaked in\n{report}");
+        }
+        assert!(report.contains("inject_prefix=<已隐去>"));
+        assert!(report.contains("preview_placeholder=<已隐去>"));
+        assert!(report.contains("output_file=<已隐去>"));
+        assert!(report.contains("trigger_pipe=~/.mofa/trigger"));
+        assert!(report.contains("text=<已隐去>"));
+        assert!(report.contains("模型目录 ~/.mofa/models"));
+    }
+}
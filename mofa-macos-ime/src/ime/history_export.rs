@@ -0,0 +1,148 @@
+// 历史导出：把“最近输入”与剪贴板中的文本写成 CSV 或 JSON，便于在外部分析；图片条目不导出。
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HistoryExportFormat {
+    Csv,
+    Json,
+}
+
+impl HistoryExportFormat {
+    fn from_tag(tag: isize) -> Option<Self> {
+        match tag {
+            0 => Some(Self::Csv),
+            1 => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// 剪贴板历史中的文本条目（按新到旧）。
+fn clipboard_texts(items: &[ClipboardHistoryItem]) -> Vec<String> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            ClipboardHistoryItem::Text(text) => Some(text.clone()),
+            ClipboardHistoryItem::Image { .. } => None,
+        })
+        .collect()
+}
+
+/// RFC 4180 字段：含逗号、引号或换行时整体加引号，内部引号写两次。
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// 每行 `source,index,text`，source 为 `history` 或 `clipboard`，index 从 0 起按新到旧。
+fn history_export_csv(history: &[String], clipboard: &[String]) -> String {
+    let mut out = String::from("source,index,text\n");
+    for (source, items) in [("history", history), ("clipboard", clipboard)] {
+        for (i, text) in items.iter().enumerate() {
+            out.push_str(&format!("{source},{i},{}\n", csv_field(text)));
+        }
+    }
+    out
+}
+
+/// `{"history": [...], "clipboard": [...]}`，数组按新到旧。
+fn history_export_json(history: &[String], clipboard: &[String]) -> serde_json::Value {
+    serde_json::json!({
+        "history": history,
+        "clipboard": clipboard,
+    })
+}
+
+/// 导出到 `~/.mofa/history-export-<毫秒时间戳>.<csv|json>`，返回写入的路径；隐私模式下拒绝导出。
+fn export_history(format: HistoryExportFormat) -> Result<PathBuf> {
+    if privacy_mode() {
+        bail!("隐私模式下不导出历史");
+    }
+    let history = get_history_items();
+    let clipboard = clipboard_texts(&get_clipboard_items());
+    let content = match format {
+        HistoryExportFormat::Csv => history_export_csv(&history, &clipboard),
+        HistoryExportFormat::Json => {
+            serde_json::to_string_pretty(&history_export_json(&history, &clipboard))?
+        }
+    };
+
+    let dir = dirs::home_dir()
+        .map(|h| h.join(".mofa"))
+        .ok_or_else(|| anyhow!("无法定位家目录"))?;
+    fs::create_dir_all(&dir).with_context(|| format!("创建目录失败 {:?}", dir))?;
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let path = dir.join(format!("history-export-{stamp}.{}", format.extension()));
+    fs::write(&path, content).with_context(|| format!("写入失败 {:?}", path))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod history_export_tests {
+    use super::*;
+
+    #[test]
+    fn csv_quotes_fields_with_commas_quotes_and_newlines() {
+        assert_eq!(csv_field("plain text"), "plain text");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line1\nline2"), "\"line1\nline2\"");
+        assert_eq!(csv_field("中文，全角逗号"), "中文，全角逗号");
+    }
+
+    #[test]
+    fn csv_lists_history_then_clipboard_newest_first() {
+        let history = vec!["最新".to_string(), "a,b".to_string()];
+        let clipboard = vec!["x\"y".to_string()];
+        assert_eq!(
+            history_export_csv(&history, &clipboard),
+            "source,index,text\n\
+             history,0,最新\n\
+             history,1,\"a,b\"\n\
+             clipboard,0,\"x\"\"y\"\n"
+        );
+        assert_eq!(history_export_csv(&[], &[]), "source,index,text\n");
+    }
+
+    #[test]
+    fn clipboard_export_skips_images() {
+        let items = vec![
+            ClipboardHistoryItem::Text("one".to_string()),
+            ClipboardHistoryItem::Image {
+                data: Vec::new(),
+                uti: "public.png".to_string(),
+            },
+            ClipboardHistoryItem::Text("two".to_string()),
+        ];
+        assert_eq!(clipboard_texts(&items), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn json_export_has_history_and_clipboard_arrays() {
+        let history = vec!["第一条".to_string(), "say \"hi\"".to_string()];
+        let clipboard = vec!["a,b\nc".to_string()];
+        let value = history_export_json(&history, &clipboard);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "history": ["第一条", "say \"hi\""],
+                "clipboard": ["a,b\nc"],
+            })
+        );
+        let empty = history_export_json(&[], &[]);
+        assert_eq!(empty["history"], serde_json::json!([]));
+        assert_eq!(empty["clipboard"], serde_json::json!([]));
+    }
+}
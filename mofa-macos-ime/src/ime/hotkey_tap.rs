@@ -1,7 +1,75 @@
 #[derive(Debug, Clone, Copy)]
 enum HotkeySignal {
-    Down,
-    Up,
+    /// 按下时按住的修饰键（HOTKEY_MOD_* 位），用于识别加速手势
+    Down { modifiers: u8 },
+    /// 松开时按住的修饰键（HOTKEY_MOD_* 位）
+    Up { modifiers: u8 },
+    /// 切换到下一个已安装的模型
+    CycleModel(ModelKind),
+    /// 用下一条备选指令改写上一次注入的文本
+    Repolish,
+    /// 转写剪贴板中的音频文件
+    TranscribeClipboard,
+    /// 不论热键模式都立即结束录音（如录音中切换了前台应用）
+    ForceStop,
+    /// 用当前 LLM 按常规润色指令重新处理上一段 ASR 原文（托盘菜单触发）
+    RepolishLast,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModelKind {
+    Asr,
+    Llm,
+}
+
+fn is_repolish_hotkey(keycode: u16, modifiers: u8) -> bool {
+    HotkeySpec { keycode, modifiers }.pack() == REPOLISH_HOTKEY.load(Ordering::SeqCst)
+}
+
+fn is_clipboard_transcribe_hotkey(keycode: u16, modifiers: u8) -> bool {
+    HotkeySpec { keycode, modifiers }.pack() == CLIPBOARD_TRANSCRIBE_HOTKEY.load(Ordering::SeqCst)
+}
+
+/// 按下的键是否命中某个切换模型热键。
+fn cycle_hotkey_for(keycode: u16, modifiers: u8) -> Option<ModelKind> {
+    let pressed = HotkeySpec { keycode, modifiers }.pack();
+    if pressed == CYCLE_LLM_HOTKEY.load(Ordering::SeqCst) {
+        Some(ModelKind::Llm)
+    } else if pressed == CYCLE_ASR_HOTKEY.load(Ordering::SeqCst) {
+        Some(ModelKind::Asr)
+    } else {
+        None
+    }
+}
+
+/// 加速手势的修饰键：按住 Alt 再按热键，本段改用已安装的最大模型。
+const BOOST_MODIFIER: u8 = HOTKEY_MOD_ALT;
+
+/// 按下时多按了加速修饰键（且它不是热键组合的一部分）。
+fn is_boost_press(down_modifiers: u8, hotkey_modifiers: u8) -> bool {
+    down_modifiers & BOOST_MODIFIER != 0 && hotkey_modifiers & BOOST_MODIFIER == 0
+}
+
+/// 组合键按住期间修饰键变化后是否视为松开；加速手势的 Alt 可以先行松开。
+fn combo_modifiers_released(current: u8, hotkey_modifiers: u8, boosted: bool) -> bool {
+    if boosted {
+        current | BOOST_MODIFIER != hotkey_modifiers | BOOST_MODIFIER
+    } else {
+        current != hotkey_modifiers
+    }
+}
+
+/// 松开热键时按住 Shift（且 Shift 不是热键组合的一部分）：结果只复制到剪贴板，不注入。
+fn release_copy_only(release_modifiers: u8, hotkey_modifiers: u8) -> bool {
+    release_modifiers & HOTKEY_MOD_SHIFT != 0 && hotkey_modifiers & HOTKEY_MOD_SHIFT == 0
+}
+
+/// 回调过慢（超时）或进入安全输入时系统会停用 event tap，之后热键不再有任何事件。
+fn tap_disabled_by_system(event_type: CGEventType) -> bool {
+    matches!(
+        event_type,
+        CGEventType::TapDisabledByTimeout | CGEventType::TapDisabledByUserInput
+    )
 }
 
 struct HotkeyGuard {
@@ -9,6 +77,24 @@ struct HotkeyGuard {
     _source: CFRunLoopSource,
 }
 
+const FN_PHASE_IDLE: usize = 0;
+const FN_PHASE_PENDING: usize = 1;
+const FN_PHASE_HOLDING: usize = 2;
+
+// Fn 按键状态：低 2 位是阶段，其余位是按下序号，避免上一次按下的计时器误触发。
+fn fn_press_state(seq: usize, phase: usize) -> usize {
+    (seq << 2) | (phase & 0b11)
+}
+
+fn fn_press_phase(state: usize) -> usize {
+    state & 0b11
+}
+
+/// 按住时长是否已达到录音阈值；阈值为 0 时按下即视为按住。
+fn fn_press_is_hold(held_ms: u64, threshold_ms: u64) -> bool {
+    threshold_ms == 0 || held_ms >= threshold_ms
+}
+
 fn event_flags_to_hotkey_modifiers(flags: CGEventFlags) -> u8 {
     let mut modifiers = 0u8;
     if flags.contains(CGEventFlags::CGEventFlagCommand) {
@@ -32,8 +118,14 @@ fn install_hotkey_tap(
 ) -> Result<HotkeyGuard> {
     let fn_pressed = Arc::new(AtomicBool::new(false));
     let fn_pressed_cb = Arc::clone(&fn_pressed);
+    let fn_state = Arc::new(std::sync::atomic::AtomicUsize::new(FN_PHASE_IDLE));
+    let fn_seq = Arc::new(AtomicUsize::new(0));
     let combo_pressed = Arc::new(AtomicBool::new(false));
     let combo_pressed_cb = Arc::clone(&combo_pressed);
+    let combo_boosted = AtomicBool::new(false);
+    // tap 创建后才知道它的 mach port，回调里用它重新启用。
+    let tap_port = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let tap_port_cb = Arc::clone(&tap_port);
 
     let tap = CGEventTap::new(
         CGEventTapLocation::Session,
@@ -47,6 +139,21 @@ fn install_hotkey_tap(
         move |_proxy, event_type, event| {
             let hotkey = HotkeySpec::unpack(hotkey_store.load(Ordering::SeqCst));
             match event_type {
+                ty if tap_disabled_by_system(ty) => {
+                    let port = tap_port_cb.load(Ordering::SeqCst);
+                    if port != 0 {
+                        unsafe { CGEventTapEnable(port as *const c_void, true) };
+                    }
+                    eprintln!("[mofa-ime] 热键监听被系统停用（{:?}），已重新启用", ty);
+                    // 停用期间可能错过了松开：仍按住中的热键按松开处理，避免录音停不下来。
+                    let prev = fn_state.swap(FN_PHASE_IDLE, Ordering::SeqCst);
+                    let fn_holding = fn_press_phase(prev) == FN_PHASE_HOLDING;
+                    let was_fn = fn_pressed_cb.swap(false, Ordering::SeqCst);
+                    let was_combo = combo_pressed_cb.swap(false, Ordering::SeqCst);
+                    if (was_fn && fn_holding) || was_combo {
+                        let _ = tx.send(HotkeySignal::Up { modifiers: 0 });
+                    }
+                }
                 CGEventType::FlagsChanged => {
                     if hotkey.is_fn() {
                         combo_pressed_cb.store(false, Ordering::SeqCst);
@@ -56,9 +163,40 @@ fn install_hotkey_tap(
                             .contains(CGEventFlags::CGEventFlagSecondaryFn);
                         let was_fn = fn_pressed_cb.swap(is_fn_now, Ordering::SeqCst);
                         if is_fn_now && !was_fn {
-                            let _ = tx.send(HotkeySignal::Down);
+                            let threshold_ms = FN_HOLD_THRESHOLD_MS.load(Ordering::SeqCst);
+                            let modifiers = event_flags_to_hotkey_modifiers(event.get_flags());
+                            if fn_press_is_hold(0, threshold_ms) {
+                                fn_state.store(FN_PHASE_HOLDING, Ordering::SeqCst);
+                                let _ = tx.send(HotkeySignal::Down { modifiers });
+                                return None;
+                            }
+                            // 按满阈值后才开始录音；tap 只旁听，Fn 事件本身总会交给系统。
+                            let seq = fn_seq.fetch_add(1, Ordering::SeqCst).wrapping_add(1);
+                            let pending = fn_press_state(seq, FN_PHASE_PENDING);
+                            fn_state.store(pending, Ordering::SeqCst);
+                            let fn_state_timer = Arc::clone(&fn_state);
+                            let tx_timer = tx.clone();
+                            std::thread::spawn(move || {
+                                std::thread::sleep(Duration::from_millis(threshold_ms));
+                                let holding = fn_press_state(seq, FN_PHASE_HOLDING);
+                                if fn_state_timer
+                                    .compare_exchange(
+                                        pending,
+                                        holding,
+                                        Ordering::SeqCst,
+                                        Ordering::SeqCst,
+                                    )
+                                    .is_ok()
+                                {
+                                    let _ = tx_timer.send(HotkeySignal::Down { modifiers });
+                                }
+                            });
                         } else if !is_fn_now && was_fn {
-                            let _ = tx.send(HotkeySignal::Up);
+                            let prev = fn_state.swap(FN_PHASE_IDLE, Ordering::SeqCst);
+                            if fn_press_phase(prev) == FN_PHASE_HOLDING {
+                                let modifiers = event_flags_to_hotkey_modifiers(event.get_flags());
+                                let _ = tx.send(HotkeySignal::Up { modifiers });
+                            }
                         }
                         return None;
                     }
@@ -66,29 +204,58 @@ fn install_hotkey_tap(
                     fn_pressed_cb.store(false, Ordering::SeqCst);
                     if combo_pressed_cb.load(Ordering::SeqCst) {
                         let modifiers = event_flags_to_hotkey_modifiers(event.get_flags());
-                        if modifiers != hotkey.modifiers {
+                        let boosted = combo_boosted.load(Ordering::SeqCst);
+                        if combo_modifiers_released(modifiers, hotkey.modifiers, boosted) {
                             combo_pressed_cb.store(false, Ordering::SeqCst);
-                            let _ = tx.send(HotkeySignal::Up);
+                            let _ = tx.send(HotkeySignal::Up { modifiers });
                         }
                     }
                 }
                 CGEventType::KeyDown => {
-                    if hotkey.is_fn() {
-                        return None;
-                    }
                     let keycode =
                         event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE) as u16;
-                    if keycode != hotkey.keycode {
+                    let modifiers = event_flags_to_hotkey_modifiers(event.get_flags());
+                    let is_repeat =
+                        event.get_integer_value_field(EventField::KEYBOARD_EVENT_AUTOREPEAT);
+                    if is_repeat == 0 {
+                        if let Some(kind) = cycle_hotkey_for(keycode, modifiers) {
+                            let _ = tx.send(HotkeySignal::CycleModel(kind));
+                            return None;
+                        }
+                        if is_repolish_hotkey(keycode, modifiers) {
+                            let _ = tx.send(HotkeySignal::Repolish);
+                            return None;
+                        }
+                        if is_clipboard_transcribe_hotkey(keycode, modifiers) {
+                            let _ = tx.send(HotkeySignal::TranscribeClipboard);
+                            return None;
+                        }
+                    }
+                    if hotkey.is_fn() {
+                        // Fn 与其他键组合使用（如 Fn+F1）时，取消尚未开始的录音。
+                        let state = fn_state.load(Ordering::SeqCst);
+                        if fn_press_phase(state) == FN_PHASE_PENDING {
+                            let _ = fn_state.compare_exchange(
+                                state,
+                                FN_PHASE_IDLE,
+                                Ordering::SeqCst,
+                                Ordering::SeqCst,
+                            );
+                        }
                         return None;
                     }
-                    let modifiers = event_flags_to_hotkey_modifiers(event.get_flags());
-                    if modifiers != hotkey.modifiers {
+                    let boosted = is_boost_press(modifiers, hotkey.modifiers);
+                    let expected = if boosted {
+                        hotkey.modifiers | BOOST_MODIFIER
+                    } else {
+                        hotkey.modifiers
+                    };
+                    if keycode != hotkey.keycode || modifiers != expected {
                         return None;
                     }
-                    let is_repeat =
-                        event.get_integer_value_field(EventField::KEYBOARD_EVENT_AUTOREPEAT);
                     if is_repeat == 0 && !combo_pressed_cb.swap(true, Ordering::SeqCst) {
-                        let _ = tx.send(HotkeySignal::Down);
+                        combo_boosted.store(boosted, Ordering::SeqCst);
+                        let _ = tx.send(HotkeySignal::Down { modifiers });
                     }
                 }
                 CGEventType::KeyUp => {
@@ -98,7 +265,8 @@ fn install_hotkey_tap(
                     let keycode =
                         event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE) as u16;
                     if keycode == hotkey.keycode && combo_pressed_cb.swap(false, Ordering::SeqCst) {
-                        let _ = tx.send(HotkeySignal::Up);
+                        let modifiers = event_flags_to_hotkey_modifiers(event.get_flags());
+                        let _ = tx.send(HotkeySignal::Up { modifiers });
                     }
                 }
                 _ => {}
@@ -108,6 +276,11 @@ fn install_hotkey_tap(
     )
     .map_err(|_| anyhow!("创建 CGEventTap 失败；请检查输入监控权限"))?;
 
+    tap_port.store(
+        tap.mach_port.as_concrete_TypeRef() as usize,
+        Ordering::SeqCst,
+    );
+
     let source = tap
         .mach_port
         .create_runloop_source(0)
@@ -124,3 +297,200 @@ fn install_hotkey_tap(
         _source: source,
     })
 }
+
+/// 外部触发（脚踏开关等）写入命名管道的一行文本 -> 热键信号。
+fn parse_trigger_line(line: &str) -> Option<HotkeySignal> {
+    match line.trim().to_ascii_lowercase().as_str() {
+        "down" => Some(HotkeySignal::Down { modifiers: 0 }),
+        "up" => Some(HotkeySignal::Up { modifiers: 0 }),
+        _ => None,
+    }
+}
+
+/// 触发管道路径是否为命名管道；普通文件打开后立刻读到 EOF，反复重开会空转。
+fn is_fifo(path: &Path) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    fs::metadata(path)
+        .map(|m| m.file_type().is_fifo())
+        .unwrap_or(false)
+}
+
+fn spawn_trigger_pipe_reader(path: PathBuf, tx: Sender<HotkeySignal>) {
+    std::thread::spawn(move || {
+        use std::io::BufRead;
+        use std::os::unix::ffi::OsStrExt;
+
+        if !path.exists() {
+            if let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) {
+                let ret = unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) };
+                if ret != 0 {
+                    let err = std::io::Error::last_os_error();
+                    eprintln!("[mofa-ime] 创建触发管道失败 {:?}: {err}", path);
+                }
+            }
+        }
+
+        while !shutdown_requested() {
+            if !is_fifo(&path) {
+                eprintln!(
+                    "[mofa-ime] 触发管道 {:?} 不是命名管道，已停用外部触发；请删除该文件后重启",
+                    path
+                );
+                return;
+            }
+            // 打开 FIFO 会阻塞到有写入方；写入方关闭后读到 EOF，再重新打开等待下一次。
+            let file = match fs::File::open(&path) {
+                Ok(f) => f,
+                Err(e) => {
+                    eprintln!("[mofa-ime] 打开触发管道失败 {:?}: {e}", path);
+                    std::thread::sleep(Duration::from_secs(2));
+                    continue;
+                }
+            };
+            for line in std::io::BufReader::new(file).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Some(sig) = parse_trigger_line(&line) {
+                    if tx.send(sig).is_err() {
+                        return;
+                    }
+                }
+            }
+            // 写入方关闭后稍等再重开，避免异常情况下忙等。
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    });
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventTapEnable(tap: *const c_void, enable: bool);
+}
+
+#[cfg(test)]
+mod hotkey_tap_tests {
+    use super::*;
+
+    #[test]
+    fn zero_threshold_treats_every_press_as_hold() {
+        assert!(fn_press_is_hold(0, 0));
+    }
+
+    #[test]
+    fn quick_tap_below_threshold_is_not_a_hold() {
+        assert!(!fn_press_is_hold(0, 250));
+        assert!(!fn_press_is_hold(249, 250));
+        assert!(fn_press_is_hold(250, 250));
+        assert!(fn_press_is_hold(900, 250));
+    }
+
+    #[test]
+    fn press_state_keeps_phase_and_separates_presses() {
+        let first = fn_press_state(1, FN_PHASE_PENDING);
+        let second = fn_press_state(2, FN_PHASE_PENDING);
+        assert_eq!(fn_press_phase(first), FN_PHASE_PENDING);
+        assert_eq!(
+            fn_press_phase(fn_press_state(7, FN_PHASE_HOLDING)),
+            FN_PHASE_HOLDING
+        );
+        // 上一次按下的计时器拿着旧状态，无法把新一次按下误判为按住。
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn trigger_lines_map_to_hotkey_signals() {
+        assert!(matches!(
+            parse_trigger_line("down"),
+            Some(HotkeySignal::Down { modifiers: 0 })
+        ));
+        assert!(matches!(
+            parse_trigger_line("  UP\r"),
+            Some(HotkeySignal::Up { modifiers: 0 })
+        ));
+    }
+
+    #[test]
+    fn unknown_trigger_lines_are_ignored() {
+        assert!(parse_trigger_line("").is_none());
+        assert!(parse_trigger_line("press").is_none());
+        assert!(parse_trigger_line("down up").is_none());
+    }
+
+    #[test]
+    fn shift_on_release_copies_instead_of_injecting() {
+        assert!(release_copy_only(HOTKEY_MOD_SHIFT, 0));
+        assert!(release_copy_only(
+            HOTKEY_MOD_SHIFT | HOTKEY_MOD_CMD,
+            HOTKEY_MOD_CMD
+        ));
+        assert!(!release_copy_only(0, 0));
+        assert!(!release_copy_only(HOTKEY_MOD_CMD, HOTKEY_MOD_CMD));
+    }
+
+    #[test]
+    fn shift_in_the_hotkey_combo_is_not_the_gesture() {
+        let combo = HOTKEY_MOD_CMD | HOTKEY_MOD_SHIFT;
+        assert!(!release_copy_only(combo, combo));
+    }
+
+    #[test]
+    fn alt_on_press_boosts_unless_part_of_the_hotkey() {
+        assert!(is_boost_press(HOTKEY_MOD_ALT, 0));
+        assert!(is_boost_press(
+            HOTKEY_MOD_ALT | HOTKEY_MOD_CMD,
+            HOTKEY_MOD_CMD
+        ));
+        assert!(!is_boost_press(0, 0));
+        assert!(!is_boost_press(HOTKEY_MOD_ALT, HOTKEY_MOD_ALT));
+    }
+
+    #[test]
+    fn boosted_combo_tolerates_releasing_alt_first() {
+        let hotkey = HOTKEY_MOD_CMD;
+        assert!(!combo_modifiers_released(
+            HOTKEY_MOD_CMD | HOTKEY_MOD_ALT,
+            hotkey,
+            true
+        ));
+        assert!(!combo_modifiers_released(HOTKEY_MOD_CMD, hotkey, true));
+        assert!(combo_modifiers_released(HOTKEY_MOD_ALT, hotkey, true));
+        assert!(combo_modifiers_released(
+            HOTKEY_MOD_CMD | HOTKEY_MOD_ALT,
+            hotkey,
+            false
+        ));
+    }
+
+    #[test]
+    fn only_system_disable_events_re_enable_the_tap() {
+        assert!(tap_disabled_by_system(CGEventType::TapDisabledByTimeout));
+        assert!(tap_disabled_by_system(CGEventType::TapDisabledByUserInput));
+        for ty in [
+            CGEventType::KeyDown,
+            CGEventType::KeyUp,
+            CGEventType::FlagsChanged,
+        ] {
+            assert!(!tap_disabled_by_system(ty));
+        }
+    }
+
+    #[test]
+    fn trigger_pipe_must_be_a_fifo() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = std::env::temp_dir().join(format!("mofa-trigger-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("regular");
+        fs::write(&file, "down\nup\n").unwrap();
+        assert!(!is_fifo(&file));
+        assert!(!is_fifo(&dir.join("missing")));
+
+        let fifo = dir.join("fifo");
+        let c_path = CString::new(fifo.as_os_str().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+        assert!(is_fifo(&fifo));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
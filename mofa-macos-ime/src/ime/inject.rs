@@ -1,4 +1,90 @@
-fn inject_text(text: &str) -> Result<()> {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InjectMethod {
+    Ax,
+    Paste,
+    Type,
+}
+
+/// 焦点元素的类型，决定 auto 策略下先用哪种注入方式。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FocusKind {
+    NativeText,
+    WebInput,
+    NonEditable,
+    Unknown,
+}
+
+/// 由 AXRole/AXSubrole 与是否网页元素（带 DOM 属性）判断焦点类型。
+fn focus_kind_from_role(role: Option<&str>, subrole: Option<&str>, is_web: bool) -> FocusKind {
+    const TEXT_ROLES: [&str; 4] = ["AXTextField", "AXTextArea", "AXComboBox", "AXSearchField"];
+    const NON_EDITABLE_ROLES: [&str; 9] = [
+        "AXButton",
+        "AXStaticText",
+        "AXImage",
+        "AXWindow",
+        "AXList",
+        "AXTable",
+        "AXOutline",
+        "AXMenuItem",
+        "AXCheckBox",
+    ];
+    let Some(role) = role else {
+        return FocusKind::Unknown;
+    };
+    let editable = TEXT_ROLES.contains(&role) || subrole == Some("AXSearchField");
+    if is_web && (editable || role == "AXWebArea" || role == "AXGroup") {
+        // Chromium/Electron 的 contenteditable 常表现为 AXGroup/AXWebArea。
+        FocusKind::WebInput
+    } else if editable {
+        FocusKind::NativeText
+    } else if NON_EDITABLE_ROLES.contains(&role) {
+        FocusKind::NonEditable
+    } else {
+        FocusKind::Unknown
+    }
+}
+
+/// auto 策略按焦点类型选顺序：原生输入框 AX 最稳，网页输入框粘贴最稳；
+/// 不可编辑时只走粘贴（结果至少留在剪贴板里）。
+fn ladder_for_focus(kind: FocusKind) -> &'static [InjectMethod] {
+    match kind {
+        FocusKind::NativeText => &[InjectMethod::Ax, InjectMethod::Paste],
+        FocusKind::WebInput => &[InjectMethod::Paste, InjectMethod::Paste],
+        FocusKind::NonEditable => &[InjectMethod::Paste],
+        FocusKind::Unknown => inject_ladder(InjectStrategy::Auto),
+    }
+}
+
+/// 各注入方式的尝试顺序；显式指定的方式只用它本身，不回退到其它方式。
+fn inject_ladder(strategy: InjectStrategy) -> &'static [InjectMethod] {
+    match strategy {
+        // 剪贴板粘贴重试两次，提升兼容性。
+        InjectStrategy::Auto | InjectStrategy::Paste => &[InjectMethod::Paste, InjectMethod::Paste],
+        InjectStrategy::Ax => &[InjectMethod::Ax],
+        InjectStrategy::Type => &[InjectMethod::Type],
+    }
+}
+
+/// 焦点是否已落在可编辑元素上。
+fn focus_is_editable(kind: FocusKind) -> bool {
+    matches!(kind, FocusKind::NativeText | FocusKind::WebInput)
+}
+
+/// 取注入前的焦点：不在输入框时等待 `settle_ms` 后复查一次，返回最后一次看到的焦点类型。
+fn settle_focus(
+    settle_ms: u64,
+    mut probe: impl FnMut() -> FocusKind,
+    mut wait: impl FnMut(u64),
+) -> FocusKind {
+    let kind = probe();
+    if focus_is_editable(kind) || settle_ms == 0 {
+        return kind;
+    }
+    wait(settle_ms);
+    probe()
+}
+
+fn inject_text(text: &str, strategy: InjectStrategy) -> Result<()> {
     if text.trim().is_empty() {
         return Ok(());
     }
@@ -11,15 +97,160 @@ fn inject_text(text: &str) -> Result<()> {
     // 注意：所有 UI 相关操作都已在主线程运行（通过管道事件触发）
     let _pool = unsafe { NSAutoreleasePool::new(nil) };
 
-    // 剪贴板粘贴重试两次，提升兼容性。
-    for _ in 0..2 {
-        if paste_via_clipboard(text).is_ok() {
-            return Ok(());
+    // 悬浮窗/悬浮球交互后目标输入框可能还没拿回焦点，稍等再注入，避免落空。
+    let focus = settle_focus(
+        INJECT_FOCUS_SETTLE_MS.load(Ordering::SeqCst),
+        classify_focus,
+        |ms| std::thread::sleep(Duration::from_millis(ms)),
+    );
+    let ladder = if strategy == InjectStrategy::Auto {
+        ladder_for_focus(focus)
+    } else {
+        inject_ladder(strategy)
+    };
+    let mut last_err = anyhow!("没有可用的注入方式");
+    for method in ladder {
+        let result = match method {
+            InjectMethod::Ax => {
+                let retries = AX_RETRIES.load(Ordering::SeqCst);
+                if retry_attempts(retries, AX_RETRY_DELAY_MS, || try_insert_via_ax(text)) {
+                    Ok(())
+                } else {
+                    Err(anyhow!("AX 插入失败"))
+                }
+            }
+            InjectMethod::Paste => paste_via_clipboard(text),
+            InjectMethod::Type => type_text_via_events(text),
+        };
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) => last_err = e,
         }
         std::thread::sleep(Duration::from_millis(90));
     }
 
-    Err(anyhow!("剪贴板粘贴失败"))
+    Err(last_err.context(format!("注入失败（inject_strategy={strategy:?}）")))
+}
+
+/// 代码编辑器/终端里回车不是“发送”，全局 auto_send 不在这些应用生效（可用应用配置显式开启）。
+fn is_code_editor_bundle(bundle_id: &str) -> bool {
+    const EDITORS: [&str; 8] = [
+        "com.microsoft.VSCode",
+        "com.apple.dt.Xcode",
+        "com.todesktop.230313mzl4w4u92",
+        "dev.zed.Zed",
+        "com.sublimetext.4",
+        "com.apple.Terminal",
+        "com.googlecode.iterm2",
+        "net.kovidgoyal.kitty",
+    ];
+    EDITORS.contains(&bundle_id) || bundle_id.starts_with("com.jetbrains.")
+}
+
+/// 注入成功后是否补发回车：应用配置优先，其次是全局 auto_send。
+fn should_press_enter(
+    auto_send: bool,
+    profile: Option<&AppProfile>,
+    bundle_id: Option<&str>,
+    text: &str,
+) -> bool {
+    if text.trim().is_empty() {
+        return false;
+    }
+    match profile.and_then(|p| p.auto_send) {
+        Some(enabled) => enabled,
+        None => auto_send && !bundle_id.is_some_and(is_code_editor_bundle),
+    }
+}
+
+fn frontmost_bundle_id() -> Option<String> {
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace == nil {
+            return None;
+        }
+        let app: id = msg_send![workspace, frontmostApplication];
+        if app == nil {
+            return None;
+        }
+        let bundle_id: id = msg_send![app, bundleIdentifier];
+        nsstring_to_rust(bundle_id)
+    }
+}
+
+/// 在运行中的应用里按 bundle id 找注入目标（忽略大小写，多个实例取第一个）。
+fn resolve_target_app(bundle_id: &str, running: &[Option<String>]) -> Option<usize> {
+    let wanted = bundle_id.trim();
+    if wanted.is_empty() {
+        return None;
+    }
+    running
+        .iter()
+        .position(|b| b.as_deref().is_some_and(|b| b.eq_ignore_ascii_case(wanted)))
+}
+
+/// 先激活 `inject_target_bundle` 指定的应用再注入；目标未运行时结果写入剪贴板并报错。
+/// 需要自动发送时在切回原应用之前补发回车。
+fn inject_into_target(
+    text: &str,
+    strategy: InjectStrategy,
+    bundle_id: &str,
+    restore_focus: bool,
+    press_enter: bool,
+) -> Result<()> {
+    // NSApplicationActivateIgnoringOtherApps
+    const ACTIVATE_IGNORING_OTHER_APPS: usize = 1 << 1;
+
+    let (target, previous) = unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace == nil {
+            bail!("无法获取 NSWorkspace");
+        }
+        let apps: id = msg_send![workspace, runningApplications];
+        let count: usize = if apps == nil {
+            0
+        } else {
+            msg_send![apps, count]
+        };
+        let mut handles = Vec::with_capacity(count);
+        let mut bundles = Vec::with_capacity(count);
+        for idx in 0..count {
+            let app: id = msg_send![apps, objectAtIndex: idx];
+            let bundle: id = msg_send![app, bundleIdentifier];
+            handles.push(app);
+            bundles.push(nsstring_to_rust(bundle));
+        }
+        let previous: id = msg_send![workspace, frontmostApplication];
+        (
+            resolve_target_app(bundle_id, &bundles).map(|idx| handles[idx]),
+            previous,
+        )
+    };
+    let Some(target) = target else {
+        copy_text_to_clipboard(text);
+        bail!("目标应用未运行: {bundle_id}（结果已复制到剪贴板）");
+    };
+
+    let already_front = target == previous;
+    if !already_front {
+        let _: BOOL =
+            unsafe { msg_send![target, activateWithOptions: ACTIVATE_IGNORING_OTHER_APPS] };
+        // 等目标应用拿到焦点再注入。
+        std::thread::sleep(Duration::from_millis(250));
+    }
+    let result = inject_text(text, strategy);
+    if result.is_ok() && press_enter {
+        std::thread::sleep(Duration::from_millis(60));
+        if let Err(e) = post_return_key() {
+            eprintln!("[mofa-ime] 自动发送回车失败: {e}");
+        }
+    }
+    if restore_focus && !already_front && previous != nil {
+        std::thread::sleep(Duration::from_millis(120));
+        let _: BOOL =
+            unsafe { msg_send![previous, activateWithOptions: ACTIVATE_IGNORING_OTHER_APPS] };
+    }
+    result
 }
 
 type AXUIElementRef = *const c_void;
@@ -34,6 +265,11 @@ extern "C" {
         attribute: core_foundation_sys::string::CFStringRef,
         value: *mut core_foundation_sys::base::CFTypeRef,
     ) -> AXError;
+    fn AXUIElementSetAttributeValue(
+        element: AXUIElementRef,
+        attribute: core_foundation_sys::string::CFStringRef,
+        value: core_foundation_sys::base::CFTypeRef,
+    ) -> AXError;
     fn AXUIElementCopyParameterizedAttributeValue(
         element: AXUIElementRef,
         parameterized_attribute: core_foundation_sys::string::CFStringRef,
@@ -48,6 +284,335 @@ extern "C" {
     ) -> core_foundation_sys::base::Boolean;
 }
 
+/// 当前焦点元素（调用方负责 CFRelease）。
+unsafe fn focused_ax_element() -> Option<AXUIElementRef> {
+    let system = AXUIElementCreateSystemWide();
+    if system.is_null() {
+        return None;
+    }
+    let focused_attr = CFString::new("AXFocusedUIElement");
+    let mut focused_val: core_foundation_sys::base::CFTypeRef = std::ptr::null();
+    let err =
+        AXUIElementCopyAttributeValue(system, focused_attr.as_concrete_TypeRef(), &mut focused_val);
+    CFRelease(system as core_foundation_sys::base::CFTypeRef);
+    if err != 0 || focused_val.is_null() {
+        None
+    } else {
+        Some(focused_val as AXUIElementRef)
+    }
+}
+
+const AX_RETRY_DELAY_MS: u64 = 60;
+
+/// 最多尝试 1 + retries 次，两次之间等待 delay_ms；任一次成功即返回 true。
+fn retry_attempts(retries: u64, delay_ms: u64, mut attempt: impl FnMut() -> bool) -> bool {
+    for i in 0..=retries {
+        if i > 0 {
+            std::thread::sleep(Duration::from_millis(delay_ms));
+        }
+        if attempt() {
+            return true;
+        }
+    }
+    false
+}
+
+unsafe fn ax_string_attribute(element: AXUIElementRef, name: &str) -> Option<String> {
+    let attr = CFString::new(name);
+    let mut value: core_foundation_sys::base::CFTypeRef = std::ptr::null();
+    let err = AXUIElementCopyAttributeValue(element, attr.as_concrete_TypeRef(), &mut value);
+    if err != 0 || value.is_null() {
+        return None;
+    }
+    CFType::wrap_under_create_rule(value)
+        .downcast_into::<CFString>()
+        .map(|s| s.to_string())
+}
+
+unsafe fn ax_has_attribute(element: AXUIElementRef, name: &str) -> bool {
+    let attr = CFString::new(name);
+    let mut value: core_foundation_sys::base::CFTypeRef = std::ptr::null();
+    let err = AXUIElementCopyAttributeValue(element, attr.as_concrete_TypeRef(), &mut value);
+    if !value.is_null() {
+        CFRelease(value);
+    }
+    err == 0 && !value.is_null()
+}
+
+/// 读取当前焦点元素的 AX 角色，判断是原生输入框、网页输入框还是不可编辑元素。
+fn classify_focus() -> FocusKind {
+    unsafe {
+        let Some(focused) = focused_ax_element() else {
+            return FocusKind::Unknown;
+        };
+        let role = ax_string_attribute(focused, "AXRole");
+        let subrole = ax_string_attribute(focused, "AXSubrole");
+        let is_web = ax_has_attribute(focused, "AXDOMClassList");
+        CFRelease(focused as core_foundation_sys::base::CFTypeRef);
+        focus_kind_from_role(role.as_deref(), subrole.as_deref(), is_web)
+    }
+}
+
+/// 通过 AX 在光标处替换选中文本（即插入）；原生控件可用，部分 Electron/网页控件不支持。
+fn try_insert_via_ax(text: &str) -> bool {
+    unsafe {
+        let Some(focused) = focused_ax_element() else {
+            return false;
+        };
+        let attr = CFString::new("AXSelectedText");
+        let value = CFString::new(text);
+        let err =
+            AXUIElementSetAttributeValue(focused, attr.as_concrete_TypeRef(), value.as_CFTypeRef());
+        CFRelease(focused as core_foundation_sys::base::CFTypeRef);
+        err == 0
+    }
+}
+
+/// 两段键盘事件之间的间隔：按 `cps`（字/秒）折算本段字数，0 表示不限速。
+fn type_chunk_delay_ms(cps: u64, chunk_chars: usize) -> u64 {
+    const MIN_DELAY_MS: u64 = 4;
+    if cps == 0 {
+        return MIN_DELAY_MS;
+    }
+    (chunk_chars as u64 * 1000 / cps).max(MIN_DELAY_MS)
+}
+
+/// 注入事件的来源标记（"mofa"），写入 EVENT_SOURCE_USER_DATA。
+const INJECT_EVENT_USER_DATA: i64 = 0x6d6f_6661;
+
+/// `disable_text_replacement_during_inject` 开启时用私有事件源：事件不继承真实键盘的状态，
+/// 多数应用不会把它当作用户逐键输入去触发文本替换。尽力而为，最终取决于目标应用。
+fn inject_event_source_state(bypass: bool) -> CGEventSourceStateID {
+    if bypass {
+        CGEventSourceStateID::Private
+    } else {
+        CGEventSourceStateID::HIDSystemState
+    }
+}
+
+fn inject_event_source() -> Result<CGEventSource> {
+    let state = inject_event_source_state(BYPASS_TEXT_REPLACEMENT.load(Ordering::SeqCst));
+    CGEventSource::new(state).map_err(|_| anyhow!("创建 CGEventSource 失败"))
+}
+
+/// 开启绕开文本替换时注入事件携带的来源标记；关闭时不改事件。
+fn inject_event_user_data(bypass: bool) -> Option<i64> {
+    bypass.then_some(INJECT_EVENT_USER_DATA)
+}
+
+fn mark_injected_event(event: &CGEvent) {
+    if let Some(data) = inject_event_user_data(BYPASS_TEXT_REPLACEMENT.load(Ordering::SeqCst)) {
+        event.set_integer_value_field(EventField::EVENT_SOURCE_USER_DATA, data);
+    }
+}
+
+/// 以 Unicode 键盘事件逐段输入，不经过剪贴板。
+fn type_text_via_events(text: &str) -> Result<()> {
+    // CGEventKeyboardSetUnicodeString 单个事件最多携带 20 个 UTF-16 单元。
+    const CHUNK_UTF16: usize = 20;
+
+    let source = inject_event_source()?;
+    let utf16: Vec<u16> = text.encode_utf16().collect();
+    let mut start = 0usize;
+    while start < utf16.len() {
+        let mut end = (start + CHUNK_UTF16).min(utf16.len());
+        // 不在代理对中间切开。
+        if end < utf16.len() && (0xD800..0xDC00).contains(&utf16[end - 1]) {
+            end -= 1;
+        }
+        let chunk = &utf16[start..end];
+        let chunk_chars = char::decode_utf16(chunk.iter().copied()).count();
+
+        let down = CGEvent::new_keyboard_event(source.clone(), 0, true)
+            .map_err(|_| anyhow!("创建 unicode down 失败"))?;
+        down.set_flags(CGEventFlags::CGEventFlagNull);
+        down.set_string_from_utf16_unchecked(chunk);
+        mark_injected_event(&down);
+        down.post(CGEventTapLocation::HID);
+
+        let up = CGEvent::new_keyboard_event(source.clone(), 0, false)
+            .map_err(|_| anyhow!("创建 unicode up 失败"))?;
+        up.set_flags(CGEventFlags::CGEventFlagNull);
+        up.set_string_from_utf16_unchecked(chunk);
+        mark_injected_event(&up);
+        up.post(CGEventTapLocation::HID);
+
+        start = end;
+        let cps = TYPE_SPEED_CPS.load(Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(type_chunk_delay_ms(cps, chunk_chars)));
+    }
+    Ok(())
+}
+
+/// 删掉 `text` 需要的退格次数：一次退格删一个字形（emoji、组合符），按 char 计会多删到前面的内容。
+fn backspaces_to_erase(text: &str) -> usize {
+    grapheme_clusters(text).len()
+}
+
+/// 退格删掉刚注入的文本（改写替换用）。
+fn erase_injected_text(text: &str) -> Result<()> {
+    post_backspaces(backspaces_to_erase(text))
+}
+
+fn post_backspaces(count: usize) -> Result<()> {
+    const KEY_DELETE: CGKeyCode = 0x33;
+
+    // 与文本注入共用事件源与来源标记：绕开文本替换开启时，退格与回车同样走私有事件源。
+    let source = inject_event_source()?;
+    for _ in 0..count {
+        let down = CGEvent::new_keyboard_event(source.clone(), KEY_DELETE, true)
+            .map_err(|_| anyhow!("创建 delete down 失败"))?;
+        down.set_flags(CGEventFlags::CGEventFlagNull);
+        mark_injected_event(&down);
+        down.post(CGEventTapLocation::HID);
+
+        let up = CGEvent::new_keyboard_event(source.clone(), KEY_DELETE, false)
+            .map_err(|_| anyhow!("创建 delete up 失败"))?;
+        up.set_flags(CGEventFlags::CGEventFlagNull);
+        mark_injected_event(&up);
+        up.post(CGEventTapLocation::HID);
+        std::thread::sleep(Duration::from_millis(4));
+    }
+    Ok(())
+}
+
+/// 只写剪贴板、不粘贴（文件转写等不注入的场景）。
+fn copy_text_to_clipboard(text: &str) {
+    let text = text.to_string();
+    Queue::main().exec_async(move || unsafe {
+        let pboard: id = NSPasteboard::generalPasteboard(nil);
+        if pboard == nil {
+            return;
+        }
+        pboard.clearContents();
+        let ns_text = NSString::alloc(nil).init_str(&text).autorelease();
+        pboard.setString_forType(ns_text, NSPasteboardTypeString);
+    });
+}
+
+/// 本地时间（年月日时分秒）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct LocalTime {
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+}
+
+impl LocalTime {
+    fn now() -> Self {
+        unsafe {
+            let now = libc::time(std::ptr::null_mut());
+            let mut tm: libc::tm = std::mem::zeroed();
+            libc::localtime_r(&now, &mut tm);
+            Self {
+                year: tm.tm_year + 1900,
+                month: (tm.tm_mon + 1) as u32,
+                day: tm.tm_mday as u32,
+                hour: tm.tm_hour as u32,
+                minute: tm.tm_min as u32,
+                second: tm.tm_sec as u32,
+            }
+        }
+    }
+
+    fn date_string(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    fn time_string(&self) -> String {
+        format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+/// 展开 strftime 风格的时间占位符：`%Y %m %d %H %M %S`，`%%` 为百分号，其它原样保留。
+fn expand_time_tokens(format: &str, time: &LocalTime) -> String {
+    let mut out = String::new();
+    let mut chars = format.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => out.push_str(&format!("{:04}", time.year)),
+            Some('m') => out.push_str(&format!("{:02}", time.month)),
+            Some('d') => out.push_str(&format!("{:02}", time.day)),
+            Some('H') => out.push_str(&format!("{:02}", time.hour)),
+            Some('M') => out.push_str(&format!("{:02}", time.minute)),
+            Some('S') => out.push_str(&format!("{:02}", time.second)),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+/// 展开输出文件模板中的 `{date}`（YYYY-MM-DD），再处理 `~/`。
+fn expand_output_file_template(template: &str, time: &LocalTime) -> Option<PathBuf> {
+    expand_config_path(&template.replace("{date}", &time.date_string()))
+}
+
+/// 追加到文件的一条记录：`- HH:MM:SS 文本`，多行文本的后续行缩进以留在同一条目内。
+fn format_sink_entry(text: &str, time: &LocalTime) -> String {
+    let mut lines = text.trim().lines();
+    let mut entry = format!("- {} {}\n", time.time_string(), lines.next().unwrap_or(""));
+    for line in lines {
+        entry.push_str("  ");
+        entry.push_str(line);
+        entry.push('\n');
+    }
+    entry
+}
+
+/// 把结果追加写入 `output_file`，返回实际写入的路径；目录/文件不存在时自动创建。
+fn append_to_output_file(template: &str, text: &str) -> Result<PathBuf> {
+    static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
+    let now = LocalTime::now();
+    let path = expand_output_file_template(template, &now)
+        .ok_or_else(|| anyhow!("output_file 路径无效"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("创建目录失败 {:?}", dir))?;
+    }
+    // 整条记录一次 write_all，配合 O_APPEND 保证多进程并发追加时不交错。
+    let entry = format_sink_entry(text, &now);
+    let _guard = APPEND_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("打开输出文件失败 {:?}", path))?;
+    std::io::Write::write_all(&mut file, entry.as_bytes())
+        .with_context(|| format!("写入输出文件失败 {:?}", path))?;
+    Ok(path)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PastePlan {
+    Paste,
+    PasteAndRestore,
+    TypeInstead,
+}
+
+/// 文本剪贴板照旧直接覆盖；只有原内容是图片时才按 clipboard_image 处理。
+fn paste_plan(policy: ClipboardImagePolicy, clipboard_has_image: bool) -> PastePlan {
+    if !clipboard_has_image {
+        return PastePlan::Paste;
+    }
+    match policy {
+        ClipboardImagePolicy::Restore => PastePlan::PasteAndRestore,
+        ClipboardImagePolicy::Type => PastePlan::TypeInstead,
+        ClipboardImagePolicy::Overwrite => PastePlan::Paste,
+    }
+}
+
 fn paste_via_clipboard(text: &str) -> Result<()> {
     unsafe {
         let pboard: id = NSPasteboard::generalPasteboard(nil);
@@ -55,7 +620,15 @@ fn paste_via_clipboard(text: &str) -> Result<()> {
             bail!("无法获取 NSPasteboard");
         }
 
-        // 不保存/恢复旧剪贴板，避免覆盖用户在此期间复制的内容
+        let saved = read_clipboard_item_from_pasteboard(pboard);
+        let has_image = matches!(saved, Some(ClipboardHistoryItem::Image { .. }));
+        let policy = CLIPBOARD_IMAGE_POLICY.load(Ordering::SeqCst);
+        let plan = paste_plan(ClipboardImagePolicy::from_index(policy), has_image);
+        if plan == PastePlan::TypeInstead {
+            return type_text_via_events(text);
+        }
+
+        // 文本不保存/恢复旧剪贴板，避免覆盖用户在此期间复制的内容
         pboard.clearContents();
         // 等待剪贴板清空完成
         std::thread::sleep(Duration::from_millis(20));
@@ -65,6 +638,7 @@ fn paste_via_clipboard(text: &str) -> Result<()> {
         if !ok {
             bail!("写入剪贴板失败");
         }
+        let our_change: isize = msg_send![pboard, changeCount];
         // 等待剪贴板同步完成，避免粘贴旧内容
         std::thread::sleep(Duration::from_millis(30));
 
@@ -73,6 +647,14 @@ fn paste_via_clipboard(text: &str) -> Result<()> {
         // 增加等待时间，提升在慢速应用（如终端）中的成功率
         std::thread::sleep(Duration::from_millis(350));
 
+        // 粘贴期间用户又复制了别的内容时不再恢复。
+        let current_change: isize = msg_send![pboard, changeCount];
+        if let (PastePlan::PasteAndRestore, Some(item)) = (plan, saved.as_ref()) {
+            if current_change == our_change {
+                write_clipboard_item_to_pasteboard(pboard, item);
+            }
+        }
+
         Ok(())
     }
 }
@@ -91,26 +673,416 @@ unsafe fn nsstring_to_rust(s: id) -> Option<String> {
 fn post_cmd_v() -> Result<()> {
     const KEY_V: CGKeyCode = 0x09;
 
-    let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
-        .map_err(|_| anyhow!("创建 CGEventSource 失败"))?;
+    let source = inject_event_source()?;
 
     let cmd_down = CGEvent::new_keyboard_event(source.clone(), KeyCode::COMMAND, true)
         .map_err(|_| anyhow!("创建 cmd down 失败"))?;
+    mark_injected_event(&cmd_down);
     cmd_down.post(CGEventTapLocation::HID);
 
     let v_down = CGEvent::new_keyboard_event(source.clone(), KEY_V, true)
         .map_err(|_| anyhow!("创建 v down 失败"))?;
     v_down.set_flags(CGEventFlags::CGEventFlagCommand);
+    mark_injected_event(&v_down);
     v_down.post(CGEventTapLocation::HID);
 
     let v_up = CGEvent::new_keyboard_event(source.clone(), KEY_V, false)
         .map_err(|_| anyhow!("创建 v up 失败"))?;
     v_up.set_flags(CGEventFlags::CGEventFlagCommand);
+    mark_injected_event(&v_up);
     v_up.post(CGEventTapLocation::HID);
 
     let cmd_up = CGEvent::new_keyboard_event(source, KeyCode::COMMAND, false)
         .map_err(|_| anyhow!("创建 cmd up 失败"))?;
+    mark_injected_event(&cmd_up);
     cmd_up.post(CGEventTapLocation::HID);
 
     Ok(())
 }
+
+fn post_return_key() -> Result<()> {
+    const KEY_RETURN: CGKeyCode = 0x24;
+
+    let source = inject_event_source()?;
+
+    let down = CGEvent::new_keyboard_event(source.clone(), KEY_RETURN, true)
+        .map_err(|_| anyhow!("创建 return down 失败"))?;
+    down.set_flags(CGEventFlags::CGEventFlagNull);
+    mark_injected_event(&down);
+    down.post(CGEventTapLocation::HID);
+
+    let up = CGEvent::new_keyboard_event(source, KEY_RETURN, false)
+        .map_err(|_| anyhow!("创建 return up 失败"))?;
+    up.set_flags(CGEventFlags::CGEventFlagNull);
+    mark_injected_event(&up);
+    up.post(CGEventTapLocation::HID);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod inject_tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    fn profile(bundle_id: &str, auto_send: Option<bool>) -> AppProfile {
+        AppProfile {
+            bundle_id: bundle_id.to_string(),
+            auto_send,
+            ..AppProfile::default()
+        }
+    }
+
+    #[test]
+    fn global_auto_send_presses_enter_in_chat_apps() {
+        assert!(should_press_enter(
+            true,
+            None,
+            Some("com.tinyspeck.slackmacgap"),
+            "hi"
+        ));
+        assert!(!should_press_enter(
+            false,
+            None,
+            Some("com.tinyspeck.slackmacgap"),
+            "hi"
+        ));
+    }
+
+    #[test]
+    fn global_auto_send_skips_code_editors() {
+        assert!(!should_press_enter(
+            true,
+            None,
+            Some("com.microsoft.VSCode"),
+            "hi"
+        ));
+        assert!(!should_press_enter(
+            true,
+            None,
+            Some("com.jetbrains.intellij"),
+            "hi"
+        ));
+    }
+
+    #[test]
+    fn app_profile_overrides_global_auto_send() {
+        let on = profile("com.microsoft.VSCode", Some(true));
+        assert!(should_press_enter(
+            false,
+            Some(&on),
+            Some("com.microsoft.VSCode"),
+            "hi"
+        ));
+        let off = profile("com.apple.MobileSMS", Some(false));
+        assert!(!should_press_enter(
+            true,
+            Some(&off),
+            Some("com.apple.MobileSMS"),
+            "hi"
+        ));
+        let unset = profile("com.apple.MobileSMS", None);
+        assert!(should_press_enter(
+            true,
+            Some(&unset),
+            Some("com.apple.MobileSMS"),
+            "hi"
+        ));
+    }
+
+    #[test]
+    fn blank_text_never_presses_enter() {
+        assert!(!should_press_enter(
+            true,
+            None,
+            Some("com.apple.MobileSMS"),
+            " \n"
+        ));
+    }
+
+    #[test]
+    fn zero_speed_types_as_fast_as_possible() {
+        assert_eq!(type_chunk_delay_ms(0, 1), type_chunk_delay_ms(0, 500));
+        assert!(type_chunk_delay_ms(0, 500) < 10);
+    }
+
+    #[test]
+    fn chunk_delay_follows_configured_speed() {
+        assert_eq!(type_chunk_delay_ms(20, 10), 500);
+        assert_eq!(type_chunk_delay_ms(10, 20), 2000);
+        // 速度很高时不低于最小间隔，避免事件挤在一起被丢。
+        assert_eq!(type_chunk_delay_ms(100_000, 1), type_chunk_delay_ms(0, 1));
+    }
+
+    fn sample_time() -> LocalTime {
+        LocalTime {
+            year: 2024,
+            month: 3,
+            day: 7,
+            hour: 9,
+            minute: 5,
+            second: 2,
+        }
+    }
+
+    #[test]
+    fn sink_entry_is_a_timestamped_list_item() {
+        assert_eq!(
+            format_sink_entry("  今天天气不错 \n", &sample_time()),
+            "- 09:05:02 今天天气不错\n"
+        );
+        assert_eq!(
+            format_sink_entry("第一行\n第二行", &sample_time()),
+            "- 09:05:02 第一行\n  第二行\n"
+        );
+    }
+
+    #[test]
+    fn output_file_template_substitutes_the_date() {
+        assert_eq!(
+            expand_output_file_template("/notes/{date}.md", &sample_time()),
+            Some(PathBuf::from("/notes/2024-03-07.md"))
+        );
+        assert_eq!(
+            expand_output_file_template("/notes/journal.md", &sample_time()),
+            Some(PathBuf::from("/notes/journal.md"))
+        );
+        assert_eq!(expand_output_file_template("  ", &sample_time()), None);
+        let home = expand_output_file_template("~/j/{date}.md", &sample_time());
+        if let Some(home_dir) = dirs::home_dir() {
+            assert_eq!(home, Some(home_dir.join("j/2024-03-07.md")));
+        }
+    }
+
+    #[test]
+    fn explicit_strategy_uses_only_its_own_method() {
+        assert_eq!(inject_ladder(InjectStrategy::Ax), &[InjectMethod::Ax]);
+        assert_eq!(inject_ladder(InjectStrategy::Type), &[InjectMethod::Type]);
+        assert!(inject_ladder(InjectStrategy::Paste)
+            .iter()
+            .all(|m| *m == InjectMethod::Paste));
+    }
+
+    #[test]
+    fn auto_strategy_keeps_the_ladder() {
+        assert_eq!(
+            inject_ladder(InjectStrategy::Auto),
+            inject_ladder(InjectStrategy::Paste)
+        );
+        assert_eq!(
+            ladder_for_focus(FocusKind::NativeText),
+            &[InjectMethod::Ax, InjectMethod::Paste]
+        );
+        assert_eq!(
+            ladder_for_focus(FocusKind::Unknown),
+            inject_ladder(InjectStrategy::Auto)
+        );
+    }
+
+    #[test]
+    fn inject_strategy_tokens() {
+        assert_eq!(
+            InjectStrategy::from_token(" Paste "),
+            Some(InjectStrategy::Paste)
+        );
+        assert_eq!(
+            InjectStrategy::from_token("type"),
+            Some(InjectStrategy::Type)
+        );
+        assert_eq!(InjectStrategy::from_token("ax"), Some(InjectStrategy::Ax));
+        assert_eq!(
+            InjectStrategy::from_token("auto"),
+            Some(InjectStrategy::Auto)
+        );
+        assert_eq!(InjectStrategy::from_token("clipboard"), None);
+    }
+
+    #[test]
+    fn target_app_is_found_by_bundle_id() {
+        let running = [
+            None,
+            Some("com.apple.finder".to_string()),
+            Some("com.apple.Notes".to_string()),
+            Some("com.apple.Notes".to_string()),
+        ];
+        assert_eq!(resolve_target_app("com.apple.Notes", &running), Some(2));
+        assert_eq!(resolve_target_app(" com.apple.notes ", &running), Some(2));
+    }
+
+    #[test]
+    fn target_app_not_running_or_blank() {
+        let running = [Some("com.apple.finder".to_string()), None];
+        assert_eq!(resolve_target_app("com.apple.Notes", &running), None);
+        assert_eq!(resolve_target_app("  ", &running), None);
+        assert_eq!(resolve_target_app("com.apple.finder", &[]), None);
+    }
+
+    /// 依次返回预设结果的假 AX 插入，`calls` 记录调用次数。
+    fn fake_ax<'a>(results: &'a [bool], calls: &'a Cell<usize>) -> impl FnMut() -> bool + 'a {
+        move || {
+            let i = calls.get();
+            calls.set(i + 1);
+            results.get(i).copied().unwrap_or(false)
+        }
+    }
+
+    #[test]
+    fn zero_retries_attempts_once() {
+        let calls = Cell::new(0);
+        assert!(!retry_attempts(0, 0, fake_ax(&[false, true], &calls)));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn transient_ax_failure_succeeds_on_retry() {
+        let calls = Cell::new(0);
+        assert!(retry_attempts(3, 0, fake_ax(&[false, false, true], &calls)));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn retries_stop_after_the_configured_count() {
+        let calls = Cell::new(0);
+        assert!(!retry_attempts(2, 0, fake_ax(&[false; 10], &calls)));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn focus_kind_from_ax_role() {
+        assert_eq!(
+            focus_kind_from_role(Some("AXTextArea"), None, false),
+            FocusKind::NativeText
+        );
+        assert_eq!(
+            focus_kind_from_role(Some("AXTextField"), Some("AXSearchField"), false),
+            FocusKind::NativeText
+        );
+        assert_eq!(
+            focus_kind_from_role(Some("AXTextArea"), None, true),
+            FocusKind::WebInput
+        );
+        // Chromium/Electron 的 contenteditable。
+        assert_eq!(
+            focus_kind_from_role(Some("AXGroup"), None, true),
+            FocusKind::WebInput
+        );
+        assert_eq!(
+            focus_kind_from_role(Some("AXButton"), None, false),
+            FocusKind::NonEditable
+        );
+        assert_eq!(
+            focus_kind_from_role(Some("AXGroup"), None, false),
+            FocusKind::Unknown
+        );
+        assert_eq!(focus_kind_from_role(None, None, true), FocusKind::Unknown);
+    }
+
+    #[test]
+    fn focus_kind_picks_the_most_reliable_method_first() {
+        assert_eq!(ladder_for_focus(FocusKind::NativeText)[0], InjectMethod::Ax);
+        assert!(ladder_for_focus(FocusKind::WebInput)
+            .iter()
+            .all(|m| *m == InjectMethod::Paste));
+        assert_eq!(
+            ladder_for_focus(FocusKind::NonEditable),
+            &[InjectMethod::Paste]
+        );
+    }
+
+    #[test]
+    fn time_tokens_expand_to_zero_padded_fields() {
+        let time = sample_time();
+        assert_eq!(expand_time_tokens("[%H:%M] ", &time), "[09:05] ");
+        assert_eq!(expand_time_tokens("%Y-%m-%d ", &time), "2024-03-07 ");
+        assert_eq!(expand_time_tokens("%H:%M:%S", &time), "09:05:02");
+    }
+
+    #[test]
+    fn unknown_tokens_and_literal_percent_are_kept() {
+        let time = sample_time();
+        assert_eq!(expand_time_tokens("100%% %q", &time), "100% %q");
+        assert_eq!(expand_time_tokens("50%", &time), "50%");
+        assert_eq!(expand_time_tokens("日记：", &time), "日记：");
+    }
+
+    #[test]
+    fn image_on_clipboard_follows_the_policy() {
+        assert_eq!(
+            paste_plan(ClipboardImagePolicy::Restore, true),
+            PastePlan::PasteAndRestore
+        );
+        assert_eq!(
+            paste_plan(ClipboardImagePolicy::Type, true),
+            PastePlan::TypeInstead
+        );
+        assert_eq!(
+            paste_plan(ClipboardImagePolicy::Overwrite, true),
+            PastePlan::Paste
+        );
+    }
+
+    #[test]
+    fn text_clipboard_is_pasted_over_regardless_of_policy() {
+        for policy in ClipboardImagePolicy::ALL {
+            assert_eq!(paste_plan(policy, false), PastePlan::Paste);
+        }
+    }
+
+    #[test]
+    fn text_replacement_bypass_uses_a_private_marked_source() {
+        assert!(matches!(
+            inject_event_source_state(true),
+            CGEventSourceStateID::Private
+        ));
+        assert!(matches!(
+            inject_event_source_state(false),
+            CGEventSourceStateID::HIDSystemState
+        ));
+        assert_eq!(inject_event_user_data(true), Some(INJECT_EVENT_USER_DATA));
+        assert_eq!(inject_event_user_data(false), None);
+        // 标记是 ASCII "mofa"，便于在事件监听里辨认。
+        assert_eq!(INJECT_EVENT_USER_DATA.to_be_bytes()[4..], *b"mofa");
+    }
+
+    #[test]
+    fn focus_settles_only_when_not_editable() {
+        let mut waited = Vec::new();
+        let kind = settle_focus(120, || FocusKind::NativeText, |ms| waited.push(ms));
+        assert_eq!(kind, FocusKind::NativeText);
+        assert!(waited.is_empty());
+
+        let mut probes = [FocusKind::NonEditable, FocusKind::WebInput].into_iter();
+        let kind = settle_focus(120, || probes.next().unwrap(), |ms| waited.push(ms));
+        assert_eq!(kind, FocusKind::WebInput);
+        assert_eq!(waited, vec![120]);
+    }
+
+    #[test]
+    fn focus_is_rechecked_once_and_not_with_zero_settle() {
+        let mut probes = 0;
+        let kind = settle_focus(
+            50,
+            || {
+                probes += 1;
+                FocusKind::Unknown
+            },
+            |_| {},
+        );
+        assert_eq!(kind, FocusKind::Unknown);
+        assert_eq!(probes, 2);
+
+        let mut waited = false;
+        let kind = settle_focus(0, || FocusKind::NonEditable, |_| waited = true);
+        assert_eq!(kind, FocusKind::NonEditable);
+        assert!(!waited);
+    }
+
+    #[test]
+    fn erasing_injected_text_counts_grapheme_clusters() {
+        assert_eq!(backspaces_to_erase("你好。"), 3);
+        assert_eq!(backspaces_to_erase("ok 👍🏽"), 4);
+        assert_eq!(backspaces_to_erase("cafe\u{301}"), 4);
+        assert_eq!(backspaces_to_erase("👨\u{200d}👩\u{200d}👧 🇨🇳"), 3);
+    }
+}
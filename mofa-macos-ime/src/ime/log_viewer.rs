@@ -0,0 +1,333 @@
+// 日志：无终端启动时把 stderr 写入 ~/.mofa/logs/ime.log，托盘“查看日志”窗口实时跟踪该文件。
+
+const LOG_VIEWER_WIDTH: f64 = 720.0;
+const LOG_VIEWER_HEIGHT: f64 = 460.0;
+const LOG_TAIL_LINES: usize = 500;
+const LOG_TAIL_MAX_BYTES: u64 = 256 * 1024;
+const LOG_POLL_MS: u64 = 1000;
+
+static LOG_WINDOW_PTR: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+static LOG_TEXT_VIEW_PTR: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+static LOG_VIEWER_VISIBLE: AtomicBool = AtomicBool::new(false);
+static LOG_AUTO_SCROLL: AtomicBool = AtomicBool::new(true);
+static LOG_POLLER_STARTED: AtomicBool = AtomicBool::new(false);
+
+fn ime_log_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".mofa/logs/ime.log"))
+}
+
+/// 从 Finder/登录项启动（stderr 不是终端）时，把 stderr 追加重定向到日志文件。
+fn init_file_logging() {
+    if unsafe { libc::isatty(libc::STDERR_FILENO) } == 1 {
+        return;
+    }
+    let Some(path) = ime_log_path() else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let Ok(file) = fs::OpenOptions::new().create(true).append(true).open(&path) else {
+        return;
+    };
+    use std::os::unix::io::AsRawFd;
+    unsafe {
+        libc::dup2(file.as_raw_fd(), libc::STDERR_FILENO);
+    }
+}
+
+/// 文本的最后 `n` 行（末尾换行不算一行）。
+fn last_lines(content: &str, n: usize) -> &str {
+    if n == 0 {
+        return "";
+    }
+    let body = content.strip_suffix('\n').unwrap_or(content);
+    match body.rmatch_indices('\n').nth(n - 1) {
+        Some((idx, _)) => &content[idx + 1..],
+        None => content,
+    }
+}
+
+/// 只读取文件末尾一段，丢弃被截断的首行后取最后 `n` 行。
+fn read_log_tail(path: &Path, n: usize) -> String {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let Ok(mut file) = fs::File::open(path) else {
+        return String::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(LOG_TAIL_MAX_BYTES);
+    if file.seek(SeekFrom::Start(start)).is_err() {
+        return String::new();
+    }
+    let mut buf = Vec::new();
+    if file.read_to_end(&mut buf).is_err() {
+        return String::new();
+    }
+    let text = String::from_utf8_lossy(&buf);
+    let text = if start > 0 {
+        text.split_once('\n').map(|(_, rest)| rest).unwrap_or("")
+    } else {
+        &text
+    };
+    last_lines(text, n).to_string()
+}
+
+fn open_log_viewer() {
+    Queue::main().exec_async(|| unsafe {
+        let mut window = LOG_WINDOW_PTR.load(Ordering::SeqCst) as id;
+        if window == nil {
+            match install_log_viewer_window() {
+                Ok(w) => window = w,
+                Err(e) => {
+                    eprintln!("[mofa-ime] 创建日志窗口失败: {e}");
+                    return;
+                }
+            }
+        }
+        refresh_log_view(true);
+        let app: id = msg_send![class!(NSApplication), sharedApplication];
+        let _: () = msg_send![app, activateIgnoringOtherApps: YES];
+        let _: () = msg_send![window, makeKeyAndOrderFront: nil];
+        LOG_VIEWER_VISIBLE.store(true, Ordering::SeqCst);
+    });
+    spawn_log_poller();
+}
+
+/// 窗口可见时每秒重读日志尾部；关闭窗口后只检查可见性。
+fn spawn_log_poller() {
+    if LOG_POLLER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| {
+        while !shutdown_requested() {
+            std::thread::sleep(Duration::from_millis(LOG_POLL_MS));
+            if !LOG_VIEWER_VISIBLE.load(Ordering::SeqCst) {
+                continue;
+            }
+            Queue::main().exec_async(|| unsafe {
+                let window = LOG_WINDOW_PTR.load(Ordering::SeqCst) as id;
+                let visible: BOOL = if window == nil {
+                    NO
+                } else {
+                    msg_send![window, isVisible]
+                };
+                LOG_VIEWER_VISIBLE.store(visible == YES, Ordering::SeqCst);
+                if visible == YES {
+                    refresh_log_view(false);
+                }
+            });
+        }
+    });
+}
+
+unsafe fn refresh_log_view(force: bool) {
+    static LAST_SHOWN: Mutex<String> = Mutex::new(String::new());
+
+    let text_view = LOG_TEXT_VIEW_PTR.load(Ordering::SeqCst) as id;
+    if text_view == nil {
+        return;
+    }
+    let content = ime_log_path()
+        .map(|p| read_log_tail(&p, LOG_TAIL_LINES))
+        .unwrap_or_default();
+    let mut last = LAST_SHOWN.lock().unwrap_or_else(|e| e.into_inner());
+    if !force && *last == content {
+        return;
+    }
+    let display = if content.is_empty() {
+        "（暂无日志）"
+    } else {
+        content.as_str()
+    };
+    let _: () = msg_send![text_view, setString: ns_string(display)];
+    *last = content;
+    if LOG_AUTO_SCROLL.load(Ordering::SeqCst) {
+        let _: () = msg_send![text_view, scrollToEndOfDocument: nil];
+    }
+}
+
+extern "C" fn clear_log_action(_this: &Object, _cmd: Sel, _sender: id) {
+    if let Some(path) = ime_log_path() {
+        // stderr 以 O_APPEND 打开，截断后新日志从文件开头继续写。
+        if let Ok(file) = fs::OpenOptions::new().write(true).open(&path) {
+            let _ = file.set_len(0);
+        }
+    }
+    unsafe {
+        refresh_log_view(true);
+    }
+}
+
+extern "C" fn toggle_log_auto_scroll_action(_this: &Object, _cmd: Sel, sender: id) {
+    let state: isize = unsafe { msg_send![sender, state] };
+    LOG_AUTO_SCROLL.store(state != 0, Ordering::SeqCst);
+    if state != 0 {
+        unsafe {
+            refresh_log_view(true);
+        }
+    }
+}
+
+fn log_viewer_actions() -> id {
+    static CLS: OnceLock<usize> = OnceLock::new();
+    static TARGET: OnceLock<usize> = OnceLock::new();
+    let cls = *CLS.get_or_init(|| unsafe {
+        let superclass = class!(NSObject);
+        let mut decl =
+            ClassDecl::new("MofaLogViewerActions", superclass).expect("failed to declare class");
+        decl.add_method(
+            sel!(clearLog:),
+            clear_log_action as extern "C" fn(&Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(toggleAutoScroll:),
+            toggle_log_auto_scroll_action as extern "C" fn(&Object, Sel, id),
+        );
+        (decl.register() as *const Class) as usize
+    }) as *const Class;
+    *TARGET.get_or_init(|| unsafe {
+        let obj: id = msg_send![cls, new];
+        obj as usize
+    }) as id
+}
+
+unsafe fn install_log_viewer_window() -> Result<id> {
+    // Titled | Closable | Miniaturizable | Resizable
+    const STYLE_MASK: usize = 1 | 2 | 4 | 8;
+    const BAR_HEIGHT: f64 = 40.0;
+
+    let rect = NSRect::new(
+        NSPoint::new(0.0, 0.0),
+        NSSize::new(LOG_VIEWER_WIDTH, LOG_VIEWER_HEIGHT),
+    );
+    let window: id = msg_send![class!(NSWindow), alloc];
+    let window: id = msg_send![
+        window,
+        initWithContentRect: rect
+        styleMask: STYLE_MASK
+        backing: NSBackingStoreBuffered
+        defer: NO
+    ];
+    if window == nil {
+        bail!("无法创建日志窗口");
+    }
+    let _: () = msg_send![window, setTitle: ns_string("MoFA IME 日志")];
+    let _: () = msg_send![window, setReleasedWhenClosed: NO];
+    let _: () = msg_send![window, center];
+
+    let content = window.contentView();
+    if content == nil {
+        bail!("日志窗口 contentView 为空");
+    }
+
+    let scroll_view: id = msg_send![class!(NSScrollView), alloc];
+    let scroll_view: id = msg_send![
+        scroll_view,
+        initWithFrame: NSRect::new(
+            NSPoint::new(0.0, BAR_HEIGHT),
+            NSSize::new(LOG_VIEWER_WIDTH, LOG_VIEWER_HEIGHT - BAR_HEIGHT),
+        )
+    ];
+    let _: () = msg_send![scroll_view, setHasVerticalScroller: YES];
+    // NSViewWidthSizable | NSViewHeightSizable
+    let _: () = msg_send![scroll_view, setAutoresizingMask: 2usize | 16usize];
+
+    let text_view: id = msg_send![class!(NSTextView), alloc];
+    let text_view: id = msg_send![
+        text_view,
+        initWithFrame: NSRect::new(
+            NSPoint::new(0.0, 0.0),
+            NSSize::new(LOG_VIEWER_WIDTH, LOG_VIEWER_HEIGHT - BAR_HEIGHT),
+        )
+    ];
+    let _: () = msg_send![text_view, setEditable: NO];
+    let _: () = msg_send![text_view, setSelectable: YES];
+    let _: () = msg_send![text_view, setAutoresizingMask: 2usize];
+    let mono: id = msg_send![
+        class!(NSFont),
+        monospacedSystemFontOfSize: 11.0f64
+        weight: 0.0f64
+    ];
+    let _: () = msg_send![text_view, setFont: mono];
+    let _: () = msg_send![scroll_view, setDocumentView: text_view];
+    content.addSubview_(scroll_view);
+
+    let actions = log_viewer_actions();
+
+    // NSButtonTypeSwitch = 3
+    let auto_scroll = NSButton::initWithFrame_(
+        NSButton::alloc(nil),
+        NSRect::new(NSPoint::new(12.0, 8.0), NSSize::new(120.0, 24.0)),
+    );
+    let _: () = msg_send![auto_scroll, setButtonType: 3usize];
+    let _: () = msg_send![auto_scroll, setTitle: ns_string("自动滚动")];
+    let on: isize = if LOG_AUTO_SCROLL.load(Ordering::SeqCst) {
+        1
+    } else {
+        0
+    };
+    let _: () = msg_send![auto_scroll, setState: on];
+    let _: () = msg_send![auto_scroll, setTarget: actions];
+    let _: () = msg_send![auto_scroll, setAction: sel!(toggleAutoScroll:)];
+    content.addSubview_(auto_scroll);
+
+    // NSViewMinXMargin，窗口拉宽时按钮保持在右侧。
+    let clear_btn = NSButton::initWithFrame_(
+        NSButton::alloc(nil),
+        NSRect::new(
+            NSPoint::new(LOG_VIEWER_WIDTH - 92.0, 6.0),
+            NSSize::new(80.0, 28.0),
+        ),
+    );
+    let _: () = msg_send![clear_btn, setBezelStyle: 1usize];
+    let _: () = msg_send![clear_btn, setTitle: ns_string("清空")];
+    let _: () = msg_send![clear_btn, setAutoresizingMask: 1usize];
+    let _: () = msg_send![clear_btn, setTarget: actions];
+    let _: () = msg_send![clear_btn, setAction: sel!(clearLog:)];
+    content.addSubview_(clear_btn);
+
+    LOG_WINDOW_PTR.store(window as usize, Ordering::SeqCst);
+    LOG_TEXT_VIEW_PTR.store(text_view as usize, Ordering::SeqCst);
+    Ok(window)
+}
+
+#[cfg(test)]
+mod log_viewer_tests {
+    use super::*;
+
+    #[test]
+    fn last_lines_keeps_the_tail() {
+        let log = "a\nb\nc\nd\n";
+        assert_eq!(last_lines(log, 2), "c\nd\n");
+        assert_eq!(last_lines(log, 4), log);
+        assert_eq!(last_lines(log, 10), log);
+        assert_eq!(last_lines(log, 0), "");
+        assert_eq!(last_lines("a\nb", 1), "b");
+        assert_eq!(last_lines("", 3), "");
+    }
+
+    #[test]
+    fn tail_of_a_large_file_drops_the_partial_first_line() {
+        let path = std::env::temp_dir().join(format!("mofa-log-tail-{}.log", std::process::id()));
+        let mut content = String::new();
+        let mut i = 0;
+        while (content.len() as u64) < LOG_TAIL_MAX_BYTES * 2 {
+            content.push_str(&format!("line {i}\n"));
+            i += 1;
+        }
+        fs::write(&path, &content).unwrap();
+
+        let tail = read_log_tail(&path, 3);
+        assert_eq!(
+            tail,
+            format!("line {}\nline {}\nline {}\n", i - 3, i - 2, i - 1)
+        );
+        let everything = read_log_tail(&path, usize::MAX);
+        assert!(everything.lines().all(|l| l.starts_with("line ")));
+        assert!(!everything.starts_with("line 0\n"));
+
+        let _ = fs::remove_file(&path);
+        assert_eq!(read_log_tail(&path, 3), "");
+    }
+}
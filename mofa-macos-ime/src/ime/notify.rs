@@ -0,0 +1,73 @@
+// 系统通知：输入法结果展示与设置器下载完成共用，经 osascript 发出，无需额外权限声明。
+
+/// 通知正文的最大字数，超出部分以省略号结尾（通知中心本身也只显示前几行）。
+const NOTIFICATION_BODY_MAX_CHARS: usize = 200;
+
+/// 合并空白为单个空格，超过 `max_chars` 个字符时截断并加 `…`。
+fn notification_body(text: &str, max_chars: usize) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= max_chars {
+        return flat;
+    }
+    let mut out: String = flat.chars().take(max_chars.saturating_sub(1)).collect();
+    out = out.trim_end().to_string();
+    out.push('…');
+    out
+}
+
+/// AppleScript 字符串字面量转义。
+fn applescript_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// 发出一条系统通知；osascript 在后台线程里跑（冷启动可能要几百毫秒），调用方不等待，
+/// 失败只记日志，不影响调用方流程。
+fn post_notification(title: &str, body: &str) {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_quote(&notification_body(body, NOTIFICATION_BODY_MAX_CHARS)),
+        applescript_quote(title)
+    );
+    std::thread::spawn(move || {
+        match std::process::Command::new("/usr/bin/osascript")
+            .arg("-e")
+            .arg(&script)
+            .output()
+        {
+            Ok(out) if out.status.success() => {}
+            Ok(out) => eprintln!(
+                "[mofa-ime] 系统通知失败: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            ),
+            Err(e) => eprintln!("[mofa-ime] 系统通知失败: {e}"),
+        }
+    });
+}
+
+#[cfg(test)]
+mod notify_tests {
+    use super::*;
+
+    #[test]
+    fn long_result_is_flattened_and_truncated_with_ellipsis() {
+        let long = format!("第一行\n\n{}", "很长的结果".repeat(60));
+        let body = notification_body(&long, NOTIFICATION_BODY_MAX_CHARS);
+        assert_eq!(body.chars().count(), NOTIFICATION_BODY_MAX_CHARS);
+        assert!(body.starts_with("第一行 很长的结果"));
+        assert!(body.ends_with('…'));
+    }
+
+    #[test]
+    fn short_result_is_kept_and_truncation_drops_trailing_space() {
+        assert_eq!(notification_body("  hello \n world ", 20), "hello world");
+        assert_eq!(notification_body("hello world again", 7), "hello…");
+    }
+
+    #[test]
+    fn applescript_quote_escapes_quotes_and_backslashes() {
+        assert_eq!(
+            applescript_quote(r#"say "hi" \ bye"#),
+            r#""say \"hi\" \\ bye""#
+        );
+    }
+}
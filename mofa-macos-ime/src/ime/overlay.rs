@@ -12,12 +12,95 @@ const OVERLAY_PREVIEW_MAX_LINES: usize = 6;
 const OVERLAY_PREVIEW_LINE_HEIGHT: f64 = 17.0;
 const OVERLAY_PREVIEW_MIN_HEIGHT: f64 = 20.0;
 const OVERLAY_PREVIEW_LINE_CAP: f32 = 24.0;
+// 预览标签宽度（与 layout_overlay_window 一致），实测换行时再扣掉文本框左右内边距。
+const OVERLAY_PREVIEW_WIDTH: f64 =
+    OVERLAY_WIDTH - OVERLAY_STATUS_BADGE_X - OVERLAY_STATUS_BADGE_WIDTH - 26.0;
+const OVERLAY_PREVIEW_TEXT_INSET: f64 = 4.0;
 const OVERLAY_MAX_HEIGHT: f64 = 158.0;
+const OVERLAY_PINYIN_LINE_HEIGHT: f64 = 16.0;
 const ASR_PREVIEW_HOLD_MS: u64 = 900;
 const RESULT_OVERLAY_HOLD_MS: u64 = 950;
 const OVERLAY_FADE_TOTAL_MS: u64 = 120;
 const OVERLAY_FADE_STEPS: u64 = 4;
-const SILENCE_RMS_THRESHOLD: f32 = 0.0015;
+const OVERLAY_HOVER_DWELL_MS: u64 = 350;
+const OVERLAY_HOVER_POLL_MS: u64 = 80;
+
+/// 淡出节奏：每步间隔毫秒数与逐步递减的 alpha（从略低于 1.0 降到 0.0）。
+fn overlay_fade_schedule(total_ms: u64, steps: u64) -> (u64, Vec<f64>) {
+    let steps = steps.max(1);
+    let step_ms = (total_ms / steps).max(1);
+    let alphas = (0..steps)
+        .rev()
+        .map(|idx| idx as f64 / steps as f64)
+        .collect();
+    (step_ms, alphas)
+}
+
+/// 主浮层外观（`overlay_radius=` / `overlay_opacity=` / `overlay_high_contrast=`）。
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct OverlayStyle {
+    corner_radius: f64,
+    opacity: f64,
+    high_contrast: bool,
+}
+
+impl Default for OverlayStyle {
+    fn default() -> Self {
+        Self {
+            corner_radius: 15.0,
+            opacity: 0.93,
+            high_contrast: false,
+        }
+    }
+}
+
+/// 高对比度模式：不透明纯黑底、白字、粗白边框，忽略 `overlay_opacity`。
+const HIGH_CONTRAST_BACKGROUND: (f64, f64, f64) = (0.0, 0.0, 0.0);
+const HIGH_CONTRAST_TEXT: (f64, f64, f64) = (1.0, 1.0, 1.0);
+const HIGH_CONTRAST_BORDER_WIDTH: f64 = 3.0;
+/// WCAG AA 正文最低对比度。
+const WCAG_AA_CONTRAST: f64 = 4.5;
+
+/// 状态徽标按当前浮层是否高对比度选配色；随浮层重建更新。
+static OVERLAY_HIGH_CONTRAST: AtomicBool = AtomicBool::new(false);
+
+/// WCAG 2.x 相对亮度，分量为 0–1 的 sRGB。
+fn relative_luminance((r, g, b): (f64, f64, f64)) -> f64 {
+    let linear = |c: f64| {
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// WCAG 对比度 (L1 + 0.05) / (L2 + 0.05)，范围 1–21，与参数顺序无关。
+fn contrast_ratio(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+fn meets_wcag_aa(text: (f64, f64, f64), background: (f64, f64, f64)) -> bool {
+    contrast_ratio(text, background) >= WCAG_AA_CONTRAST
+}
+
+/// 圆角限制在 [0, 高度/2]，再大就不是圆角矩形了。
+fn clamp_overlay_radius(radius: f64, height: f64) -> f64 {
+    if radius.is_nan() {
+        return 0.0;
+    }
+    radius.clamp(0.0, height * 0.5)
+}
+
+/// 不透明度下限 0.3，避免浮层文字看不清。
+fn clamp_overlay_opacity(opacity: f64) -> f64 {
+    if opacity.is_nan() {
+        return 1.0;
+    }
+    opacity.clamp(0.3, 1.0)
+}
 
 // History window constants
 const HISTORY_WIDTH: f64 = 280.0;
@@ -26,10 +109,70 @@ const HISTORY_MARGIN: f64 = 24.0;
 const HISTORY_MIN_HEIGHT: f64 = 120.0;
 const HISTORY_ITEM_HEIGHT: f64 = 32.0;
 const CLIPBOARD_ITEM_HEIGHT: f64 = 32.0;
+const SNIPPET_ITEM_HEIGHT: f64 = 32.0;
 
 // Floating orb constants
 const ORB_SIZE: f64 = 48.0;
 const ORB_MARGIN: f64 = 16.0;
+const ORB_IDLE_ALPHA: f64 = 0.25;
+const ORB_IDLE_FADE_MS: u64 = 400;
+const ORB_WAKE_DISTANCE: f64 = 48.0;
+const ORB_AUTO_HIDE_POLL_MS: u64 = 100;
+
+// 最近一次活动（流水线状态更新或鼠标靠近悬浮球）的时间戳，毫秒。
+static ORB_LAST_ACTIVITY_MS: AtomicU64 = AtomicU64::new(0);
+
+fn note_orb_activity() {
+    ORB_LAST_ACTIVITY_MS.store(current_time_ms(), Ordering::SeqCst);
+}
+
+/// 空闲 `hide_after_ms` 后在 ORB_IDLE_FADE_MS 内线性淡到 ORB_IDLE_ALPHA；0 表示不自动隐藏。
+fn orb_idle_alpha(idle_ms: u64, hide_after_ms: u64) -> f64 {
+    if hide_after_ms == 0 || idle_ms < hide_after_ms {
+        return 1.0;
+    }
+    let t = ((idle_ms - hide_after_ms) as f64 / ORB_IDLE_FADE_MS as f64).min(1.0);
+    1.0 - (1.0 - ORB_IDLE_ALPHA) * t
+}
+
+/// 鼠标到矩形的距离（在矩形内为 0）。
+fn distance_to_frame(p: NSPoint, frame: NSRect) -> f64 {
+    let dx = (frame.origin.x - p.x)
+        .max(p.x - (frame.origin.x + frame.size.width))
+        .max(0.0);
+    let dy = (frame.origin.y - p.y)
+        .max(p.y - (frame.origin.y + frame.size.height))
+        .max(0.0);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// orb_auto_hide_ms>0 时启动：空闲后把悬浮球调淡（仍可点击），鼠标靠近或有新状态时恢复。
+fn spawn_orb_auto_hide_watcher(hide_after_ms: u64) {
+    note_orb_activity();
+    std::thread::spawn(move || loop {
+        if shutdown_requested() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(ORB_AUTO_HIDE_POLL_MS));
+        Queue::main().exec_async(move || unsafe {
+            let window = ORB_WINDOW_PTR.load(Ordering::SeqCst) as id;
+            if window == nil {
+                return;
+            }
+            let mouse: NSPoint = msg_send![class!(NSEvent), mouseLocation];
+            if distance_to_frame(mouse, NSWindow::frame(window)) <= ORB_WAKE_DISTANCE {
+                note_orb_activity();
+            }
+            let idle_ms =
+                current_time_ms().saturating_sub(ORB_LAST_ACTIVITY_MS.load(Ordering::SeqCst));
+            let alpha = orb_idle_alpha(idle_ms, hide_after_ms);
+            let current: f64 = msg_send![window, alphaValue];
+            if (current - alpha).abs() > 0.01 {
+                let _: () = msg_send![window, setAlphaValue: alpha];
+            }
+        });
+    });
+}
 
 // Global state for orb click handling
 static ORB_CLICK_TX: OnceLock<std::sync::mpsc::Sender<OrbCommand>> = OnceLock::new();
@@ -39,12 +182,28 @@ static ORB_WINDOW_PTR: std::sync::atomic::AtomicUsize = std::sync::atomic::Atomi
 const MAX_HISTORY_ITEMS: usize = 50;
 const MAX_CLIPBOARD_ITEMS: usize = 50;
 const CLIPBOARD_POLL_INTERVAL_MS: u64 = 450;
+// 剪贴板历史里图片的最长边（像素），更大的截图先缩小并转成 PNG 再保存。
+const CLIPBOARD_IMAGE_MAX_EDGE: usize = 2048;
+
+// history_audio=true 时最近几条历史附带录音（16k WAV），超出条数或时长的不保存，控制磁盘占用。
+const HISTORY_AUDIO_MAX_CLIPS: usize = 10;
+const HISTORY_AUDIO_MAX_SECS: usize = 60;
+
+struct HistoryEntry {
+    text: String,
+    /// 录音旁车文件，位于 `~/.mofa/history-audio/`
+    audio: Option<PathBuf>,
+}
 
-fn history_items() -> &'static Mutex<Vec<String>> {
-    static HISTORY: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+fn history_items() -> &'static Mutex<Vec<HistoryEntry>> {
+    static HISTORY: OnceLock<Mutex<Vec<HistoryEntry>>> = OnceLock::new();
     HISTORY.get_or_init(|| Mutex::new(Vec::new()))
 }
 
+fn history_audio_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".mofa").join("history-audio"))
+}
+
 #[derive(Clone)]
 enum ClipboardHistoryItem {
     Text(String),
@@ -64,9 +223,14 @@ fn history_tab_state() -> &'static std::sync::atomic::AtomicUsize {
     HISTORY_TAB.get_or_init(|| std::sync::atomic::AtomicUsize::new(0))
 }
 
+// 历史窗口页签：最近输入 / 剪切板 / 常用
+const HISTORY_TAB_COUNT: usize = 3;
+const HISTORY_TAB_SNIPPETS: usize = 2;
+const HISTORY_TAB_WIDTH: f64 = 216.0;
+
 fn normalize_history_tab(index: usize) -> usize {
-    if index == 1 {
-        1
+    if index < HISTORY_TAB_COUNT {
+        index
     } else {
         0
     }
@@ -80,6 +244,23 @@ fn set_history_tab_index(index: usize) {
     history_tab_state().store(normalize_history_tab(index), Ordering::Relaxed);
 }
 
+/// `~/.mofa/snippets.txt`：每行一条常用文本，`#` 开头为注释；`\n` 表示换行（多行签名等）。
+fn parse_snippets(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.replace("\\n", "\n"))
+        .collect()
+}
+
+/// 每次展示列表或点击条目时重新读取，编辑文件后无需重启。
+fn load_snippets() -> Vec<String> {
+    fs::read_to_string(snippets_path())
+        .map(|content| parse_snippets(&content))
+        .unwrap_or_default()
+}
+
 fn get_clipboard_items() -> Vec<ClipboardHistoryItem> {
     clipboard_items().lock().unwrap().clone()
 }
@@ -132,9 +313,117 @@ fn push_clipboard_item(item: ClipboardHistoryItem) -> bool {
     if items.len() > MAX_CLIPBOARD_ITEMS {
         items.pop();
     }
+    let max_bytes = CLIPBOARD_IMAGE_MAX_BYTES.load(Ordering::SeqCst) as usize;
+    evict_clipboard_images(&mut items, max_bytes);
     true
 }
 
+/// 图片总字节超过 `max_bytes` 时从最旧的图片开始移除，文本条目不受影响；最新一条总是保留。
+fn evict_clipboard_images(items: &mut Vec<ClipboardHistoryItem>, max_bytes: usize) {
+    let image_len = |item: &ClipboardHistoryItem| match item {
+        ClipboardHistoryItem::Image { data, .. } => data.len(),
+        ClipboardHistoryItem::Text(_) => 0,
+    };
+    let mut total: usize = items.iter().map(image_len).sum();
+    let mut i = items.len();
+    while total > max_bytes && i > 1 {
+        i -= 1;
+        let len = image_len(&items[i]);
+        if len > 0 {
+            total -= len;
+            items.remove(i);
+        }
+    }
+}
+
+/// 最长边超过 `max_edge` 时按比例缩小后的像素尺寸；无需缩小时为 None。
+fn clipboard_image_scaled_size(
+    width: usize,
+    height: usize,
+    max_edge: usize,
+) -> Option<(usize, usize)> {
+    let edge = width.max(height);
+    if edge <= max_edge || edge == 0 {
+        return None;
+    }
+    let scale = max_edge as f64 / edge as f64;
+    let scaled = |v: usize| ((v as f64 * scale).round() as usize).max(1);
+    Some((scaled(width), scaled(height)))
+}
+
+/// 过大的图片重绘为较小的 PNG 再存入剪贴板历史；解码或绘制失败时原样保留。须在主线程调用。
+unsafe fn shrink_clipboard_image(item: ClipboardHistoryItem) -> ClipboardHistoryItem {
+    let ClipboardHistoryItem::Image { data, .. } = &item else {
+        return item;
+    };
+    let ns_data: id = msg_send![
+        class!(NSData),
+        dataWithBytes: data.as_ptr()
+        length: data.len()
+    ];
+    if ns_data == nil {
+        return item;
+    }
+    let source: id = msg_send![class!(NSBitmapImageRep), imageRepWithData: ns_data];
+    if source == nil {
+        return item;
+    }
+    let width: isize = msg_send![source, pixelsWide];
+    let height: isize = msg_send![source, pixelsHigh];
+    let Some((w, h)) = clipboard_image_scaled_size(
+        width.max(0) as usize,
+        height.max(0) as usize,
+        CLIPBOARD_IMAGE_MAX_EDGE,
+    ) else {
+        return item;
+    };
+
+    let target: id = msg_send![class!(NSBitmapImageRep), alloc];
+    let target: id = msg_send![
+        target,
+        initWithBitmapDataPlanes: std::ptr::null_mut::<*mut u8>()
+        pixelsWide: w as isize
+        pixelsHigh: h as isize
+        bitsPerSample: 8isize
+        samplesPerPixel: 4isize
+        hasAlpha: YES
+        isPlanar: NO
+        colorSpaceName: ns_string("NSCalibratedRGBColorSpace")
+        bytesPerRow: 0isize
+        bitsPerPixel: 0isize
+    ];
+    if target == nil {
+        return item;
+    }
+    let target: id = msg_send![target, autorelease];
+    let context: id =
+        msg_send![class!(NSGraphicsContext), graphicsContextWithBitmapImageRep: target];
+    if context == nil {
+        return item;
+    }
+    let _: () = msg_send![class!(NSGraphicsContext), saveGraphicsState];
+    let _: () = msg_send![class!(NSGraphicsContext), setCurrentContext: context];
+    let rect = NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new(w as f64, h as f64));
+    let _: BOOL = msg_send![source, drawInRect: rect];
+    let _: () = msg_send![class!(NSGraphicsContext), restoreGraphicsState];
+
+    // NSBitmapImageFileTypePNG == 4
+    let empty: id = msg_send![class!(NSDictionary), dictionary];
+    let png: id = msg_send![target, representationUsingType: 4usize properties: empty];
+    if png == nil {
+        return item;
+    }
+    let len: usize = msg_send![png, length];
+    let bytes: *const u8 = msg_send![png, bytes];
+    if len == 0 || bytes.is_null() {
+        return item;
+    }
+    ClipboardHistoryItem::Image {
+        data: std::slice::from_raw_parts(bytes, len).to_vec(),
+        uti: "public.png".to_string(),
+    }
+}
+
 unsafe fn read_clipboard_item_from_pasteboard(pasteboard: id) -> Option<ClipboardHistoryItem> {
     if pasteboard == nil {
         return None;
@@ -176,12 +465,40 @@ unsafe fn read_clipboard_item_from_pasteboard(pasteboard: id) -> Option<Clipboar
     None
 }
 
+/// 清空剪贴板并写入一条文本或图片。
+unsafe fn write_clipboard_item_to_pasteboard(pasteboard: id, item: &ClipboardHistoryItem) {
+    let _: () = msg_send![pasteboard, clearContents];
+
+    match item {
+        ClipboardHistoryItem::Text(text) => {
+            let ns_string = NSString::alloc(nil).init_str(text).autorelease();
+            let _: BOOL =
+                msg_send![pasteboard, setString: ns_string forType: NSPasteboardTypeString];
+        }
+        ClipboardHistoryItem::Image { data, uti } => {
+            let ns_data: id = msg_send![
+                class!(NSData),
+                dataWithBytes: data.as_ptr()
+                length: data.len()
+            ];
+            if ns_data != nil {
+                let _: BOOL = msg_send![
+                    pasteboard,
+                    setData: ns_data
+                    forType: ns_string(uti)
+                ];
+            }
+        }
+    }
+}
+
 fn apply_history_tab_ui(
     tab_index: usize,
     tab_control: id,
     title_label: id,
     history_scroll_view: id,
     clipboard_scroll_view: id,
+    snippet_scroll_view: id,
 ) {
     unsafe {
         let normalized = normalize_history_tab(tab_index);
@@ -196,8 +513,20 @@ fn apply_history_tab_ui(
             let hidden = if normalized == 1 { NO } else { YES };
             let _: () = msg_send![clipboard_scroll_view, setHidden: hidden];
         }
+        if snippet_scroll_view != nil {
+            let hidden = if normalized == HISTORY_TAB_SNIPPETS {
+                NO
+            } else {
+                YES
+            };
+            let _: () = msg_send![snippet_scroll_view, setHidden: hidden];
+        }
         if title_label != nil {
-            let title = if normalized == 0 { "最近输入" } else { "剪切板" };
+            let title = match normalized {
+                0 => "最近输入",
+                1 => "剪切板",
+                _ => "常用",
+            };
             let _: () = msg_send![title_label, setStringValue: ns_string(title)];
         }
     }
@@ -213,64 +542,234 @@ fn clipboard_uti_label(uti: &str) -> &'static str {
 }
 
 pub fn add_history_item(text: &str, overlay: OverlayHandle) {
-    if text.trim().is_empty() {
+    add_history_item_with_audio(text, None, overlay);
+}
+
+/// 隐私模式下不记任何历史；空白文本也不记。
+fn should_record_history(text: &str, privacy: bool) -> bool {
+    !privacy && !text.trim().is_empty()
+}
+
+/// 同 `add_history_item`；给出 `samples` 时把本段录音存为旁车文件供历史窗口回放。
+fn add_history_item_with_audio(text: &str, samples: Option<&[f32]>, overlay: OverlayHandle) {
+    if !should_record_history(text, privacy_mode()) {
         return;
     }
+    let audio = samples.and_then(|samples| {
+        save_history_audio(samples)
+            .map_err(|e| eprintln!("[mofa-ime] 保存历史录音失败: {e:#}"))
+            .ok()
+            .flatten()
+    });
     let mut items = history_items().lock().unwrap();
-    items.insert(0, text.to_string());
+    items.insert(
+        0,
+        HistoryEntry {
+            text: text.to_string(),
+            audio,
+        },
+    );
+    let mut stale = evict_history_audio(&mut items, HISTORY_AUDIO_MAX_CLIPS);
     if items.len() > MAX_HISTORY_ITEMS {
-        items.pop();
+        stale.extend(items.pop().and_then(|e| e.audio));
     }
     // Refresh history window if it's visible
     drop(items); // Release lock before calling refresh
+    remove_history_audio_files(&stale);
     overlay.refresh_history_if_visible();
 }
 
+/// 只有最新的 `keep` 条保留录音，其余条目摘下录音路径（由调用方删除文件）。
+fn evict_history_audio(entries: &mut [HistoryEntry], keep: usize) -> Vec<PathBuf> {
+    entries
+        .iter_mut()
+        .filter(|e| e.audio.is_some())
+        .skip(keep)
+        .filter_map(|e| e.audio.take())
+        .collect()
+}
+
+fn save_history_audio(samples: &[f32]) -> Result<Option<PathBuf>> {
+    let dir = history_audio_dir().ok_or_else(|| anyhow!("无法定位家目录"))?;
+    save_history_audio_in(&dir, samples)
+}
+
+/// 超过时长上限的录音不保存（返回 None）。
+fn save_history_audio_in(dir: &Path, samples: &[f32]) -> Result<Option<PathBuf>> {
+    if samples.is_empty() || samples.len() > HISTORY_AUDIO_MAX_SECS * CAPTURE_TARGET_RATE as usize {
+        return Ok(None);
+    }
+    fs::create_dir_all(dir).with_context(|| format!("创建目录失败 {:?}", dir))?;
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let path = dir.join(format!("{stamp}.wav"));
+    mofa_input::audio::write_wav(&path, samples, CAPTURE_TARGET_RATE)
+        .with_context(|| format!("写入失败 {:?}", path))?;
+    Ok(Some(path))
+}
+
+fn remove_history_audio_files(paths: &[PathBuf]) {
+    for path in paths {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 历史只在内存中，启动时清掉上次运行留下的录音。
+fn clear_history_audio_dir() {
+    if let Some(dir) = history_audio_dir() {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
+
 pub fn get_history_items() -> Vec<String> {
-    history_items().lock().unwrap().clone()
+    history_items()
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| e.text.clone())
+        .collect()
+}
+
+fn history_audio_path(index: usize) -> Option<PathBuf> {
+    history_items()
+        .lock()
+        .unwrap()
+        .get(index)
+        .and_then(|e| e.audio.clone())
 }
 
 pub fn clear_history() {
-    history_items().lock().unwrap().clear();
+    let stale: Vec<PathBuf> = history_items()
+        .lock()
+        .unwrap()
+        .drain(..)
+        .filter_map(|e| e.audio)
+        .collect();
+    remove_history_audio_files(&stale);
 }
 
 fn spawn_clipboard_watcher(overlay: OverlayHandle) {
+    // 读取剪贴板必须在主线程，但这里不等待主线程返回：
+    // 主线程忙（模态框、淡出动画）时跳过本轮，避免与流水线的 UI 更新互相阻塞。
+    static LAST_CHANGE_COUNT: AtomicIsize = AtomicIsize::new(-1);
+    static SAMPLE_IN_FLIGHT: AtomicBool = AtomicBool::new(false);
+
     std::thread::spawn(move || {
-        let mut last_change_count: isize = -1;
-        loop {
+        while !shutdown_requested() {
             std::thread::sleep(Duration::from_millis(CLIPBOARD_POLL_INTERVAL_MS));
-            let sample = Queue::main().exec_sync(move || unsafe {
-                let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
-                if pasteboard == nil {
-                    return None;
-                }
-                let change_count: isize = msg_send![pasteboard, changeCount];
-                let item = read_clipboard_item_from_pasteboard(pasteboard);
-                Some((change_count, item))
-            });
-
-            let Some((change_count, item_opt)) = sample else {
+            if privacy_mode() {
                 continue;
-            };
-            if change_count == last_change_count {
+            }
+            if SAMPLE_IN_FLIGHT.swap(true, Ordering::AcqRel) {
                 continue;
             }
-            last_change_count = change_count;
+            Queue::main().exec_async(move || {
+                let item_opt = unsafe {
+                    let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+                    if pasteboard == nil {
+                        None
+                    } else {
+                        let change_count: isize = msg_send![pasteboard, changeCount];
+                        if LAST_CHANGE_COUNT.swap(change_count, Ordering::AcqRel) == change_count {
+                            None
+                        } else {
+                            read_clipboard_item_from_pasteboard(pasteboard)
+                                .map(|item| shrink_clipboard_image(item))
+                        }
+                    }
+                };
+                SAMPLE_IN_FLIGHT.store(false, Ordering::Release);
 
-            let mut changed = false;
-            if let Some(item) = item_opt {
-                changed = push_clipboard_item(item);
-            }
-            if changed {
-                overlay.refresh_history_if_visible();
-            }
+                if let Some(item) = item_opt {
+                    if push_clipboard_item(item) {
+                        overlay.refresh_history_if_visible();
+                    }
+                }
+            });
         }
     });
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum OrbCommand {
     ToggleHistory,
+    /// 拖到悬浮球上的音频文件
+    TranscribeFile(PathBuf),
+    /// 托盘菜单“重新润色上一段”
+    RepolishLast,
+}
+
+const FILE_URL_PASTEBOARD_TYPE: &str = "public.file-url";
+
+/// 悬浮球接受的拖放文件：与命令行转写相同（WAV 与 m4a/caf/aac）。
+fn is_droppable_audio_file(path: &Path) -> bool {
+    is_cli_audio_file(path)
+}
+
+unsafe fn dragged_file_path(dragging_info: id) -> Option<PathBuf> {
+    let pasteboard: id = msg_send![dragging_info, draggingPasteboard];
+    pasteboard_file_path(pasteboard)
+}
+
+unsafe fn pasteboard_file_path(pasteboard: id) -> Option<PathBuf> {
+    if pasteboard == nil {
+        return None;
+    }
+    let url_string: id = msg_send![pasteboard, stringForType: ns_string(FILE_URL_PASTEBOARD_TYPE)];
+    if url_string == nil {
+        return None;
+    }
+    let url: id = msg_send![class!(NSURL), URLWithString: url_string];
+    if url == nil {
+        return None;
+    }
+    // file reference URL（/.file/id=...）需先转成路径 URL
+    let file_url: id = msg_send![url, filePathURL];
+    let path: id = msg_send![if file_url != nil { file_url } else { url }, path];
+    nsstring_to_rust(path).map(PathBuf::from)
+}
+
+/// 剪贴板里的音频文件：优先 Finder 复制的文件，其次复制的路径文本（可带引号、`~`、`file://`）。
+fn clipboard_audio_path(
+    file_url_path: Option<PathBuf>,
+    text: Option<&str>,
+    home: Option<&Path>,
+) -> Option<PathBuf> {
+    if let Some(path) = file_url_path.filter(|p| is_cli_audio_file(p)) {
+        return Some(path);
+    }
+    let text = text?.trim().trim_matches(|c| c == '"' || c == '\'');
+    if text.contains('\n') {
+        return None;
+    }
+    let text = text.strip_prefix("file://").unwrap_or(text);
+    let path = match text.strip_prefix("~/") {
+        Some(rest) => home?.join(rest),
+        None => PathBuf::from(text),
+    };
+    (path.is_absolute() && is_cli_audio_file(&path)).then_some(path)
+}
+
+/// 在主线程读取剪贴板，返回其中的音频文件路径（不检查文件是否存在）。
+fn read_clipboard_audio_path() -> Option<PathBuf> {
+    Queue::main().exec_sync(|| unsafe {
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        if pasteboard == nil {
+            return None;
+        }
+        let text = match read_clipboard_item_from_pasteboard(pasteboard) {
+            Some(ClipboardHistoryItem::Text(text)) => Some(text),
+            _ => None,
+        };
+        let home = dirs::home_dir();
+        clipboard_audio_path(
+            pasteboard_file_path(pasteboard),
+            text.as_deref(),
+            home.as_deref(),
+        )
+    })
 }
 
 #[repr(C)]
@@ -329,7 +828,7 @@ fn get_orb_delegate_class() -> &'static objc::runtime::Class {
     })
 }
 
-unsafe fn visible_frame() -> NSRect {
+unsafe fn main_visible_frame() -> NSRect {
     let screen: id = msg_send![class!(NSScreen), mainScreen];
     if screen != nil {
         let frame: NSRect = msg_send![screen, visibleFrame];
@@ -339,6 +838,85 @@ unsafe fn visible_frame() -> NSRect {
     }
 }
 
+/// 所有显示器的 (frame, visibleFrame)；第 0 个为主显示器（菜单栏所在）。
+unsafe fn screen_frames() -> Vec<(NSRect, NSRect)> {
+    let screens: id = msg_send![class!(NSScreen), screens];
+    if screens == nil {
+        return Vec::new();
+    }
+    let count: usize = msg_send![screens, count];
+    (0..count)
+        .map(|i| {
+            let screen: id = msg_send![screens, objectAtIndex: i];
+            let frame: NSRect = msg_send![screen, frame];
+            let visible: NSRect = msg_send![screen, visibleFrame];
+            (frame, visible)
+        })
+        .collect()
+}
+
+fn rect_intersection_area(a: NSRect, b: NSRect) -> f64 {
+    let w = (a.origin.x + a.size.width).min(b.origin.x + b.size.width) - a.origin.x.max(b.origin.x);
+    let h =
+        (a.origin.y + a.size.height).min(b.origin.y + b.size.height) - a.origin.y.max(b.origin.y);
+    if w <= 0.0 || h <= 0.0 {
+        0.0
+    } else {
+        w * h
+    }
+}
+
+/// 选出包含目标矩形的显示器：重叠面积最大者优先，零尺寸矩形（如插入点）按中心点判断。
+fn pick_screen_for_rect(screens: &[NSRect], target: NSRect) -> Option<usize> {
+    let mut best: Option<(f64, usize)> = None;
+    for (i, screen) in screens.iter().enumerate() {
+        let area = rect_intersection_area(*screen, target);
+        if area > 0.0 && best.map(|(a, _)| area > a).unwrap_or(true) {
+            best = Some((area, i));
+        }
+    }
+    if let Some((_, i)) = best {
+        return Some(i);
+    }
+    let center = frame_center(target);
+    screens.iter().position(|s| point_in_frame(center, *s))
+}
+
+/// AX 坐标以主显示器左上角为原点、y 向下；转换为 Cocoa 全局坐标（左下角原点）。
+fn ax_rect_to_cocoa(rect: AxRect, primary_height: f64) -> NSRect {
+    NSRect::new(
+        NSPoint::new(
+            rect.origin.x,
+            primary_height - rect.origin.y - rect.size.height,
+        ),
+        NSSize::new(rect.size.width, rect.size.height),
+    )
+}
+
+/// 按插入点所在显示器取可用区域；无插入点时取鼠标所在显示器。
+unsafe fn visible_frame_for_focus(caret: Option<AxRect>) -> NSRect {
+    let screens = screen_frames();
+    let Some(&(primary, _)) = screens.first() else {
+        return main_visible_frame();
+    };
+    let full: Vec<NSRect> = screens.iter().map(|(f, _)| *f).collect();
+    let target = match caret {
+        Some(caret) => ax_rect_to_cocoa(caret, primary.size.height),
+        None => {
+            let mouse: NSPoint = msg_send![class!(NSEvent), mouseLocation];
+            NSRect::new(mouse, NSSize::new(0.0, 0.0))
+        }
+    };
+    match pick_screen_for_rect(&full, target) {
+        Some(i) => screens[i].1,
+        None => main_visible_frame(),
+    }
+}
+
+unsafe fn visible_frame() -> NSRect {
+    visible_frame_for_focus(None)
+}
+
 fn clamp_overlay_origin(
     mut x: f64,
     mut y: f64,
@@ -415,7 +993,74 @@ fn preview_char_unit(ch: char) -> f32 {
     }
 }
 
+/// 空闲预览提示的最大字数，超出截断加 `…`，避免把悬浮窗撑高。
+const PREVIEW_PLACEHOLDER_MAX_CHARS: usize = 40;
+
+/// 空闲预览提示：优先用 `preview_placeholder`，否则按识别语言给默认文案。
+fn preview_placeholder_text(configured: Option<&str>, language: AsrLanguage) -> String {
+    let text = configured.map(str::trim).filter(|t| !t.is_empty());
+    let Some(text) = text else {
+        return match language {
+            AsrLanguage::En => "Hold the hotkey to speak".to_string(),
+            _ => "按住快捷键说话".to_string(),
+        };
+    };
+    if text.chars().count() <= PREVIEW_PLACEHOLDER_MAX_CHARS {
+        return text.to_string();
+    }
+    let mut out: String = text
+        .chars()
+        .take(PREVIEW_PLACEHOLDER_MAX_CHARS - 1)
+        .collect();
+    out = out.trim_end().to_string();
+    out.push('…');
+    out
+}
+
+/// 估算宽度的换行，不依赖 AppKit；主线程拿不到预览字体时兜底。
 fn wrap_preview_text(raw: &str) -> String {
+    wrap_preview_text_by(raw, |line| {
+        line.chars().map(preview_char_unit).sum::<f32>() <= OVERLAY_PREVIEW_LINE_CAP
+    })
+}
+
+/// 按预览标签的实际字体测量每行宽度换行，比例字体下不再过早截断或留大片空白。须在主线程调用。
+unsafe fn wrap_preview_text_measured(raw: &str, preview_label: id) -> String {
+    if preview_label == nil {
+        return wrap_preview_text(raw);
+    }
+    let font: id = msg_send![preview_label, font];
+    if font == nil {
+        return wrap_preview_text(raw);
+    }
+    // NSFontAttributeName == @"NSFont"
+    let attrs: id = msg_send![
+        class!(NSDictionary),
+        dictionaryWithObject: font
+        forKey: ns_string("NSFont")
+    ];
+    let max_width = OVERLAY_PREVIEW_WIDTH - OVERLAY_PREVIEW_TEXT_INSET;
+    wrap_preview_text_by(raw, |line| measure_text_width(line, attrs) <= max_width)
+}
+
+unsafe fn measure_text_width(text: &str, attrs: id) -> f64 {
+    let attributed: id = msg_send![class!(NSAttributedString), alloc];
+    let attributed: id = msg_send![attributed, initWithString: ns_string(text) attributes: attrs];
+    if attributed == nil {
+        return 0.0;
+    }
+    // NSStringDrawingUsesLineFragmentOrigin
+    let rect: NSRect = msg_send![
+        attributed,
+        boundingRectWithSize: NSSize::new(f64::MAX, f64::MAX)
+        options: 1usize
+    ];
+    let _: () = msg_send![attributed, release];
+    rect.size.width.ceil()
+}
+
+/// 逐字累加，`fits` 判断加上这个字后当前行是否仍放得下；超过最大行数时末行以省略号结尾。
+fn wrap_preview_text_by(raw: &str, mut fits: impl FnMut(&str) -> bool) -> String {
     let text = raw.replace('\r', "");
     if text.trim().is_empty() {
         return String::new();
@@ -423,7 +1068,6 @@ fn wrap_preview_text(raw: &str) -> String {
 
     let mut lines: Vec<String> = Vec::new();
     let mut current = String::new();
-    let mut width_units = 0.0f32;
     let mut truncated = false;
 
     for ch in text.chars() {
@@ -431,7 +1075,6 @@ fn wrap_preview_text(raw: &str) -> String {
         if ch == '\n' {
             lines.push(current);
             current = String::new();
-            width_units = 0.0;
             if lines.len() >= OVERLAY_PREVIEW_MAX_LINES {
                 truncated = true;
                 break;
@@ -439,11 +1082,11 @@ fn wrap_preview_text(raw: &str) -> String {
             continue;
         }
 
-        let unit = preview_char_unit(ch);
-        if width_units + unit > OVERLAY_PREVIEW_LINE_CAP {
+        let mut candidate = current.clone();
+        candidate.push(ch);
+        if !current.is_empty() && !fits(&candidate) {
             lines.push(current);
             current = String::new();
-            width_units = 0.0;
             if lines.len() >= OVERLAY_PREVIEW_MAX_LINES {
                 truncated = true;
                 break;
@@ -451,7 +1094,6 @@ fn wrap_preview_text(raw: &str) -> String {
         }
 
         current.push(ch);
-        width_units += unit;
     }
 
     if !current.is_empty() && lines.len() < OVERLAY_PREVIEW_MAX_LINES {
@@ -486,10 +1128,18 @@ unsafe fn layout_overlay_window(
     status_label: id,
     preview_label: id,
     preview_text: &str,
+    pinyin_label: id,
+    pinyin_text: &str,
 ) {
     let lines = estimate_preview_lines(preview_text);
     let preview_h = (OVERLAY_PREVIEW_LINE_HEIGHT * lines as f64).max(OVERLAY_PREVIEW_MIN_HEIGHT);
-    let mut total_h = (preview_h + 18.0).max(OVERLAY_HEIGHT);
+    // 拼音行贴在预览下方，单行截断
+    let pinyin_h = if pinyin_label != nil && !pinyin_text.is_empty() {
+        OVERLAY_PINYIN_LINE_HEIGHT
+    } else {
+        0.0
+    };
+    let mut total_h = (preview_h + pinyin_h + 18.0).max(OVERLAY_HEIGHT);
     if total_h > OVERLAY_MAX_HEIGHT {
         total_h = OVERLAY_MAX_HEIGHT;
     }
@@ -498,9 +1148,10 @@ unsafe fn layout_overlay_window(
     let status_w = OVERLAY_STATUS_BADGE_WIDTH;
     let badge_x = OVERLAY_STATUS_BADGE_X;
     let preview_x = badge_x + status_w + 16.0;
-    let preview_w = OVERLAY_WIDTH - preview_x - 10.0;
+    let preview_w = OVERLAY_PREVIEW_WIDTH;
     let status_y = ((total_h - status_h) * 0.5).floor();
-    let preview_y = ((total_h - preview_h) * 0.5).floor();
+    let pinyin_y = ((total_h - preview_h - pinyin_h) * 0.5).floor();
+    let preview_y = pinyin_y + pinyin_h;
     let badge_frame = NSRect::new(
         NSPoint::new(badge_x, status_y),
         NSSize::new(status_w, status_h),
@@ -519,6 +1170,15 @@ unsafe fn layout_overlay_window(
     let _: () = msg_send![status_badge, setFrame: badge_frame];
     let _: () = msg_send![status_label, setFrame: status_text_frame];
     let _: () = msg_send![preview_label, setFrame: preview_frame];
+    if pinyin_label != nil {
+        let pinyin_frame = NSRect::new(
+            NSPoint::new(preview_x, pinyin_y),
+            NSSize::new(preview_w, OVERLAY_PINYIN_LINE_HEIGHT),
+        );
+        let _: () = msg_send![pinyin_label, setFrame: pinyin_frame];
+        let hidden = if pinyin_h > 0.0 { NO } else { YES };
+        let _: () = msg_send![pinyin_label, setHidden: hidden];
+    }
 
     let current_frame: NSRect = msg_send![window, frame];
     if (current_frame.size.height - total_h).abs() > 0.5 {
@@ -593,11 +1253,15 @@ unsafe fn focused_caret_rect() -> Option<AxRect> {
     }
 }
 
-fn pick_focus_point(frame: NSRect, mouse: NSPoint, caret: AxRect) -> Option<NSPoint> {
+fn pick_focus_point(
+    frame: NSRect,
+    primary_height: f64,
+    mouse: NSPoint,
+    caret: AxRect,
+) -> Option<NSPoint> {
     let center_x = caret.origin.x + caret.size.width * 0.5;
     let y_bottom_origin = caret.origin.y + caret.size.height * 0.5;
-    let y_top_origin =
-        frame.origin.y + frame.size.height - caret.origin.y - caret.size.height * 0.5;
+    let y_top_origin = primary_height - caret.origin.y - caret.size.height * 0.5;
 
     let candidates = [
         NSPoint::new(center_x, y_bottom_origin),
@@ -619,9 +1283,41 @@ fn pick_focus_point(frame: NSRect, mouse: NSPoint, caret: AxRect) -> Option<NSPo
     best.map(|(_, p)| p)
 }
 
+/// overlay_fixed_x/y 的摆放：坐标以主显示器左上角为原点、y 向下（同 AX 坐标），
+/// 按落点所在显示器的可用区域收回，不会跑到屏幕外或菜单栏/Dock 下面。
+fn fixed_overlay_origin(
+    fixed: (f64, f64),
+    width: f64,
+    height: f64,
+    screens: &[(NSRect, NSRect)],
+) -> Option<(f64, f64)> {
+    let &(primary, primary_visible) = screens.first()?;
+    let x = fixed.0;
+    let y = primary.size.height - fixed.1 - height;
+    let target = NSRect::new(NSPoint::new(x, y), NSSize::new(width, height));
+    let full: Vec<NSRect> = screens.iter().map(|(f, _)| *f).collect();
+    let frame = pick_screen_for_rect(&full, target)
+        .map(|i| screens[i].1)
+        .unwrap_or(primary_visible);
+    Some(clamp_overlay_origin(x, y, width, height, frame))
+}
+
 // Returns true if positioned at top, false if at bottom
 unsafe fn position_overlay_window(window: id) -> bool {
-    let frame = visible_frame();
+    if let Some(fixed) = overlay_fixed_origin() {
+        let size = NSWindow::frame(window).size;
+        if let Some((x, y)) = fixed_overlay_origin(fixed, size.width, size.height, &screen_frames())
+        {
+            window.setFrameOrigin_(NSPoint::new(x, y));
+            return false;
+        }
+    }
+    let caret = focused_caret_rect();
+    let frame = visible_frame_for_focus(caret);
+    let primary_height = screen_frames()
+        .first()
+        .map(|(f, _)| f.size.height)
+        .unwrap_or(frame.origin.y + frame.size.height);
     let window_frame = NSWindow::frame(window);
     let width = window_frame.size.width;
     let height = window_frame.size.height;
@@ -630,8 +1326,8 @@ unsafe fn position_overlay_window(window: id) -> bool {
     let top_y = frame.origin.y + frame.size.height - height - OVERLAY_TOP_MARGIN;
     let bottom_center = NSPoint::new(x + width * 0.5, bottom_y + height * 0.5);
     let mouse: NSPoint = msg_send![class!(NSEvent), mouseLocation];
-    let focus = if let Some(caret) = focused_caret_rect() {
-        pick_focus_point(frame, mouse, caret)
+    let focus = if let Some(caret) = caret {
+        pick_focus_point(frame, primary_height, mouse, caret)
     } else if point_in_frame(mouse, frame) {
         Some(mouse)
     } else {
@@ -653,7 +1349,31 @@ unsafe fn position_overlay_window(window: id) -> bool {
     is_top
 }
 
-unsafe fn install_overlay(show_orb: bool) -> Result<OverlayHandle> {
+// NSPopUpMenuWindowLevel：高于全屏应用的窗口与菜单栏。
+const OVERLAY_FULLSCREEN_WINDOW_LEVEL: i64 = 101;
+
+/// 悬浮窗/悬浮球/历史窗口的层级与空间行为。默认跟随所有桌面空间、不进调度中心；
+/// `overlay_over_fullscreen` 时再加入全屏空间（FullScreenAuxiliary）并固定不随空间切换动画移动，
+/// 层级提到弹出菜单一级，代价是会盖住其它应用的菜单与提示框。
+fn floating_window_placement(over_fullscreen: bool) -> (i64, NSWindowCollectionBehavior) {
+    let base = NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces;
+    if over_fullscreen {
+        (
+            OVERLAY_FULLSCREEN_WINDOW_LEVEL,
+            base | NSWindowCollectionBehavior::NSWindowCollectionBehaviorFullScreenAuxiliary
+                | NSWindowCollectionBehavior::NSWindowCollectionBehaviorStationary,
+        )
+    } else {
+        (
+            (NSMainMenuWindowLevel + 1) as i64,
+            base | NSWindowCollectionBehavior::NSWindowCollectionBehaviorTransient,
+        )
+    }
+}
+
+unsafe fn install_main_overlay_window(style: OverlayStyle) -> Result<(id, id, id, id, id)> {
+    let cfg = load_app_config();
+    let over_fullscreen = cfg.overlay_over_fullscreen;
     let frame = visible_frame();
     let width = OVERLAY_WIDTH;
     let height = OVERLAY_HEIGHT;
@@ -677,11 +1397,9 @@ unsafe fn install_overlay(show_orb: bool) -> Result<OverlayHandle> {
     window.setHasShadow_(YES);
     window.setIgnoresMouseEvents_(YES);
     window.setHidesOnDeactivate_(NO);
-    window.setLevel_((NSMainMenuWindowLevel + 1) as i64);
-    window.setCollectionBehavior_(
-        NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
-            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorTransient,
-    );
+    let (level, behavior) = floating_window_placement(over_fullscreen);
+    window.setLevel_(level);
+    window.setCollectionBehavior_(behavior);
     let _: () = msg_send![window, setReleasedWhenClosed: NO];
     let _: () = msg_send![window, setMovableByWindowBackground: NO];
 
@@ -689,25 +1407,40 @@ unsafe fn install_overlay(show_orb: bool) -> Result<OverlayHandle> {
     if content == nil {
         bail!("浮层 contentView 为空");
     }
+    let high_contrast = style.high_contrast;
+    debug_assert!(meets_wcag_aa(HIGH_CONTRAST_TEXT, HIGH_CONTRAST_BACKGROUND));
+    OVERLAY_HIGH_CONTRAST.store(high_contrast, Ordering::SeqCst);
     let _: () = msg_send![content, setWantsLayer: YES];
     let content_layer: id = msg_send![content, layer];
     if content_layer != nil {
-        let content_bg: id = msg_send![
-            class!(NSColor),
-            colorWithCalibratedWhite: 0.16f64
-            alpha: 0.93f64
-        ];
-        let content_border: id = msg_send![
-            class!(NSColor),
-            colorWithCalibratedWhite: 0.44f64
-            alpha: 0.34f64
-        ];
+        let (content_bg, content_border, border_width): (id, id, f64) = if high_contrast {
+            (
+                ns_rgb(HIGH_CONTRAST_BACKGROUND, 1.0),
+                ns_rgb(HIGH_CONTRAST_TEXT, 1.0),
+                HIGH_CONTRAST_BORDER_WIDTH,
+            )
+        } else {
+            (
+                msg_send![
+                    class!(NSColor),
+                    colorWithCalibratedWhite: 0.16f64
+                    alpha: clamp_overlay_opacity(style.opacity)
+                ],
+                msg_send![
+                    class!(NSColor),
+                    colorWithCalibratedWhite: 0.44f64
+                    alpha: 0.34f64
+                ],
+                1.0,
+            )
+        };
         let content_bg_cg: id = msg_send![content_bg, CGColor];
         let content_border_cg: id = msg_send![content_border, CGColor];
-        let _: () = msg_send![content_layer, setCornerRadius: 15.0f64];
+        let radius = clamp_overlay_radius(style.corner_radius, OVERLAY_HEIGHT);
+        let _: () = msg_send![content_layer, setCornerRadius: radius];
         let _: () = msg_send![content_layer, setMasksToBounds: YES];
         let _: () = msg_send![content_layer, setBackgroundColor: content_bg_cg];
-        let _: () = msg_send![content_layer, setBorderWidth: 1.0f64];
+        let _: () = msg_send![content_layer, setBorderWidth: border_width];
         let _: () = msg_send![content_layer, setBorderColor: content_border_cg];
     }
 
@@ -774,13 +1507,17 @@ unsafe fn install_overlay(show_orb: bool) -> Result<OverlayHandle> {
     let _: () = msg_send![preview_label, setAlignment: 0usize];
     let preview_font: id = msg_send![class!(NSFont), systemFontOfSize: 15.0f64];
     let _: () = msg_send![preview_label, setFont: preview_font];
-    let preview_color: id = msg_send![
-        class!(NSColor),
-        colorWithCalibratedRed: 0.94f64
-        green: 0.91f64
-        blue: 0.78f64
-        alpha: 1.0f64
-    ];
+    let preview_color: id = if high_contrast {
+        ns_rgb(HIGH_CONTRAST_TEXT, 1.0)
+    } else {
+        msg_send![
+            class!(NSColor),
+            colorWithCalibratedRed: 0.94f64
+            green: 0.91f64
+            blue: 0.78f64
+            alpha: 1.0f64
+        ]
+    };
     let _: () = msg_send![preview_label, setTextColor: preview_color];
     let cell: id = msg_send![preview_label, cell];
     if cell != nil {
@@ -789,10 +1526,68 @@ unsafe fn install_overlay(show_orb: bool) -> Result<OverlayHandle> {
         let _: () = msg_send![cell, setUsesSingleLineMode: NO];
         let _: () = msg_send![cell, setLineBreakMode: 0usize];
     }
-    let _: () = msg_send![preview_label, setStringValue: ns_string("按住快捷键说话")];
+    let placeholder = preview_placeholder_text(cfg.preview_placeholder.as_deref(), cfg.language);
+    let placeholder = wrap_preview_text_measured(&placeholder, preview_label);
+    let _: () = msg_send![preview_label, setStringValue: ns_string(&placeholder)];
     content.addSubview_(preview_label);
 
+    // show_pinyin=true 时在预览下方显示拼音；默认隐藏，布局时按内容显隐。
+    let pinyin_label = NSTextField::initWithFrame_(
+        NSTextField::alloc(nil),
+        NSRect::new(
+            NSPoint::new(108.0, 2.0),
+            NSSize::new(442.0, OVERLAY_PINYIN_LINE_HEIGHT),
+        ),
+    );
+    let _: () = msg_send![pinyin_label, setEditable: NO];
+    let _: () = msg_send![pinyin_label, setSelectable: NO];
+    let _: () = msg_send![pinyin_label, setBezeled: NO];
+    let _: () = msg_send![pinyin_label, setBordered: NO];
+    let _: () = msg_send![pinyin_label, setDrawsBackground: NO];
+    let pinyin_font: id = msg_send![class!(NSFont), systemFontOfSize: 11.0f64];
+    let _: () = msg_send![pinyin_label, setFont: pinyin_font];
+    let pinyin_color: id = if high_contrast {
+        ns_rgb(HIGH_CONTRAST_TEXT, 1.0)
+    } else {
+        msg_send![
+            class!(NSColor),
+            colorWithCalibratedWhite: 0.78f64
+            alpha: 0.85f64
+        ]
+    };
+    let _: () = msg_send![pinyin_label, setTextColor: pinyin_color];
+    let pinyin_cell: id = msg_send![pinyin_label, cell];
+    if pinyin_cell != nil {
+        let _: () = msg_send![pinyin_cell, setUsesSingleLineMode: YES];
+        let _: () = msg_send![pinyin_cell, setLineBreakMode: 4usize];
+    }
+    let _: () = msg_send![pinyin_label, setStringValue: ns_string("")];
+    let _: () = msg_send![pinyin_label, setHidden: YES];
+    content.addSubview_(pinyin_label);
+
+    install_overlay_hover_buttons(window, content, preview_label);
+
     window.orderOut_(nil);
+    Ok((
+        window,
+        status_badge,
+        status_label,
+        preview_label,
+        pinyin_label,
+    ))
+}
+
+unsafe fn install_overlay(
+    show_orb: bool,
+    show_overlay: bool,
+    style: OverlayStyle,
+) -> Result<OverlayHandle> {
+    // overlay=off：不创建主浮层，句柄中指针为 0，所有浮层调用变为空操作。
+    let (window, status_badge, status_label, preview_label, pinyin_label) = if show_overlay {
+        install_main_overlay_window(style)?
+    } else {
+        (nil, nil, nil, nil, nil)
+    };
 
     // Install history window
     let (
@@ -803,44 +1598,365 @@ unsafe fn install_overlay(show_orb: bool) -> Result<OverlayHandle> {
         history_list_view,
         clipboard_scroll_view,
         clipboard_list_view,
+        snippet_scroll_view,
+        snippet_list_view,
         close_btn,
     ) = install_history_window()?;
 
     // Install floating orb (if enabled)
     let orb_window = if show_orb {
-        let orb = install_floating_orb()?;
-        ORB_WINDOW_PTR.store(orb as usize, Ordering::SeqCst);
-        orb
+        install_floating_orb()?
     } else {
         nil
     };
 
-    Ok(OverlayHandle {
-        window_ptr: window as usize,
-        status_badge_ptr: status_badge as usize,
-        status_label_ptr: status_label as usize,
-        preview_label_ptr: preview_label as usize,
-        history_window_ptr: history_window as usize,
-        history_title_ptr: history_title_label as usize,
-        history_tab_control_ptr: history_tab_control as usize,
-        history_scroll_view_ptr: history_scroll_view as usize,
-        history_list_view_ptr: history_list_view as usize,
-        clipboard_scroll_view_ptr: clipboard_scroll_view as usize,
-        clipboard_list_view_ptr: clipboard_list_view as usize,
-        history_close_btn_ptr: close_btn as usize,
-        orb_window_ptr: orb_window as usize,
-    })
+    let windows = OverlayWindows {
+        window: window as usize,
+        status_badge: status_badge as usize,
+        status_label: status_label as usize,
+        preview_label: preview_label as usize,
+        pinyin_label: pinyin_label as usize,
+        orb_window: orb_window as usize,
+        generation: 0,
+    };
+    swap_overlay_windows(windows);
+    *OVERLAY_LAYOUT.lock().unwrap() = Some(OverlayLayout {
+        show_orb,
+        show_overlay,
+        style,
+    });
+
+    Ok(OverlayHandle {
+        history_window_ptr: history_window as usize,
+        history_title_ptr: history_title_label as usize,
+        history_tab_control_ptr: history_tab_control as usize,
+        history_scroll_view_ptr: history_scroll_view as usize,
+        history_list_view_ptr: history_list_view as usize,
+        clipboard_scroll_view_ptr: clipboard_scroll_view as usize,
+        clipboard_list_view_ptr: clipboard_list_view as usize,
+        snippet_scroll_view_ptr: snippet_scroll_view as usize,
+        snippet_list_view_ptr: snippet_list_view as usize,
+        history_close_btn_ptr: close_btn as usize,
+    })
+}
+
+/// 主浮层与悬浮球的窗口指针；只在主线程整体替换，读取方在主线程取快照后使用。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct OverlayWindows {
+    window: usize,
+    status_badge: usize,
+    status_label: usize,
+    preview_label: usize,
+    pinyin_label: usize,
+    orb_window: usize,
+    /// 每次替换加一，便于排查重建次数
+    generation: u64,
+}
+
+impl OverlayWindows {
+    /// 换上新指针并返回旧的一组（代数在旧值基础上加一）。
+    fn replace(&mut self, next: OverlayWindows) -> OverlayWindows {
+        let old = *self;
+        *self = OverlayWindows {
+            generation: old.generation + 1,
+            ..next
+        };
+        old
+    }
+}
+
+/// 替换后需要关闭释放的旧窗口：仍在使用（指针未变）或为空的不释放。
+fn windows_to_release(old: &OverlayWindows, new: &OverlayWindows) -> Vec<usize> {
+    [(old.window, new.window), (old.orb_window, new.orb_window)]
+        .into_iter()
+        .filter(|(old_ptr, new_ptr)| *old_ptr != 0 && old_ptr != new_ptr)
+        .map(|(old_ptr, _)| old_ptr)
+        .collect()
+}
+
+/// 决定浮层/悬浮球窗口如何创建的配置；变化时重建窗口。
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct OverlayLayout {
+    show_orb: bool,
+    show_overlay: bool,
+    style: OverlayStyle,
+}
+
+static OVERLAY_WINDOWS: Mutex<OverlayWindows> = Mutex::new(OverlayWindows {
+    window: 0,
+    status_badge: 0,
+    status_label: 0,
+    preview_label: 0,
+    pinyin_label: 0,
+    orb_window: 0,
+    generation: 0,
+});
+static OVERLAY_LAYOUT: Mutex<Option<OverlayLayout>> = Mutex::new(None);
+
+fn overlay_windows() -> OverlayWindows {
+    *OVERLAY_WINDOWS.lock().unwrap()
+}
+
+/// 原子地换上新窗口（连同 ORB_WINDOW_PTR），返回被替换的旧窗口。
+fn swap_overlay_windows(next: OverlayWindows) -> OverlayWindows {
+    let mut slot = OVERLAY_WINDOWS.lock().unwrap();
+    let old = slot.replace(next);
+    ORB_WINDOW_PTR.store(next.orb_window, Ordering::SeqCst);
+    old
+}
+
+/// 配置监视线程调用：浮层开关/外观或悬浮球开关变化时，在主线程重建对应窗口。
+fn rebuild_overlay_if_changed(cfg: &AppConfig) {
+    let next = OverlayLayout {
+        show_orb: cfg.show_floating_orb,
+        show_overlay: cfg.show_overlay,
+        style: cfg.overlay_style,
+    };
+    let Some(current) = *OVERLAY_LAYOUT.lock().unwrap() else {
+        return;
+    };
+    if current == next {
+        return;
+    }
+    *OVERLAY_LAYOUT.lock().unwrap() = Some(next);
+    Queue::main().exec_async(move || unsafe {
+        if let Err(e) = rebuild_overlay_windows(current, next) {
+            eprintln!("[mofa-ime] 重建浮层失败: {e}");
+        }
+    });
+}
+
+unsafe fn rebuild_overlay_windows(current: OverlayLayout, next: OverlayLayout) -> Result<()> {
+    let mut windows = overlay_windows();
+    if current.show_overlay != next.show_overlay || current.style != next.style {
+        let (window, status_badge, status_label, preview_label, pinyin_label) = if next.show_overlay
+        {
+            install_main_overlay_window(next.style)?
+        } else {
+            (nil, nil, nil, nil, nil)
+        };
+        windows.window = window as usize;
+        windows.status_badge = status_badge as usize;
+        windows.status_label = status_label as usize;
+        windows.preview_label = preview_label as usize;
+        windows.pinyin_label = pinyin_label as usize;
+        *OVERLAY_HOVER_PHASE.lock().unwrap() = OverlayHoverPhase::Outside;
+    }
+    if current.show_orb != next.show_orb {
+        windows.orb_window = if next.show_orb {
+            install_floating_orb()? as usize
+        } else {
+            0
+        };
+    }
+
+    let old = swap_overlay_windows(windows);
+    for ptr in windows_to_release(&old, &windows) {
+        let window = ptr as id;
+        window.orderOut_(nil);
+        let _: () = msg_send![window, close];
+        let _: () = msg_send![window, release];
+    }
+    Ok(())
+}
+
+// 悬停交互：浮层默认鼠标穿透（ignoresMouseEvents），穿透时收不到 tracking area 事件，
+// 因此轮询鼠标位置；停留片刻后才允许点击并显示 ×/复制 按钮，移开即恢复穿透。
+static OVERLAY_DISMISS_BTN_PTR: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(0);
+static OVERLAY_COPY_BTN_PTR: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(0);
+static OVERLAY_HOVER_PHASE: Mutex<OverlayHoverPhase> = Mutex::new(OverlayHoverPhase::Outside);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OverlayHoverPhase {
+    Outside,
+    /// 鼠标进入的时间（ms）
+    Hovering(u64),
+    Interactive,
+}
+
+fn overlay_hover_transition(
+    phase: OverlayHoverPhase,
+    inside: bool,
+    now_ms: u64,
+    dwell_ms: u64,
+) -> OverlayHoverPhase {
+    match (phase, inside) {
+        (_, false) => OverlayHoverPhase::Outside,
+        (OverlayHoverPhase::Outside, true) => OverlayHoverPhase::Hovering(now_ms),
+        (OverlayHoverPhase::Hovering(since), true) if now_ms.saturating_sub(since) >= dwell_ms => {
+            OverlayHoverPhase::Interactive
+        }
+        (phase, true) => phase,
+    }
+}
+
+unsafe fn set_overlay_interactive(window: id, interactive: bool) {
+    window.setIgnoresMouseEvents_(if interactive { NO } else { YES });
+    let hidden = if interactive { NO } else { YES };
+    for ptr in [&OVERLAY_DISMISS_BTN_PTR, &OVERLAY_COPY_BTN_PTR] {
+        let button = ptr.load(Ordering::SeqCst) as id;
+        if button != nil {
+            let _: () = msg_send![button, setHidden: hidden];
+        }
+    }
+}
+
+unsafe fn install_overlay_hover_buttons(window: id, content: id, preview_label: id) {
+    // NSViewMinXMargin | NSViewMinYMargin：浮层高度变化时按钮贴住右上角
+    const PIN_TOP_RIGHT: usize = 1 | 8;
+
+    let delegate = create_overlay_actions_delegate(window, preview_label);
+    for (ptr, offset, symbol, action) in [
+        (
+            &OVERLAY_DISMISS_BTN_PTR,
+            28.0,
+            "xmark",
+            sel!(dismissOverlay:),
+        ),
+        (
+            &OVERLAY_COPY_BTN_PTR,
+            52.0,
+            "doc.on.doc",
+            sel!(copyPreview:),
+        ),
+    ] {
+        let button = NSButton::initWithFrame_(
+            NSButton::alloc(nil),
+            NSRect::new(
+                NSPoint::new(OVERLAY_WIDTH - offset, OVERLAY_HEIGHT - 24.0),
+                NSSize::new(18.0, 18.0),
+            ),
+        );
+        let _: () = msg_send![button, setBezelStyle: 8usize];
+        let _: () = msg_send![button, setBordered: NO];
+        let _: () = msg_send![button, setButtonType: 0usize];
+        let _: () = msg_send![button, setAutoresizingMask: PIN_TOP_RIGHT];
+        set_status_button_symbol(button, symbol);
+        let _: () = msg_send![button, setTarget: delegate];
+        let _: () = msg_send![button, setAction: action];
+        let _: () = msg_send![button, setHidden: YES];
+        content.addSubview_(button);
+        ptr.store(button as usize, Ordering::SeqCst);
+    }
+}
+
+fn create_overlay_actions_delegate(window: id, preview_label: id) -> id {
+    static CLASS: OnceLock<usize> = OnceLock::new();
+    let ptr = *CLASS.get_or_init(|| {
+        let superclass = class!(NSObject);
+        let mut decl = ClassDecl::new("MofaOverlayActions", superclass).unwrap();
+        decl.add_ivar::<usize>("window_ptr");
+        decl.add_ivar::<usize>("preview_ptr");
+
+        extern "C" fn dismiss(this: &Object, _sel: Sel, _sender: id) {
+            unsafe {
+                let window = *this.get_ivar::<usize>("window_ptr") as id;
+                if window != nil {
+                    set_overlay_interactive(window, false);
+                    *OVERLAY_HOVER_PHASE.lock().unwrap() = OverlayHoverPhase::Outside;
+                    window.orderOut_(nil);
+                }
+            }
+        }
+
+        extern "C" fn copy_preview(this: &Object, _sel: Sel, _sender: id) {
+            unsafe {
+                let preview = *this.get_ivar::<usize>("preview_ptr") as id;
+                if preview == nil {
+                    return;
+                }
+                let value: id = msg_send![preview, stringValue];
+                if value == nil {
+                    return;
+                }
+                let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+                let _: () = msg_send![pasteboard, clearContents];
+                let _: BOOL =
+                    msg_send![pasteboard, setString: value forType: NSPasteboardTypeString];
+            }
+        }
+
+        unsafe {
+            decl.add_method(
+                sel!(dismissOverlay:),
+                dismiss as extern "C" fn(&Object, Sel, id),
+            );
+            decl.add_method(
+                sel!(copyPreview:),
+                copy_preview as extern "C" fn(&Object, Sel, id),
+            );
+        }
+        decl.register() as *const Class as usize
+    });
+
+    unsafe {
+        let class = &*(ptr as *const Class);
+        let delegate: id = msg_send![class, alloc];
+        let delegate: id = msg_send![delegate, init];
+        (*delegate).set_ivar("window_ptr", window as usize);
+        (*delegate).set_ivar("preview_ptr", preview_label as usize);
+        delegate
+    }
+}
+
+/// overlay_interactive=true 时启动：轮询鼠标是否停在可见浮层上，切换穿透/可交互。
+fn spawn_overlay_hover_watcher(overlay: OverlayHandle) {
+    if !overlay.overlay_enabled() {
+        return;
+    }
+    let epoch = std::time::Instant::now();
+    std::thread::spawn(move || loop {
+        if shutdown_requested() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(OVERLAY_HOVER_POLL_MS));
+        let now_ms = epoch.elapsed().as_millis() as u64;
+        Queue::main().exec_async(move || unsafe {
+            let window = overlay_windows().window as id;
+            if window == nil {
+                return;
+            }
+            let visible: BOOL = msg_send![window, isVisible];
+            let inside = visible == YES && {
+                let mouse: NSPoint = msg_send![class!(NSEvent), mouseLocation];
+                point_in_frame(mouse, NSWindow::frame(window))
+            };
+            let mut phase = OVERLAY_HOVER_PHASE.lock().unwrap();
+            let next = overlay_hover_transition(*phase, inside, now_ms, OVERLAY_HOVER_DWELL_MS);
+            let was_interactive = *phase == OverlayHoverPhase::Interactive;
+            let is_interactive = next == OverlayHoverPhase::Interactive;
+            if was_interactive != is_interactive {
+                set_overlay_interactive(window, is_interactive);
+            }
+            *phase = next;
+        });
+    });
 }
 
 unsafe fn ns_string(s: &str) -> id {
     NSString::alloc(nil).init_str(s).autorelease()
 }
 
+unsafe fn ns_rgb((r, g, b): (f64, f64, f64), alpha: f64) -> id {
+    msg_send![
+        class!(NSColor),
+        colorWithCalibratedRed: r
+        green: g
+        blue: b
+        alpha: alpha
+    ]
+}
+
 unsafe fn set_status_badge_appearance(status_label: id, status: &str) {
     if status_label == nil {
         return;
     }
-    let (r, g, b) = if status.contains("录音") {
+    let high_contrast = OVERLAY_HIGH_CONTRAST.load(Ordering::SeqCst);
+    let (r, g, b) = if high_contrast {
+        HIGH_CONTRAST_BACKGROUND
+    } else if status.contains("录音") {
         (0.20, 0.44, 0.95)
     } else if status.contains("转录") || status.contains("识别") {
         (0.35, 0.37, 0.44)
@@ -868,6 +1984,12 @@ unsafe fn set_status_badge_appearance(status_label: id, status: &str) {
         ];
         let _: () = msg_send![status_layer, setMasksToBounds: YES];
         let _: () = msg_send![status_layer, setBackgroundColor: badge_bg_cg];
+        // 高对比度下徽标与底色同为黑色，用白色描边区分。
+        let border_width = if high_contrast { 1.5f64 } else { 0.0f64 };
+        let border: id = ns_rgb(HIGH_CONTRAST_TEXT, 1.0);
+        let border_cg: id = msg_send![border, CGColor];
+        let _: () = msg_send![status_layer, setBorderWidth: border_width];
+        let _: () = msg_send![status_layer, setBorderColor: border_cg];
     }
 }
 
@@ -981,6 +2103,8 @@ unsafe fn layout_history_window_views(
     history_list_view: id,
     clipboard_scroll_view: id,
     clipboard_list_view: id,
+    snippet_scroll_view: id,
+    snippet_list_view: id,
     close_btn: id,
     settings_btn: id,
     quit_btn: id,
@@ -1027,7 +2151,7 @@ unsafe fn layout_history_window_views(
     if tab_control != nil {
         let _: () = msg_send![
             tab_control,
-            setFrame: NSRect::new(NSPoint::new(12.0, tab_y), NSSize::new(152.0, 20.0))
+            setFrame: NSRect::new(NSPoint::new(12.0, tab_y), NSSize::new(HISTORY_TAB_WIDTH, 20.0))
         ];
     }
     if history_scroll_view != nil {
@@ -1048,6 +2172,15 @@ unsafe fn layout_history_window_views(
             )
         ];
     }
+    if snippet_scroll_view != nil {
+        let _: () = msg_send![
+            snippet_scroll_view,
+            setFrame: NSRect::new(
+                NSPoint::new(list_x, list_y),
+                NSSize::new(list_width, list_height)
+            )
+        ];
+    }
     if history_list_view != nil {
         let list_frame: NSRect = msg_send![history_list_view, frame];
         let target_h = list_frame.size.height.max(list_height);
@@ -1064,6 +2197,14 @@ unsafe fn layout_history_window_views(
             setFrame: NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new((list_width - 4.0).max(80.0), target_h))
         ];
     }
+    if snippet_list_view != nil {
+        let list_frame: NSRect = msg_send![snippet_list_view, frame];
+        let target_h = list_frame.size.height.max(list_height);
+        let _: () = msg_send![
+            snippet_list_view,
+            setFrame: NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new((list_width - 4.0).max(80.0), target_h))
+        ];
+    }
     if resize_handle != nil {
         let _: () = msg_send![
             resize_handle,
@@ -1093,6 +2234,8 @@ fn register_history_resize_handle_class() -> &'static objc::runtime::Class {
         decl.add_ivar::<usize>("history_list_view_ptr");
         decl.add_ivar::<usize>("clipboard_scroll_view_ptr");
         decl.add_ivar::<usize>("clipboard_list_view_ptr");
+        decl.add_ivar::<usize>("snippet_scroll_view_ptr");
+        decl.add_ivar::<usize>("snippet_list_view_ptr");
         decl.add_ivar::<usize>("close_btn_ptr");
         decl.add_ivar::<usize>("settings_btn_ptr");
         decl.add_ivar::<usize>("quit_btn_ptr");
@@ -1159,6 +2302,8 @@ fn register_history_resize_handle_class() -> &'static objc::runtime::Class {
                 let history_list_view_ptr: usize = *this.get_ivar("history_list_view_ptr");
                 let clipboard_scroll_view_ptr: usize = *this.get_ivar("clipboard_scroll_view_ptr");
                 let clipboard_list_view_ptr: usize = *this.get_ivar("clipboard_list_view_ptr");
+                let snippet_scroll_view_ptr: usize = *this.get_ivar("snippet_scroll_view_ptr");
+                let snippet_list_view_ptr: usize = *this.get_ivar("snippet_list_view_ptr");
                 let close_btn_ptr: usize = *this.get_ivar("close_btn_ptr");
                 let settings_btn_ptr: usize = *this.get_ivar("settings_btn_ptr");
                 let quit_btn_ptr: usize = *this.get_ivar("quit_btn_ptr");
@@ -1169,6 +2314,8 @@ fn register_history_resize_handle_class() -> &'static objc::runtime::Class {
                 let history_list_view = history_list_view_ptr as id;
                 let clipboard_scroll_view = clipboard_scroll_view_ptr as id;
                 let clipboard_list_view = clipboard_list_view_ptr as id;
+                let snippet_scroll_view = snippet_scroll_view_ptr as id;
+                let snippet_list_view = snippet_list_view_ptr as id;
                 let close_btn = close_btn_ptr as id;
                 let settings_btn = settings_btn_ptr as id;
                 let quit_btn = quit_btn_ptr as id;
@@ -1182,6 +2329,8 @@ fn register_history_resize_handle_class() -> &'static objc::runtime::Class {
                     history_list_view,
                     clipboard_scroll_view,
                     clipboard_list_view,
+                    snippet_scroll_view,
+                    snippet_list_view,
                     close_btn,
                     settings_btn,
                     quit_btn,
@@ -1190,6 +2339,7 @@ fn register_history_resize_handle_class() -> &'static objc::runtime::Class {
 
                 let history = get_history_items();
                 let clipboard = get_clipboard_items();
+                let snippets = load_snippets();
                 let active_tab = get_history_tab_index();
                 rebuild_history_list_view(
                     history_scroll_view,
@@ -1203,12 +2353,14 @@ fn register_history_resize_handle_class() -> &'static objc::runtime::Class {
                     &clipboard,
                     false,
                 );
+                rebuild_snippet_list_view(snippet_scroll_view, snippet_list_view, &snippets, false);
                 apply_history_tab_ui(
                     active_tab,
                     tab_control,
                     title_label,
                     history_scroll_view,
                     clipboard_scroll_view,
+                    snippet_scroll_view,
                 );
             }
         }
@@ -1246,7 +2398,8 @@ fn register_history_resize_handle_class() -> &'static objc::runtime::Class {
 }
 
 // Create the history window with tabs and scrollable list views
-unsafe fn install_history_window() -> Result<(id, id, id, id, id, id, id, id)> {
+unsafe fn install_history_window() -> Result<(id, id, id, id, id, id, id, id, id, id)> {
+    let over_fullscreen = load_app_config().overlay_over_fullscreen;
     let rect = NSRect::new(
         NSPoint::new(0.0, 0.0),
         NSSize::new(HISTORY_WIDTH, HISTORY_HEIGHT),
@@ -1268,11 +2421,9 @@ unsafe fn install_history_window() -> Result<(id, id, id, id, id, id, id, id)> {
     window.setHasShadow_(YES);
     window.setIgnoresMouseEvents_(NO); // Allow mouse interaction
     window.setHidesOnDeactivate_(NO);
-    window.setLevel_((NSMainMenuWindowLevel + 1) as i64);
-    window.setCollectionBehavior_(
-        NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
-            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorTransient,
-    );
+    let (level, behavior) = floating_window_placement(over_fullscreen);
+    window.setLevel_(level);
+    window.setCollectionBehavior_(behavior);
     let _: () = msg_send![window, setReleasedWhenClosed: NO];
     let _: () = msg_send![window, setMovableByWindowBackground: YES];
 
@@ -1375,14 +2526,18 @@ unsafe fn install_history_window() -> Result<(id, id, id, id, id, id, id, id)> {
     let tab_control_alloc: id = msg_send![class!(NSSegmentedControl), alloc];
     let tab_control: id = msg_send![
         tab_control_alloc,
-        initWithFrame: NSRect::new(NSPoint::new(12.0, HISTORY_HEIGHT - 50.0), NSSize::new(152.0, 20.0))
+        initWithFrame: NSRect::new(
+            NSPoint::new(12.0, HISTORY_HEIGHT - 50.0),
+            NSSize::new(HISTORY_TAB_WIDTH, 20.0)
+        )
     ];
     if tab_control == nil {
         bail!("无法创建页签控件");
     }
-    let _: () = msg_send![tab_control, setSegmentCount: 2isize];
+    let _: () = msg_send![tab_control, setSegmentCount: HISTORY_TAB_COUNT as isize];
     let _: () = msg_send![tab_control, setLabel: ns_string("最近输入") forSegment: 0isize];
     let _: () = msg_send![tab_control, setLabel: ns_string("剪切板") forSegment: 1isize];
+    let _: () = msg_send![tab_control, setLabel: ns_string("常用") forSegment: 2isize];
     let _: () = msg_send![tab_control, setSelectedSegment: 0isize];
     content.addSubview_(tab_control);
 
@@ -1440,6 +2595,33 @@ unsafe fn install_history_window() -> Result<(id, id, id, id, id, id, id, id)> {
     let _: () = msg_send![clipboard_scroll_view, setDocumentView: clipboard_list_view];
     content.addSubview_(clipboard_scroll_view);
 
+    let snippet_scroll_view_alloc: id = msg_send![class!(NSScrollView), alloc];
+    let snippet_scroll_view: id = msg_send![
+        snippet_scroll_view_alloc,
+        initWithFrame: NSRect::new(NSPoint::new(list_x, list_y), NSSize::new(list_width, list_height))
+    ];
+    if snippet_scroll_view == nil {
+        bail!("无法创建常用文本滚动区域");
+    }
+    let _: () = msg_send![snippet_scroll_view, setHasVerticalScroller: YES];
+    let _: () = msg_send![snippet_scroll_view, setHasHorizontalScroller: NO];
+    let _: () = msg_send![snippet_scroll_view, setAutohidesScrollers: YES];
+    let _: () = msg_send![snippet_scroll_view, setBorderType: 0usize];
+    let _: () = msg_send![snippet_scroll_view, setDrawsBackground: NO];
+
+    let snippet_list_view = NSView::initWithFrame_(
+        NSView::alloc(nil),
+        NSRect::new(
+            NSPoint::new(0.0, 0.0),
+            NSSize::new(list_width - 4.0, list_height),
+        ),
+    );
+    if snippet_list_view == nil {
+        bail!("无法创建常用文本列表视图");
+    }
+    let _: () = msg_send![snippet_scroll_view, setDocumentView: snippet_list_view];
+    content.addSubview_(snippet_scroll_view);
+
     // Tab action and initial selected panel.
     let tab_delegate = create_history_tab_delegate(
         tab_control,
@@ -1448,6 +2630,8 @@ unsafe fn install_history_window() -> Result<(id, id, id, id, id, id, id, id)> {
         history_list_view,
         clipboard_scroll_view,
         clipboard_list_view,
+        snippet_scroll_view,
+        snippet_list_view,
     );
     let _: () = msg_send![tab_control, setTarget: tab_delegate];
     let _: () = msg_send![tab_control, setAction: sel!(switchHistoryTab:)];
@@ -1472,6 +2656,8 @@ unsafe fn install_history_window() -> Result<(id, id, id, id, id, id, id, id)> {
     (*resize_handle).set_ivar("history_list_view_ptr", history_list_view as usize);
     (*resize_handle).set_ivar("clipboard_scroll_view_ptr", clipboard_scroll_view as usize);
     (*resize_handle).set_ivar("clipboard_list_view_ptr", clipboard_list_view as usize);
+    (*resize_handle).set_ivar("snippet_scroll_view_ptr", snippet_scroll_view as usize);
+    (*resize_handle).set_ivar("snippet_list_view_ptr", snippet_list_view as usize);
     (*resize_handle).set_ivar("close_btn_ptr", close_btn as usize);
     (*resize_handle).set_ivar("settings_btn_ptr", settings_btn as usize);
     (*resize_handle).set_ivar("quit_btn_ptr", quit_btn as usize);
@@ -1496,6 +2682,8 @@ unsafe fn install_history_window() -> Result<(id, id, id, id, id, id, id, id)> {
         history_list_view,
         clipboard_scroll_view,
         clipboard_list_view,
+        snippet_scroll_view,
+        snippet_list_view,
         close_btn,
         settings_btn,
         quit_btn,
@@ -1508,6 +2696,7 @@ unsafe fn install_history_window() -> Result<(id, id, id, id, id, id, id, id)> {
         title_label,
         history_scroll_view,
         clipboard_scroll_view,
+        snippet_scroll_view,
     );
 
     window.orderOut_(nil);
@@ -1520,12 +2709,15 @@ unsafe fn install_history_window() -> Result<(id, id, id, id, id, id, id, id)> {
         history_list_view,
         clipboard_scroll_view,
         clipboard_list_view,
+        snippet_scroll_view,
+        snippet_list_view,
         close_btn,
     ))
 }
 
 // Create floating orb window (常驻悬浮球)
 unsafe fn install_floating_orb() -> Result<id> {
+    let over_fullscreen = load_app_config().overlay_over_fullscreen;
     let frame = visible_frame();
     let orb_size = ORB_SIZE;
     // Default position: bottom-right corner
@@ -1553,11 +2745,9 @@ unsafe fn install_floating_orb() -> Result<id> {
     window.setHasShadow_(YES);
     window.setIgnoresMouseEvents_(NO); // Allow mouse interaction
     window.setHidesOnDeactivate_(NO);
-    window.setLevel_((NSMainMenuWindowLevel + 1) as i64);
-    window.setCollectionBehavior_(
-        NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
-            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorTransient,
-    );
+    let (level, behavior) = floating_window_placement(over_fullscreen);
+    window.setLevel_(level);
+    window.setCollectionBehavior_(behavior);
     let _: () = msg_send![window, setReleasedWhenClosed: NO];
     let _: () = msg_send![window, setMovableByWindowBackground: YES]; // Draggable
 
@@ -1688,6 +2878,12 @@ unsafe fn setup_orb_mouse_handling(window: id, content: id, orb_size: f64) {
             }
         }
 
+        let drag_types: id = msg_send![
+            class!(NSArray),
+            arrayWithObject: ns_string(FILE_URL_PASTEBOARD_TYPE)
+        ];
+        let _: () = msg_send![tracking_view, registerForDraggedTypes: drag_types];
+
         // Replace content view
         let _: () = msg_send![window, setContentView: tracking_view];
     }
@@ -1778,7 +2974,37 @@ fn register_orb_tracking_class() -> &'static objc::runtime::Class {
             }
         }
 
+        // 文件拖放：只对支持的音频高亮“复制”光标，其余拖放直接拒绝。
+        extern "C" fn dragging_entered(_this: &mut Object, _sel: Sel, info: id) -> usize {
+            // NSDragOperationCopy / NSDragOperationNone
+            match unsafe { dragged_file_path(info) } {
+                Some(path) if is_droppable_audio_file(&path) => 1,
+                _ => 0,
+            }
+        }
+
+        extern "C" fn perform_drag(_this: &mut Object, _sel: Sel, info: id) -> BOOL {
+            let Some(path) = (unsafe { dragged_file_path(info) }) else {
+                return NO;
+            };
+            if !is_droppable_audio_file(&path) {
+                return NO;
+            }
+            match ORB_CLICK_TX.get() {
+                Some(tx) if tx.send(OrbCommand::TranscribeFile(path)).is_ok() => YES,
+                _ => NO,
+            }
+        }
+
         unsafe {
+            decl.add_method(
+                sel!(draggingEntered:),
+                dragging_entered as extern "C" fn(&mut Object, Sel, id) -> usize,
+            );
+            decl.add_method(
+                sel!(performDragOperation:),
+                perform_drag as extern "C" fn(&mut Object, Sel, id) -> BOOL,
+            );
             decl.add_method(
                 sel!(mouseDown:),
                 mouse_down as extern "C" fn(&mut Object, Sel, id),
@@ -1838,11 +3064,49 @@ fn create_copy_delegate() -> id {
             }
         }
 
+        // 回放历史录音；再次点击其它条目时先停掉正在播放的。
+        extern "C" fn play_item(_this: &mut Object, _sel: Sel, sender: id) {
+            static PLAYING: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+            unsafe {
+                if sender == nil {
+                    return;
+                }
+                let index: isize = msg_send![sender, tag];
+                if index < 0 {
+                    return;
+                }
+                let previous = PLAYING.swap(0, Ordering::SeqCst) as id;
+                if previous != nil {
+                    let _: BOOL = msg_send![previous, stop];
+                    let _: () = msg_send![previous, release];
+                }
+                let Some(path) = history_audio_path(index as usize) else {
+                    return;
+                };
+                let sound: id = msg_send![class!(NSSound), alloc];
+                let sound: id = msg_send![
+                    sound,
+                    initWithContentsOfFile: ns_string(&path.to_string_lossy())
+                    byReference: YES
+                ];
+                if sound == nil {
+                    eprintln!("[mofa-ime] 无法播放历史录音 {:?}", path);
+                    return;
+                }
+                let _: BOOL = msg_send![sound, play];
+                PLAYING.store(sound as usize, Ordering::SeqCst);
+            }
+        }
+
         unsafe {
             decl.add_method(
                 sel!(copyHistoryItem:),
                 copy_item as extern "C" fn(&mut Object, Sel, id),
             );
+            decl.add_method(
+                sel!(playHistoryAudio:),
+                play_item as extern "C" fn(&mut Object, Sel, id),
+            );
         }
 
         let class = decl.register();
@@ -1886,29 +3150,7 @@ fn create_clipboard_copy_delegate() -> id {
                 if pasteboard == nil {
                     return;
                 }
-                let _: () = msg_send![pasteboard, clearContents];
-
-                match item {
-                    ClipboardHistoryItem::Text(text) => {
-                        let ns_string = NSString::alloc(nil).init_str(text).autorelease();
-                        let _: BOOL =
-                            msg_send![pasteboard, setString: ns_string forType: NSPasteboardTypeString];
-                    }
-                    ClipboardHistoryItem::Image { data, uti } => {
-                        let ns_data: id = msg_send![
-                            class!(NSData),
-                            dataWithBytes: data.as_ptr()
-                            length: data.len()
-                        ];
-                        if ns_data != nil {
-                            let _: BOOL = msg_send![
-                                pasteboard,
-                                setData: ns_data
-                                forType: ns_string(uti)
-                            ];
-                        }
-                    }
-                }
+                write_clipboard_item_to_pasteboard(pasteboard, item);
             }
         }
 
@@ -1930,6 +3172,85 @@ fn create_clipboard_copy_delegate() -> id {
     unsafe { DELEGATE }
 }
 
+// 常用文本：复制到剪贴板，或收起历史窗口后直接输入到前台应用。
+fn create_snippet_delegate() -> id {
+    use objc::declare::ClassDecl;
+    use std::sync::Once;
+
+    static mut CLASS: *const objc::runtime::Class = std::ptr::null();
+    static mut DELEGATE: id = nil;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        let superclass = objc::runtime::Class::get("NSObject").unwrap();
+        let mut decl = ClassDecl::new("SnippetDelegate", superclass).unwrap();
+
+        extern "C" fn copy_snippet(_this: &mut Object, _sel: Sel, sender: id) {
+            unsafe {
+                let Some(text) = snippet_for_sender(sender) else {
+                    return;
+                };
+                let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+                if pasteboard == nil {
+                    return;
+                }
+                write_clipboard_item_to_pasteboard(pasteboard, &ClipboardHistoryItem::Text(text));
+            }
+        }
+
+        extern "C" fn inject_snippet(_this: &mut Object, _sel: Sel, sender: id) {
+            unsafe {
+                let Some(text) = snippet_for_sender(sender) else {
+                    return;
+                };
+                let window: id = msg_send![sender, window];
+                if window != nil {
+                    window.orderOut_(nil);
+                }
+                // 注入会等待按键事件与剪贴板恢复，放到后台线程，避免卡住主线程。
+                std::thread::spawn(move || {
+                    let strategy =
+                        load_app_config().inject_strategy_for(frontmost_bundle_id().as_deref());
+                    if let Err(e) = inject_text(&text, strategy) {
+                        eprintln!("[mofa-ime] 常用文本输入失败: {e}");
+                    }
+                });
+            }
+        }
+
+        unsafe {
+            decl.add_method(
+                sel!(copySnippet:),
+                copy_snippet as extern "C" fn(&mut Object, Sel, id),
+            );
+            decl.add_method(
+                sel!(injectSnippet:),
+                inject_snippet as extern "C" fn(&mut Object, Sel, id),
+            );
+        }
+
+        let class = decl.register();
+        unsafe {
+            CLASS = class;
+            let delegate: id = msg_send![class, new];
+            DELEGATE = delegate;
+        }
+    });
+
+    unsafe { DELEGATE }
+}
+
+unsafe fn snippet_for_sender(sender: id) -> Option<String> {
+    if sender == nil {
+        return None;
+    }
+    let index: isize = msg_send![sender, tag];
+    if index < 0 {
+        return None;
+    }
+    load_snippets().into_iter().nth(index as usize)
+}
+
 fn create_history_tab_delegate(
     tab_control: id,
     title_label: id,
@@ -1937,6 +3258,8 @@ fn create_history_tab_delegate(
     history_list_view: id,
     clipboard_scroll_view: id,
     clipboard_list_view: id,
+    snippet_scroll_view: id,
+    snippet_list_view: id,
 ) -> id {
     use objc::declare::ClassDecl;
     use std::sync::Once;
@@ -1954,6 +3277,8 @@ fn create_history_tab_delegate(
         decl.add_ivar::<usize>("history_list_view_ptr");
         decl.add_ivar::<usize>("clipboard_scroll_view_ptr");
         decl.add_ivar::<usize>("clipboard_list_view_ptr");
+        decl.add_ivar::<usize>("snippet_scroll_view_ptr");
+        decl.add_ivar::<usize>("snippet_list_view_ptr");
 
         extern "C" fn switch_history_tab(this: &mut Object, _sel: Sel, sender: id) {
             unsafe {
@@ -1962,7 +3287,7 @@ fn create_history_tab_delegate(
                 } else {
                     0
                 };
-                let tab_index = if index == 1 { 1 } else { 0 };
+                let tab_index = normalize_history_tab(index.max(0) as usize);
                 set_history_tab_index(tab_index);
 
                 let tab_control_ptr: usize = *this.get_ivar("tab_control_ptr");
@@ -1971,6 +3296,8 @@ fn create_history_tab_delegate(
                 let history_list_view_ptr: usize = *this.get_ivar("history_list_view_ptr");
                 let clipboard_scroll_view_ptr: usize = *this.get_ivar("clipboard_scroll_view_ptr");
                 let clipboard_list_view_ptr: usize = *this.get_ivar("clipboard_list_view_ptr");
+                let snippet_scroll_view_ptr: usize = *this.get_ivar("snippet_scroll_view_ptr");
+                let snippet_list_view_ptr: usize = *this.get_ivar("snippet_list_view_ptr");
 
                 let tab_control = tab_control_ptr as id;
                 let title_label = title_label_ptr as id;
@@ -1978,9 +3305,12 @@ fn create_history_tab_delegate(
                 let history_list_view = history_list_view_ptr as id;
                 let clipboard_scroll_view = clipboard_scroll_view_ptr as id;
                 let clipboard_list_view = clipboard_list_view_ptr as id;
+                let snippet_scroll_view = snippet_scroll_view_ptr as id;
+                let snippet_list_view = snippet_list_view_ptr as id;
 
                 let history = get_history_items();
                 let clipboard = get_clipboard_items();
+                let snippets = load_snippets();
                 rebuild_history_list_view(
                     history_scroll_view,
                     history_list_view,
@@ -1993,12 +3323,19 @@ fn create_history_tab_delegate(
                     &clipboard,
                     tab_index == 1,
                 );
+                rebuild_snippet_list_view(
+                    snippet_scroll_view,
+                    snippet_list_view,
+                    &snippets,
+                    tab_index == HISTORY_TAB_SNIPPETS,
+                );
                 apply_history_tab_ui(
                     tab_index,
                     tab_control,
                     title_label,
                     history_scroll_view,
                     clipboard_scroll_view,
+                    snippet_scroll_view,
                 );
             }
         }
@@ -2024,6 +3361,8 @@ fn create_history_tab_delegate(
         (*delegate).set_ivar("history_list_view_ptr", history_list_view as usize);
         (*delegate).set_ivar("clipboard_scroll_view_ptr", clipboard_scroll_view as usize);
         (*delegate).set_ivar("clipboard_list_view_ptr", clipboard_list_view as usize);
+        (*delegate).set_ivar("snippet_scroll_view_ptr", snippet_scroll_view as usize);
+        (*delegate).set_ivar("snippet_list_view_ptr", snippet_list_view as usize);
         delegate
     }
 }
@@ -2148,3 +3487,555 @@ fn create_close_delegate(window: id) -> id {
         delegate
     }
 }
+
+#[cfg(test)]
+mod overlay_tests {
+    use super::*;
+
+    #[test]
+    fn fade_schedule_splits_total_time_and_ends_transparent() {
+        let (step_ms, alphas) = overlay_fade_schedule(120, 4);
+        assert_eq!(step_ms, 30);
+        assert_eq!(alphas, vec![0.75, 0.5, 0.25, 0.0]);
+    }
+
+    #[test]
+    fn fade_schedule_guards_against_zero_steps_and_time() {
+        let (step_ms, alphas) = overlay_fade_schedule(0, 0);
+        assert_eq!(step_ms, 1);
+        assert_eq!(alphas, vec![0.0]);
+    }
+
+    /// overlay=off 时的句柄：主浮层未创建，历史窗口指针也为 0。
+    fn detached_overlay() -> OverlayHandle {
+        OverlayHandle {
+            history_window_ptr: 0,
+            history_title_ptr: 0,
+            history_tab_control_ptr: 0,
+            history_scroll_view_ptr: 0,
+            history_list_view_ptr: 0,
+            clipboard_scroll_view_ptr: 0,
+            clipboard_list_view_ptr: 0,
+            snippet_scroll_view_ptr: 0,
+            snippet_list_view_ptr: 0,
+            history_close_btn_ptr: 0,
+        }
+    }
+
+    #[test]
+    fn overlay_calls_are_no_ops_when_disabled() {
+        let overlay = detached_overlay();
+        assert!(!overlay.overlay_enabled());
+
+        let started = Instant::now();
+        overlay.show_recording();
+        overlay.show_transcribing();
+        overlay.set_preview("你好");
+        overlay.show_error("失败");
+        overlay.hide();
+        // 有浮层时淡出要在工作线程上等满 OVERLAY_FADE_TOTAL_MS，关闭时应立即返回。
+        overlay.fade_out_quick();
+        assert!(started.elapsed() < Duration::from_millis(OVERLAY_FADE_TOTAL_MS));
+    }
+
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> NSRect {
+        NSRect::new(NSPoint::new(x, y), NSSize::new(w, h))
+    }
+
+    /// 主显示器 1440x900，副显示器 1920x1080 在其右侧。
+    fn two_screens() -> [NSRect; 2] {
+        [
+            rect(0.0, 0.0, 1440.0, 900.0),
+            rect(1440.0, 0.0, 1920.0, 1080.0),
+        ]
+    }
+
+    #[test]
+    fn screen_with_largest_overlap_wins() {
+        let screens = two_screens();
+        assert_eq!(
+            pick_screen_for_rect(&screens, rect(100.0, 100.0, 400.0, 300.0)),
+            Some(0)
+        );
+        assert_eq!(
+            pick_screen_for_rect(&screens, rect(2000.0, 500.0, 10.0, 20.0)),
+            Some(1)
+        );
+        // 跨两块屏时取重叠更多的一侧。
+        assert_eq!(
+            pick_screen_for_rect(&screens, rect(1340.0, 100.0, 400.0, 300.0)),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn zero_size_rect_uses_its_point() {
+        let screens = two_screens();
+        assert_eq!(
+            pick_screen_for_rect(&screens, rect(1500.0, 1000.0, 0.0, 0.0)),
+            Some(1)
+        );
+        assert_eq!(
+            pick_screen_for_rect(&screens, rect(5000.0, 10.0, 0.0, 0.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn ax_rect_is_flipped_against_primary_height() {
+        let caret = AxRect {
+            origin: AxPoint {
+                x: 1500.0,
+                y: 100.0,
+            },
+            size: AxSize {
+                width: 2.0,
+                height: 20.0,
+            },
+        };
+        let flipped = ax_rect_to_cocoa(caret, 900.0);
+        assert_eq!((flipped.origin.x, flipped.origin.y), (1500.0, 780.0));
+        assert_eq!((flipped.size.width, flipped.size.height), (2.0, 20.0));
+    }
+
+    #[test]
+    fn hover_becomes_interactive_after_dwell() {
+        let dwell = OVERLAY_HOVER_DWELL_MS;
+        let phase = overlay_hover_transition(OverlayHoverPhase::Outside, true, 1000, dwell);
+        assert_eq!(phase, OverlayHoverPhase::Hovering(1000));
+        let phase = overlay_hover_transition(phase, true, 1000 + dwell - 1, dwell);
+        assert_eq!(phase, OverlayHoverPhase::Hovering(1000));
+        let phase = overlay_hover_transition(phase, true, 1000 + dwell, dwell);
+        assert_eq!(phase, OverlayHoverPhase::Interactive);
+        assert_eq!(
+            overlay_hover_transition(phase, true, 9999, dwell),
+            OverlayHoverPhase::Interactive
+        );
+    }
+
+    #[test]
+    fn leaving_reverts_to_click_through() {
+        let dwell = OVERLAY_HOVER_DWELL_MS;
+        for phase in [
+            OverlayHoverPhase::Hovering(0),
+            OverlayHoverPhase::Interactive,
+        ] {
+            assert_eq!(
+                overlay_hover_transition(phase, false, 10, dwell),
+                OverlayHoverPhase::Outside
+            );
+        }
+        // 短暂掠过不会变为可交互。
+        let phase = overlay_hover_transition(OverlayHoverPhase::Outside, true, 0, dwell);
+        let phase = overlay_hover_transition(phase, false, 100, dwell);
+        let phase = overlay_hover_transition(phase, true, dwell + 50, dwell);
+        assert_eq!(phase, OverlayHoverPhase::Hovering(dwell + 50));
+    }
+
+    #[test]
+    fn orb_accepts_only_decodable_audio_drops() {
+        for name in ["memo.wav", "Voice Memo.M4A", "clip.caf", "a.b.aac"] {
+            assert!(is_droppable_audio_file(Path::new(name)), "{name}");
+        }
+        for name in ["movie.mov", "notes.txt", "wav", "archive.wav.zip", "noext"] {
+            assert!(!is_droppable_audio_file(Path::new(name)), "{name}");
+        }
+    }
+
+    #[test]
+    fn orb_stays_opaque_until_idle_timeout() {
+        assert_eq!(orb_idle_alpha(0, 3000), 1.0);
+        assert_eq!(orb_idle_alpha(2999, 3000), 1.0);
+        // 0 表示关闭自动隐藏，空闲多久都不变淡。
+        assert_eq!(orb_idle_alpha(u64::MAX, 0), 1.0);
+    }
+
+    #[test]
+    fn orb_fades_linearly_to_idle_alpha() {
+        let half = orb_idle_alpha(3000 + ORB_IDLE_FADE_MS / 2, 3000);
+        assert!((half - (1.0 + ORB_IDLE_ALPHA) / 2.0).abs() < 1e-9);
+        assert_eq!(
+            orb_idle_alpha(3000 + ORB_IDLE_FADE_MS, 3000),
+            ORB_IDLE_ALPHA
+        );
+        assert_eq!(
+            orb_idle_alpha(3000 + ORB_IDLE_FADE_MS * 10, 3000),
+            ORB_IDLE_ALPHA
+        );
+        assert!(ORB_IDLE_ALPHA > 0.0);
+    }
+
+    #[test]
+    fn distance_to_orb_frame() {
+        let frame = rect(100.0, 100.0, 40.0, 40.0);
+        assert_eq!(distance_to_frame(NSPoint::new(120.0, 120.0), frame), 0.0);
+        assert_eq!(distance_to_frame(NSPoint::new(150.0, 120.0), frame), 10.0);
+        // 角外按欧氏距离计算。
+        assert_eq!(distance_to_frame(NSPoint::new(97.0, 96.0), frame), 5.0);
+    }
+
+    #[test]
+    fn overlay_radius_is_clamped_to_half_height() {
+        assert_eq!(clamp_overlay_radius(15.0, OVERLAY_HEIGHT), 15.0);
+        assert_eq!(clamp_overlay_radius(-4.0, OVERLAY_HEIGHT), 0.0);
+        assert_eq!(
+            clamp_overlay_radius(80.0, OVERLAY_HEIGHT),
+            OVERLAY_HEIGHT / 2.0
+        );
+        assert_eq!(clamp_overlay_radius(f64::NAN, OVERLAY_HEIGHT), 0.0);
+    }
+
+    #[test]
+    fn overlay_opacity_is_clamped_to_readable_range() {
+        assert_eq!(clamp_overlay_opacity(0.93), 0.93);
+        assert_eq!(clamp_overlay_opacity(0.1), 0.3);
+        assert_eq!(clamp_overlay_opacity(1.5), 1.0);
+        assert_eq!(clamp_overlay_opacity(f64::NAN), 1.0);
+    }
+
+    fn windows(window: usize, orb_window: usize) -> OverlayWindows {
+        OverlayWindows {
+            window,
+            status_badge: window + 1,
+            status_label: window + 2,
+            preview_label: window + 3,
+            pinyin_label: window + 4,
+            orb_window,
+            generation: 0,
+        }
+    }
+
+    #[test]
+    fn replacing_windows_returns_the_old_set_and_bumps_generation() {
+        let mut slot = windows(100, 200);
+        let old = slot.replace(windows(300, 200));
+        assert_eq!(old, windows(100, 200));
+        assert_eq!(slot.window, 300);
+        assert_eq!(slot.preview_label, 303);
+        assert_eq!(slot.generation, 1);
+        slot.replace(windows(300, 0));
+        assert_eq!(slot.generation, 2);
+    }
+
+    #[test]
+    fn only_replaced_windows_are_released() {
+        // 只换了主浮层：悬浮球仍在使用，不能释放。
+        assert_eq!(
+            windows_to_release(&windows(100, 200), &windows(300, 200)),
+            vec![100]
+        );
+        // 关闭悬浮球：旧悬浮球释放，主浮层不动。
+        assert_eq!(
+            windows_to_release(&windows(100, 200), &windows(100, 0)),
+            vec![200]
+        );
+        // 从无到有不需要释放任何窗口。
+        assert!(windows_to_release(&windows(0, 0), &windows(100, 200)).is_empty());
+        assert_eq!(
+            windows_to_release(&windows(100, 200), &windows(300, 400)),
+            vec![100, 200]
+        );
+    }
+
+    #[test]
+    fn contrast_ratio_matches_wcag_reference_values() {
+        let white = (1.0, 1.0, 1.0);
+        let black = (0.0, 0.0, 0.0);
+        assert!((contrast_ratio(white, black) - 21.0).abs() < 1e-9);
+        assert_eq!(contrast_ratio(white, black), contrast_ratio(black, white));
+        assert!((contrast_ratio(white, white) - 1.0).abs() < 1e-9);
+        // 白底上的 #777777 约 4.48:1，是刚好不达标的典型灰色。
+        let grey = (119.0 / 255.0, 119.0 / 255.0, 119.0 / 255.0);
+        assert!((contrast_ratio(grey, white) - 4.48).abs() < 0.01);
+        assert!(!meets_wcag_aa(grey, white));
+    }
+
+    #[test]
+    fn high_contrast_colors_pass_wcag_aa() {
+        assert!(meets_wcag_aa(HIGH_CONTRAST_TEXT, HIGH_CONTRAST_BACKGROUND));
+        assert!(contrast_ratio(HIGH_CONTRAST_TEXT, HIGH_CONTRAST_BACKGROUND) >= 7.0);
+    }
+
+    #[test]
+    fn history_audio_sidecars_are_created_and_evicted() {
+        let dir = std::env::temp_dir().join(format!("mofa-history-audio-{}", std::process::id()));
+        let newest = save_history_audio_in(&dir, &[0.1; 1600]).unwrap().unwrap();
+        assert!(newest.exists());
+        let too_long = vec![0.0; HISTORY_AUDIO_MAX_SECS * CAPTURE_TARGET_RATE as usize + 1];
+        assert_eq!(save_history_audio_in(&dir, &too_long).unwrap(), None);
+        assert_eq!(save_history_audio_in(&dir, &[]).unwrap(), None);
+
+        let older: Vec<PathBuf> = (0..2).map(|i| dir.join(format!("older-{i}.wav"))).collect();
+        for path in &older {
+            fs::write(path, b"RIFF").unwrap();
+        }
+        let entry = |audio: Option<&PathBuf>| HistoryEntry {
+            text: "文本".to_string(),
+            audio: audio.cloned(),
+        };
+        let mut entries = vec![
+            entry(Some(&newest)),
+            entry(None),
+            entry(Some(&older[0])),
+            entry(Some(&older[1])),
+        ];
+
+        // 只保留最新两条的录音；更早的摘下路径并删除文件。
+        let stale = evict_history_audio(&mut entries, 2);
+        assert_eq!(stale, vec![older[1].clone()]);
+        remove_history_audio_files(&stale);
+        assert!(!older[1].exists());
+        assert!(newest.exists() && older[0].exists());
+        assert_eq!(entries[2].audio.as_ref(), Some(&older[0]));
+        assert!(entries[3].audio.is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn clipboard_audio_path_prefers_copied_file_then_path_text() {
+        let home = Path::new("/Users/me");
+        let finder = Some(PathBuf::from("/Users/me/Desktop/memo.m4a"));
+        assert_eq!(
+            clipboard_audio_path(finder.clone(), Some("/tmp/other.wav"), Some(home)),
+            finder
+        );
+        assert_eq!(
+            clipboard_audio_path(None, Some("  \"~/Music/take 1.WAV\"\n"), Some(home)),
+            Some(PathBuf::from("/Users/me/Music/take 1.WAV"))
+        );
+        assert_eq!(
+            clipboard_audio_path(None, Some("file:///tmp/clip.caf"), Some(home)),
+            Some(PathBuf::from("/tmp/clip.caf"))
+        );
+    }
+
+    #[test]
+    fn clipboard_audio_path_ignores_non_audio_content() {
+        let home = Path::new("/Users/me");
+        let document = Some(PathBuf::from("/Users/me/notes.txt"));
+        assert_eq!(clipboard_audio_path(document, None, Some(home)), None);
+        assert_eq!(
+            clipboard_audio_path(None, Some("你好，世界"), Some(home)),
+            None
+        );
+        assert_eq!(
+            clipboard_audio_path(None, Some("relative/clip.wav"), Some(home)),
+            None
+        );
+        assert_eq!(
+            clipboard_audio_path(None, Some("/a.wav\n/b.wav"), Some(home)),
+            None
+        );
+        assert_eq!(clipboard_audio_path(None, Some("~/clip.wav"), None), None);
+    }
+
+    #[test]
+    fn fixed_overlay_position_is_converted_and_clamped() {
+        // 主显示器 1440x900，可用区域去掉顶部菜单栏与底部 Dock；右侧接一块 1920x1080 的副屏。
+        let screens = [
+            (
+                rect(0.0, 0.0, 1440.0, 900.0),
+                rect(0.0, 70.0, 1440.0, 805.0),
+            ),
+            (
+                rect(1440.0, 0.0, 1920.0, 1080.0),
+                rect(1440.0, 0.0, 1920.0, 1080.0),
+            ),
+        ];
+        // 左上角原点的 (100, 100) 换成 Cocoa 坐标：900 - 100 - 80。
+        assert_eq!(
+            fixed_overlay_origin((100.0, 100.0), 300.0, 80.0, &screens),
+            Some((100.0, 720.0))
+        );
+        assert_eq!(
+            fixed_overlay_origin((1500.0, 100.0), 300.0, 80.0, &screens),
+            Some((1500.0, 720.0))
+        );
+        // 落在所有屏幕之外：收回主显示器可用区域内。
+        assert_eq!(
+            fixed_overlay_origin((5000.0, -50.0), 300.0, 80.0, &screens),
+            Some((1134.0, 789.0))
+        );
+        // 贴近顶部：不压到菜单栏下面。
+        assert_eq!(
+            fixed_overlay_origin((0.0, 0.0), 300.0, 80.0, &screens),
+            Some((6.0, 789.0))
+        );
+        assert_eq!(fixed_overlay_origin((100.0, 100.0), 300.0, 80.0, &[]), None);
+    }
+
+    /// 假的测量：ASCII 每字 7pt、其它每字 14pt。
+    fn fake_width(line: &str) -> f64 {
+        line.chars()
+            .map(|c| if c.is_ascii() { 7.0 } else { 14.0 })
+            .sum()
+    }
+
+    #[test]
+    fn measured_wrap_breaks_mixed_text_at_measured_width() {
+        let wrapped = wrap_preview_text_by("ab你好世界hello world末尾", |line| {
+            fake_width(line) <= 70.0
+        });
+        assert_eq!(wrapped, "ab你好世界\nhello worl\nd末尾");
+    }
+
+    #[test]
+    fn measured_wrap_truncates_after_max_lines() {
+        let raw = (1..=8)
+            .map(|i| format!("第{i}行"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let wrapped = wrap_preview_text_by(&raw, |line| fake_width(line) <= 70.0);
+        let lines: Vec<&str> = wrapped.lines().collect();
+        assert_eq!(lines.len(), OVERLAY_PREVIEW_MAX_LINES);
+        assert_eq!(lines[5], "第6行…");
+    }
+
+    #[test]
+    fn heuristic_wrap_fits_more_latin_than_cjk_per_line() {
+        let cjk = wrap_preview_text(&"中".repeat(30));
+        assert_eq!(
+            cjk.lines().map(|l| l.chars().count()).collect::<Vec<_>>(),
+            vec![24, 6]
+        );
+        let latin = wrap_preview_text(&"a".repeat(50));
+        assert_eq!(
+            latin.lines().map(|l| l.chars().count()).collect::<Vec<_>>(),
+            vec![41, 9]
+        );
+        assert_eq!(wrap_preview_text("  \n "), "");
+    }
+
+    #[test]
+    fn snippet_file_skips_comments_and_expands_newlines() {
+        let content = "# 签名\n\n  Best,\\nAlice  \n北京市海淀区 1 号\n#注释\n";
+        assert_eq!(
+            parse_snippets(content),
+            vec!["Best,\nAlice".to_string(), "北京市海淀区 1 号".to_string()]
+        );
+        assert!(parse_snippets("").is_empty());
+    }
+
+    #[test]
+    fn history_tab_normalization_includes_snippets() {
+        assert_eq!(normalize_history_tab(0), 0);
+        assert_eq!(normalize_history_tab(1), 1);
+        assert_eq!(normalize_history_tab(HISTORY_TAB_SNIPPETS), 2);
+        assert_eq!(normalize_history_tab(3), 0);
+        assert_eq!(normalize_history_tab(usize::MAX), 0);
+    }
+
+    #[test]
+    fn preview_placeholder_prefers_config_and_falls_back_per_language() {
+        assert_eq!(
+            preview_placeholder_text(Some(" Hold fn to talk "), AsrLanguage::Zh),
+            "Hold fn to talk"
+        );
+        assert_eq!(
+            preview_placeholder_text(None, AsrLanguage::Auto),
+            "按住快捷键说话"
+        );
+        assert_eq!(
+            preview_placeholder_text(Some("  "), AsrLanguage::En),
+            "Hold the hotkey to speak"
+        );
+        let cfg = parse_app_config("preview_placeholder=\"按住 fn 开始\"\n");
+        assert_eq!(cfg.preview_placeholder.as_deref(), Some("按住 fn 开始"));
+    }
+
+    #[test]
+    fn long_preview_placeholder_is_truncated_then_wrapped() {
+        let text = preview_placeholder_text(Some(&"说".repeat(60)), AsrLanguage::Zh);
+        assert_eq!(text.chars().count(), PREVIEW_PLACEHOLDER_MAX_CHARS);
+        assert!(text.ends_with('…'));
+
+        let wrapped = wrap_preview_text_by(&text, |line| fake_width(line) <= 140.0);
+        let lines: Vec<&str> = wrapped.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[..3].iter().all(|l| l.chars().count() == 10));
+        assert_eq!(lines[3], format!("{}…", "说".repeat(9)));
+    }
+
+    #[test]
+    fn fullscreen_option_raises_level_and_joins_fullscreen_spaces() {
+        use NSWindowCollectionBehavior as B;
+
+        let (level, behavior) = floating_window_placement(false);
+        assert_eq!(level, (NSMainMenuWindowLevel + 1) as i64);
+        assert_eq!(
+            behavior,
+            B::NSWindowCollectionBehaviorCanJoinAllSpaces | B::NSWindowCollectionBehaviorTransient
+        );
+
+        let (level, behavior) = floating_window_placement(true);
+        assert_eq!(level, OVERLAY_FULLSCREEN_WINDOW_LEVEL);
+        assert!(level > (NSMainMenuWindowLevel + 1) as i64);
+        assert!(behavior.contains(
+            B::NSWindowCollectionBehaviorCanJoinAllSpaces
+                | B::NSWindowCollectionBehaviorFullScreenAuxiliary
+                | B::NSWindowCollectionBehaviorStationary
+        ));
+        // Transient 会让窗口在全屏空间里被隐藏
+        assert!(!behavior.contains(B::NSWindowCollectionBehaviorTransient));
+    }
+
+    fn image(len: usize) -> ClipboardHistoryItem {
+        ClipboardHistoryItem::Image {
+            data: vec![0; len],
+            uti: "public.png".to_string(),
+        }
+    }
+
+    fn image_sizes(items: &[ClipboardHistoryItem]) -> Vec<usize> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                ClipboardHistoryItem::Image { data, .. } => Some(data.len()),
+                ClipboardHistoryItem::Text(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn clipboard_images_are_evicted_oldest_first_by_total_size() {
+        // 新到旧：40、text、30、text、50、20
+        let mut items = vec![
+            image(40),
+            ClipboardHistoryItem::Text("a".to_string()),
+            image(30),
+            ClipboardHistoryItem::Text("b".to_string()),
+            image(50),
+            image(20),
+        ];
+        evict_clipboard_images(&mut items, 100);
+        assert_eq!(image_sizes(&items), vec![40, 30]);
+        // 文本条目全部保留
+        assert_eq!(items.len(), 4);
+
+        // 未超出时不动
+        let mut items = vec![image(10), image(20)];
+        evict_clipboard_images(&mut items, 30);
+        assert_eq!(image_sizes(&items), vec![10, 20]);
+    }
+
+    #[test]
+    fn newest_clipboard_image_is_kept_even_over_cap() {
+        let mut items = vec![image(500), image(10)];
+        evict_clipboard_images(&mut items, 100);
+        assert_eq!(image_sizes(&items), vec![500]);
+    }
+
+    #[test]
+    fn oversized_clipboard_images_scale_to_max_edge() {
+        assert_eq!(clipboard_image_scaled_size(1920, 1080, 2048), None);
+        assert_eq!(
+            clipboard_image_scaled_size(5120, 2880, 2048),
+            Some((2048, 1152))
+        );
+        assert_eq!(
+            clipboard_image_scaled_size(1000, 4096, 2048),
+            Some((500, 2048))
+        );
+        assert_eq!(clipboard_image_scaled_size(0, 0, 2048), None);
+    }
+}
@@ -1,28 +1,347 @@
+/// 失败提示音；抽成 trait 便于替换实现。
+trait FeedbackSound {
+    fn play_error(&self);
+}
+
+struct SystemSound;
+
+impl FeedbackSound for SystemSound {
+    fn play_error(&self) {
+        // NSSound.play 本身是异步的，这里只派发到主队列，不阻塞流水线。
+        Queue::main().exec_async(|| unsafe {
+            let sound: id = msg_send![class!(NSSound), soundNamed: ns_string("Basso")];
+            if sound != nil {
+                let _: BOOL = msg_send![sound, play];
+            }
+        });
+    }
+}
+
+fn play_error_feedback(sound: &dyn FeedbackSound, enabled: bool) {
+    if enabled {
+        sound.play_error();
+    }
+}
+
+/// 一次录音没有产生输出的原因（debug_drops=true 时写入日志与提示）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DropReason {
+    TooShort,
+    Silence,
+    AsrUnavailable,
+    AsrFailed,
+    EmptyTranscript,
+    TemplateNoise,
+    LlmEmpty,
+    ConfirmDiscarded,
+}
+
+impl DropReason {
+    fn detail(self) -> &'static str {
+        match self {
+            Self::TooShort => "录音不足 0.2 秒",
+            Self::Silence => "未检测到语音（静音或持续噪声）",
+            Self::AsrUnavailable => "ASR 模型未加载",
+            Self::AsrFailed => "Whisper 推理出错",
+            Self::EmptyTranscript => "识别结果为空或只有语气词",
+            Self::TemplateNoise => "识别结果为模板幻听句（如“谢谢观看”）",
+            Self::LlmEmpty => "LLM 输出为空或无效，且 llm_fallback=off",
+            Self::ConfirmDiscarded => "在确认框中放弃",
+        }
+    }
+}
+
+fn audio_drop_reason(samples: &[f32]) -> Option<DropReason> {
+    if samples.len() < 3200 {
+        // < 0.2s @16k
+        Some(DropReason::TooShort)
+    } else if !mofa_input::audio::vad::detect_speech(samples, CAPTURE_TARGET_RATE) {
+        Some(DropReason::Silence)
+    } else {
+        None
+    }
+}
+
+/// 结果停留期间收到新的热键信号（如紧接着又按下）时立即结束停留，返回该信号交给下一轮处理。
+/// 单独的松开不算：切换/锁定模式下结束录音那一下按键，松开往往落在停留期间。
+fn hold_result_overlay(rx: &Receiver<HotkeySignal>, hold: Duration) -> Option<HotkeySignal> {
+    let deadline = Instant::now() + hold;
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(left) {
+            Ok(HotkeySignal::Up { .. }) => continue,
+            other => return other.ok(),
+        }
+    }
+}
+
+/// hybrid 模式下按住超过该时长再松开即锁定录音。
+const HYBRID_LATCH_MS: u64 = 800;
+
+/// 热键模式对一次按下/松开的处理。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HotkeyModeAction {
+    /// 按原有的按住说话流程处理
+    Pass,
+    Ignore,
+    /// 松开但继续录音，等下一次按下再结束
+    Latch,
+    /// 按下即结束录音（按松开处理）
+    Stop,
+}
+
+/// `held_ms` 为本次录音从按下到现在的时长；`latched` 表示 hybrid 已锁定。
+fn hotkey_mode_action(
+    mode: HotkeyMode,
+    is_down: bool,
+    recording: bool,
+    latched: bool,
+    held_ms: u64,
+) -> HotkeyModeAction {
+    if mode == HotkeyMode::Hold {
+        return HotkeyModeAction::Pass;
+    }
+    if is_down {
+        return if recording && (mode == HotkeyMode::Toggle || latched) {
+            HotkeyModeAction::Stop
+        } else {
+            HotkeyModeAction::Pass
+        };
+    }
+    if !recording || mode == HotkeyMode::Toggle || latched {
+        HotkeyModeAction::Ignore
+    } else if held_ms >= HYBRID_LATCH_MS {
+        HotkeyModeAction::Latch
+    } else {
+        HotkeyModeAction::Pass
+    }
+}
+
+fn transcript_drop_reason(text: &str, strict: bool) -> Option<DropReason> {
+    if !should_drop_transcript(text, strict) {
+        None
+    } else if strict && is_template_noise_text(text) {
+        Some(DropReason::TemplateNoise)
+    } else {
+        Some(DropReason::EmptyTranscript)
+    }
+}
+
+/// 丢弃日志行；上下文可能含转写原文，隐私模式下不写入。
+fn drop_log_line(reason: DropReason, context: &str, privacy: bool) -> String {
+    let context = if privacy { "" } else { context };
+    format!(
+        "[mofa-ime] 丢弃 {:?}: {} {context}",
+        reason,
+        reason.detail()
+    )
+}
+
+/// debug_drops 打开时记录丢弃原因，并把具体原因显示在菜单提示与浮层中。
+fn report_drop(
+    reason: DropReason,
+    debug: bool,
+    context: &str,
+    monitor: MonitorHandle,
+    overlay: OverlayHandle,
+    default_message: &str,
+) {
+    if debug {
+        eprintln!("{}", drop_log_line(reason, context, privacy_mode()));
+        monitor.set_hint(&format!("丢弃原因: {}", reason.detail()));
+        overlay.show_error(reason.detail());
+    } else {
+        overlay.show_error(default_message);
+    }
+}
+
+/// 最近一次注入的文本，供改写热键在窗口期内退格替换。
+struct LastInjection {
+    raw_text: String,
+    prefix: String,
+    injected: String,
+    at: Instant,
+    bundle_id: Option<String>,
+    next_style: usize,
+}
+
+impl LastInjection {
+    /// 窗口期内且前台仍是同一应用时才能安全地退格替换。
+    fn can_repolish(&self, now: Instant, window_ms: u64, front_bundle: Option<&str>) -> bool {
+        now.duration_since(self.at) <= Duration::from_millis(window_ms)
+            && self.bundle_id.as_deref() == front_bundle
+    }
+
+    /// 轮换到下一条备选指令；提示词始终基于 `raw_text`（ASR 原文）生成。
+    fn next_repolish(&mut self) -> RepolishStyle {
+        let style = RepolishStyle::ALL[self.next_style % RepolishStyle::ALL.len()];
+        self.next_style += 1;
+        style
+    }
+
+    /// 改写成功后记下新文本，窗口期从替换时刻重新计算。
+    fn replaced(&mut self, injected: String, now: Instant) {
+        self.injected = injected;
+        self.at = now;
+    }
+}
+
+/// 注入前对结果文本的统一整理：去 Markdown 标记、口述缩写展开、中英文之间加空格；主输出与改写共用。
+fn finish_output_text(
+    text: &str,
+    cfg: &AppConfig,
+    expansions: &mofa_input::text::ExpansionMap,
+) -> String {
+    // 纯文本输入框里不要出现 LLM 附带的 **、列表符号等标记。
+    let mut text = if cfg.strip_markdown {
+        strip_markdown(text)
+    } else {
+        text.to_string()
+    };
+    // 口述缩写展开作用在润色后的最终文本上，与识别阶段的符号表互不影响。
+    text = mofa_input::text::apply_expansions(&text, expansions);
+    if cfg.auto_space_cjk {
+        text = mofa_input::text::space_cjk_latin(&text);
+    }
+    text
+}
+
+/// 改写热键与托盘“重新润色上一段”共用：LLM 对保存的 ASR 原文的输出按主流程同样整理；
+/// 输出无效时返回 None（此时不回退原文，上一段已经注入过了）。
+fn repolish_raw_text(
+    raw_text: &str,
+    llm_out: Option<String>,
+    cfg: &AppConfig,
+    expansions: &mofa_input::text::ExpansionMap,
+) -> Option<String> {
+    let llm_out =
+        llm_out.map(|out| trim_added_terminal_period(raw_text, &normalize_transcript(&out)));
+    match decide_refine_outcome(llm_out, false) {
+        RefineOutcome::Refined(text) => Some(finish_output_text(&text, cfg, expansions)),
+        RefineOutcome::FallbackRaw | RefineOutcome::Failed => None,
+    }
+}
+
+/// show_pinyin 开启且文本里有收录的汉字时，返回预览下方的拼音行。
+fn pinyin_preview_line(text: &str, enabled: bool) -> Option<String> {
+    let has_hanzi = text
+        .chars()
+        .any(|c| mofa_input::text::char_pinyin(c).is_some());
+    if !enabled || !has_hanzi {
+        return None;
+    }
+    Some(mofa_input::text::to_pinyin(text))
+}
+
+/// 松开热键后的宽限期结果。
+enum ReleaseGrace {
+    /// 宽限期内再次按下：合并为同一段录音
+    Resume,
+    /// 照常结束录音；期间收到的其他信号交回主循环处理
+    Finish(Option<HotkeySignal>),
+}
+
+/// 松开后最多等待 `grace`：再次按下则继续录音，其他信号不丢弃而是随结果带回。
+fn wait_release_grace(rx: &mpsc::Receiver<HotkeySignal>, grace: Duration) -> ReleaseGrace {
+    match rx.recv_timeout(grace) {
+        Ok(HotkeySignal::Down { .. }) => ReleaseGrace::Resume,
+        Ok(other) => ReleaseGrace::Finish(Some(other)),
+        Err(_) => ReleaseGrace::Finish(None),
+    }
+}
+
+/// 上一段录音结束后的冷却期内不再开始新录音，避免快速双击造成重叠。
+fn recording_cooldown_elapsed(last_end: Option<Instant>, now: Instant, cooldown_ms: u64) -> bool {
+    match last_end {
+        Some(end) => now.saturating_duration_since(end) >= Duration::from_millis(cooldown_ms),
+        None => true,
+    }
+}
+
+/// llm_lazy：还没加载过 LLM 时推迟加载，直到某次结果确实要用 LLM；加载后常驻。
+fn defer_llm_load(lazy: bool, llm_loaded: bool, llm_on_demand: bool) -> bool {
+    lazy && !llm_loaded && !llm_on_demand
+}
+
+/// `on_demand` 在松开热键、本次结果要用 LLM 时传入浮层，llm_lazy 下在此首次加载并提示。
 fn refresh_models(
     model_base: &Path,
-    cfg: AppConfig,
+    cfg: &AppConfig,
     asr: &mut Option<mofa_input::asr::AsrSession>,
     asr_loaded_path: &mut Option<PathBuf>,
     llm: &mut Option<mofa_input::llm::ChatSession>,
     llm_loaded_path: &mut Option<PathBuf>,
+    llm_registry: &mut LlmRegistry,
     monitor: MonitorHandle,
+    on_demand: Option<OverlayHandle>,
 ) {
-    let desired_asr = choose_asr_model(model_base, cfg.asr_model);
+    let mut desired_asr = choose_asr_model(model_base, cfg.asr_model, cfg.language);
+    let mut desired_llm = choose_llm_model(model_base, cfg.llm_model);
+
+    // 仅对自动选型做内存降级；用户显式选择的模型保持不变。
+    let mem_gb = total_memory_gb().unwrap_or(32);
+    let budget_mb = model_memory_budget_mb(mem_gb, cfg.memory_budget_gb);
+    let llm_mb = if desired_llm.is_some() {
+        model_file_mb(desired_llm.as_deref()) + LLM_RUNTIME_OVERHEAD_MB
+    } else {
+        0
+    };
+    let downgrade =
+        decide_memory_downgrade(budget_mb, model_file_mb(desired_asr.as_deref()), llm_mb);
+    let mut downgraded = false;
+    if downgrade != MemoryDowngrade::None && cfg.llm_model == LlmModelChoice::Auto {
+        if let Some(p) = choose_llm_model_auto(model_base, true) {
+            downgraded |= Some(&p) != desired_llm.as_ref();
+            desired_llm = Some(p);
+        }
+    }
+    if downgrade == MemoryDowngrade::AsrAndLlm && cfg.asr_model == AsrModelChoice::Auto {
+        if let Some(p) = choose_asr_model_auto(model_base, true) {
+            let p = prefer_english_variant(p, cfg.language);
+            downgraded |= Some(&p) != desired_asr.as_ref();
+            desired_asr = Some(p);
+        }
+    }
+    let defer_llm = defer_llm_load(cfg.llm_lazy, llm_loaded_path.is_some(), on_demand.is_some());
+    let models_changed = desired_asr != *asr_loaded_path || desired_llm != *llm_loaded_path;
+    let planned_llm_mb = match desired_llm.as_deref() {
+        Some(path) if !defer_llm => model_file_mb(Some(path)) + LLM_RUNTIME_OVERHEAD_MB,
+        _ => 0,
+    };
+    let over_budget = if models_changed {
+        memory_budget_warning(
+            budget_mb,
+            model_file_mb(desired_asr.as_deref()),
+            planned_llm_mb,
+        )
+    } else {
+        None
+    };
+
     if desired_asr != *asr_loaded_path {
         *asr = None;
         *asr_loaded_path = desired_asr.clone();
 
         if let Some(path) = desired_asr {
-            match mofa_input::asr::AsrSession::new(&path) {
-                Ok(s) => {
-                    *asr = Some(s);
-                    if cfg.asr_model != AsrModelChoice::Auto {
-                        monitor.set_hint(&format!("ASR 已切换: {}", cfg.asr_model.label()));
+            if let Err(msg) = validate_model_file(&path, ModelKind::Asr) {
+                eprintln!("[mofa-ime] {msg} ({:?})", path);
+                monitor.set_hint(&msg);
+            } else {
+                match mofa_input::asr::AsrSession::new(&path) {
+                    Ok(s) => {
+                        let s = warmed(s, cfg.warmup);
+                        let info = s.model_info();
+                        *asr = Some(s);
+                        if cfg.asr_model != AsrModelChoice::Auto {
+                            monitor.set_hint(&format!("ASR 已切换: {}", cfg.asr_model.label()));
+                        } else {
+                            monitor.set_hint(&format!("当前: {}", info.label()));
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("[mofa-ime] ASR 加载失败 {:?}: {e}", path);
+                        monitor.set_hint("ASR 加载失败");
                     }
-                }
-                Err(e) => {
-                    eprintln!("[mofa-ime] ASR 加载失败 {:?}: {e}", path);
-                    monitor.set_hint("ASR 加载失败");
                 }
             }
         } else {
@@ -30,32 +349,221 @@ fn refresh_models(
         }
     }
 
-    let desired_llm = choose_llm_model(model_base, cfg.llm_model);
-    if desired_llm != *llm_loaded_path {
+    if desired_llm != *llm_loaded_path && !defer_llm {
+        let first_lazy_load = cfg.llm_lazy && llm_loaded_path.is_none();
         *llm = None;
         *llm_loaded_path = desired_llm.clone();
 
         if let Some(path) = desired_llm {
-            match mofa_input::llm::ChatSession::new(&path) {
-                Ok(s) => {
-                    *llm = Some(s);
-                    if cfg.llm_model != LlmModelChoice::Auto {
-                        monitor.set_hint(&format!("LLM 已切换: {}", cfg.llm_model.label()));
-                    }
+            if first_lazy_load {
+                eprintln!("[mofa-ime] 首次加载 LLM {:?}", path);
+                monitor.set_hint("首次加载 LLM…");
+                if let Some(overlay) = on_demand {
+                    overlay.set_status("首次加载 LLM");
                 }
-                Err(e) => {
-                    eprintln!("[mofa-ime] LLM 加载失败 {:?}: {e}", path);
-                    monitor.set_hint("LLM 加载失败");
+            }
+            if let Err(msg) = validate_model_file(&path, ModelKind::Llm) {
+                eprintln!("[mofa-ime] {msg} ({:?})", path);
+                monitor.set_hint(&msg);
+            } else {
+                match mofa_input::llm::ChatSession::new(&path) {
+                    Ok(s) => {
+                        *llm = Some(warmed(s, cfg.warmup));
+                        if cfg.llm_model != LlmModelChoice::Auto {
+                            monitor.set_hint(&format!("LLM 已切换: {}", cfg.llm_model.label()));
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("[mofa-ime] LLM 加载失败 {:?}: {e}", path);
+                        monitor.set_hint("LLM 加载失败");
+                    }
                 }
             }
         } else {
             monitor.set_hint("未发现 LLM，默认直发识别文本");
         }
     }
+
+    // 按用途的额外模型：与主 LLM 相同的文件不重复加载；超出内存预算则不加载。
+    let asr_mb = model_file_mb(asr_loaded_path.as_deref());
+    let primary_mb = if llm.is_some() {
+        model_file_mb(llm_loaded_path.as_deref()) + LLM_RUNTIME_OVERHEAD_MB
+    } else {
+        0
+    };
+    for role in LlmRole::ALL {
+        if defer_llm {
+            break;
+        }
+        let desired = cfg
+            .llm_model_for(role)
+            .and_then(|choice| choice.file_name())
+            .map(|name| model_base.join(name))
+            .filter(|p| p.exists() && Some(p) != llm_loaded_path.as_ref());
+        if desired.as_deref() == llm_registry.loaded_path(role) {
+            continue;
+        }
+        llm_registry.remove(role);
+        let Some(path) = desired else {
+            continue;
+        };
+        let used_mb = asr_mb + primary_mb + llm_registry.loaded_mb();
+        let candidate_mb = model_file_mb(Some(&path)) + LLM_RUNTIME_OVERHEAD_MB;
+        if !role_model_fits_budget(budget_mb, used_mb, candidate_mb) {
+            monitor.set_hint(&format!("内存不足，未加载{}模型", role.label()));
+            continue;
+        }
+        if let Err(msg) = validate_model_file(&path, ModelKind::Llm) {
+            eprintln!("[mofa-ime] {msg} ({:?})", path);
+            monitor.set_hint(&msg);
+            continue;
+        }
+        match mofa_input::llm::ChatSession::new(&path) {
+            Ok(s) => llm_registry.insert(role, path, warmed(s, cfg.warmup)),
+            Err(e) => {
+                eprintln!("[mofa-ime] {}模型加载失败 {:?}: {e}", role.label(), path);
+            }
+        }
+    }
+
+    if downgraded && models_changed {
+        monitor.set_hint(&format!("内存 {mem_gb}GB 偏小，已自动降级为轻量模型"));
+    } else if let Some(warning) = over_budget {
+        eprintln!("[mofa-ime] {warning}");
+        monitor.set_hint(&warning);
+    }
+}
+
+/// 模型刚加载时跑一次极短推理，让计算图/Metal 缓冲就绪，避免首次使用卡顿。
+trait Warmup {
+    fn warmup(&self);
+}
+
+impl Warmup for mofa_input::asr::AsrSession {
+    fn warmup(&self) {
+        let silence = vec![0.0f32; 16_000];
+        if let Err(e) = self.transcribe(&silence) {
+            eprintln!("[mofa-ime] ASR 预热失败: {e}");
+        }
+    }
+}
+
+impl Warmup for mofa_input::llm::ChatSession {
+    fn warmup(&self) {
+        self.clear();
+        if let Err(e) = self.send("你好", 1, 0.0) {
+            eprintln!("[mofa-ime] LLM 预热失败: {e}");
+        }
+        self.clear();
+    }
 }
 
-fn spawn_pipeline_worker(
+/// 每次加载模型后调用一次：`warmup=true` 时预热后再交给调用方。
+fn warmed<S: Warmup>(session: S, enabled: bool) -> S {
+    if enabled {
+        session.warmup();
+    }
+    session
+}
+
+/// 加速手势：临时加载已安装的最大 LLM 供本段使用，用完即释放；与主 LLM 相同时返回 None 直接用主 LLM。
+fn load_boost_llm(
+    model_base: &Path,
+    loaded: Option<&Path>,
+) -> Option<mofa_input::llm::ChatSession> {
+    let names = LlmModelChoice::ALL.iter().filter_map(|c| c.file_name());
+    let path = boosted_model_path(model_base, names).filter(|p| Some(p.as_path()) != loaded)?;
+    if let Err(msg) = validate_model_file(&path, ModelKind::Llm) {
+        eprintln!("[mofa-ime] {msg} ({:?})", path);
+        return None;
+    }
+    match mofa_input::llm::ChatSession::new(&path) {
+        Ok(s) => Some(s),
+        Err(e) => {
+            eprintln!("[mofa-ime] 加速 LLM 加载失败 {:?}: {e}", path);
+            None
+        }
+    }
+}
+
+/// 原文加润色指令超出模型上下文时按句分段润色再拼接，避免提示词被截断或报错。
+/// `build_prompt` 为每段原文生成提示词（主流程润色或改写热键的备选指令）。
+fn refine_within_context(
+    chat: &mofa_input::llm::ChatSession,
+    raw_text: &str,
+    build_prompt: impl Fn(&str) -> String,
+) -> Result<String> {
+    let instruction_tokens = chat.count_tokens(&build_prompt(""));
+    let raw_tokens = chat.count_tokens(raw_text);
+    let Some(max_chars) = refine_chunk_chars(
+        raw_text.chars().count(),
+        raw_tokens,
+        instruction_tokens,
+        chat.context_size(),
+    ) else {
+        chat.clear();
+        return Ok(chat.send(&build_prompt(raw_text), REFINE_MAX_TOKENS, 0.1)?);
+    };
+
+    let chunks = split_at_sentences(raw_text, max_chars);
+    eprintln!(
+        "[mofa-ime] 润色提示词超出上下文（{} tokens，上下文 {}），分 {} 段润色",
+        instruction_tokens + raw_tokens,
+        chat.context_size(),
+        chunks.len()
+    );
+    let mut parts = Vec::with_capacity(chunks.len());
+    for chunk in &chunks {
+        chat.clear();
+        let out = chat.send(&build_prompt(chunk), REFINE_MAX_TOKENS, 0.1)?;
+        parts.push(normalize_transcript(&out));
+    }
+    Ok(join_refined_chunks(&parts))
+}
+
+/// 拖到悬浮球或剪贴板里的音频：转写后写入剪贴板与历史，不注入到当前输入框。
+fn transcribe_dropped_file(
+    asr: Option<&mofa_input::asr::AsrSession>,
+    path: &Path,
+    overlay: OverlayHandle,
+    monitor: MonitorHandle,
+) {
+    let Some(asr) = asr else {
+        monitor.set_hint("ASR 未就绪，无法转写文件");
+        overlay.show_error("ASR 未就绪");
+        std::thread::sleep(Duration::from_millis(900));
+        overlay.fade_out_quick();
+        return;
+    };
+    monitor.set_state("转写文件");
+    overlay.show_transcribing();
+    match transcribe_file(asr, path, TranscriptFormat::Txt) {
+        Ok(text) if !text.trim().is_empty() => {
+            copy_text_to_clipboard(&text);
+            add_history_item(&text, overlay);
+            monitor.set_asr(&text);
+            monitor.set_state("已复制");
+            overlay.show("已复制", privacy_redact(&text, privacy_mode()));
+            std::thread::sleep(Duration::from_millis(RESULT_OVERLAY_HOLD_MS));
+        }
+        Ok(_) => {
+            overlay.show_error("文件中未识别到有效内容");
+            std::thread::sleep(Duration::from_millis(900));
+        }
+        Err(e) => {
+            eprintln!("[mofa-ime] 文件转写失败 {:?}: {e}", path);
+            monitor.set_hint("文件转写失败");
+            overlay.show_error("文件转写失败");
+            std::thread::sleep(Duration::from_millis(900));
+        }
+    }
+    monitor.set_state("就绪");
+    overlay.fade_out_quick();
+}
+
+fn spawn_pipeline_worker<S: AudioSource + 'static>(
     rx: Receiver<HotkeySignal>,
+    tx: Sender<HotkeySignal>,
     status: StatusHandle,
     monitor: MonitorHandle,
     overlay: OverlayHandle,
@@ -65,12 +573,18 @@ fn spawn_pipeline_worker(
     set_orb_click_handler(orb_tx);
 
     std::thread::spawn(move || {
+        let _guard = WorkerGuard::register();
         let model_base = model_base_dir();
 
         let mut asr: Option<mofa_input::asr::AsrSession> = None;
         let mut asr_loaded_path: Option<PathBuf> = None;
         let mut llm: Option<mofa_input::llm::ChatSession> = None;
         let mut llm_loaded_path: Option<PathBuf> = None;
+        let mut llm_registry = LlmRegistry::default();
+        // asr_length_adaptive / 加速手势：与主 ASR 不同的模型单独缓存，避免来回重载主模型。
+        let mut length_asr: Option<(PathBuf, mofa_input::asr::AsrSession)> = None;
+        // 按住 Alt 按下热键：本段录音改用已安装的最大模型。
+        let mut boost = false;
 
         monitor.set_state("就绪");
         monitor.set_asr("-");
@@ -80,21 +594,49 @@ fn spawn_pipeline_worker(
         let startup_cfg = load_app_config();
         refresh_models(
             &model_base,
-            startup_cfg,
+            &startup_cfg,
             &mut asr,
             &mut asr_loaded_path,
             &mut llm,
             &mut llm_loaded_path,
+            &mut llm_registry,
             monitor,
+            None,
         );
 
-        let mut recorder: Option<ActiveRecorder> = None;
+        let error_sound = SystemSound;
+        let mut recorder: Option<S> = None;
         let mut recording_ticker: Option<RecordingTicker> = None;
+        let mut app_switch: Option<AppSwitchWatcher> = None;
+        let mut incremental: Option<IncrementalInjector> = None;
+        let mut continuous: Option<ContinuousDictation> = None;
         let mut history_visible = false;
+        let mut last_recording_end: Option<Instant> = None;
+        // 本次录音按下的时刻与 hybrid 是否已锁定，供 hotkey_mode 判断松开的含义。
+        let mut pressed_at: Option<Instant> = None;
+        let mut latched = false;
+        let mut last_injection: Option<LastInjection> = None;
+        // 上一段成功发送的 ASR 原文，供托盘“重新润色上一段”使用；新录音不清除。
+        let mut last_raw_text: Option<String> = None;
+        let mut preroll: Option<PrerollCapture> = None;
+        // 结果停留期间提前收到的热键信号
+        let mut pending_signal: Option<HotkeySignal> = None;
+        PrerollCapture::sync(
+            &mut preroll,
+            startup_cfg.mic_channel,
+            startup_cfg.preroll_ms,
+        );
 
         loop {
+            if shutdown_requested() {
+                break;
+            }
             // Check for hotkey signal (blocking with timeout)
-            let sig = match rx.recv_timeout(Duration::from_millis(100)) {
+            let received = match pending_signal.take() {
+                Some(sig) => Ok(sig),
+                None => rx.recv_timeout(Duration::from_millis(100)),
+            };
+            let sig = match received {
                 Ok(s) => s,
                 Err(mpsc::RecvTimeoutError::Timeout) => {
                     // Check orb click events during timeout
@@ -109,6 +651,12 @@ fn spawn_pipeline_worker(
                                     overlay.hide_history();
                                 }
                             }
+                            OrbCommand::TranscribeFile(path) => {
+                                transcribe_dropped_file(asr.as_ref(), &path, overlay, monitor);
+                            }
+                            OrbCommand::RepolishLast => {
+                                pending_signal = Some(HotkeySignal::RepolishLast);
+                            }
                         }
                     }
                     continue;
@@ -127,25 +675,320 @@ fn spawn_pipeline_worker(
                             overlay.hide_history();
                         }
                     }
+                    OrbCommand::TranscribeFile(path) => {
+                        transcribe_dropped_file(asr.as_ref(), &path, overlay, monitor);
+                    }
+                    OrbCommand::RepolishLast => {
+                        pending_signal = Some(HotkeySignal::RepolishLast);
+                    }
                 }
             }
 
+            let sig = match sig {
+                HotkeySignal::Down { .. } | HotkeySignal::Up { .. } => {
+                    let is_down = matches!(sig, HotkeySignal::Down { .. });
+                    let held_ms = pressed_at.map_or(0, |t| t.elapsed().as_millis() as u64);
+                    let recording = recorder.is_some();
+                    match hotkey_mode_action(hotkey_mode(), is_down, recording, latched, held_ms) {
+                        HotkeyModeAction::Pass => sig,
+                        HotkeyModeAction::Ignore => continue,
+                        HotkeyModeAction::Latch => {
+                            latched = true;
+                            monitor.set_hint("录音已锁定，再按一次结束");
+                            continue;
+                        }
+                        HotkeyModeAction::Stop => HotkeySignal::Up { modifiers: 0 },
+                    }
+                }
+                HotkeySignal::ForceStop => HotkeySignal::Up { modifiers: 0 },
+                other => other,
+            };
+
             match sig {
-                HotkeySignal::Down => {
+                HotkeySignal::CycleModel(kind) => {
+                    // 录音中不切换，避免本段录音用到一半换模型。
+                    if recorder.is_some() {
+                        continue;
+                    }
+                    let title = match kind {
+                        ModelKind::Asr => "识别模型",
+                        ModelKind::Llm => "润色模型",
+                    };
+                    match cycle_model_in_config(&model_base, kind) {
+                        Ok(label) => {
+                            let app_cfg = load_app_config();
+                            refresh_models(
+                                &model_base,
+                                &app_cfg,
+                                &mut asr,
+                                &mut asr_loaded_path,
+                                &mut llm,
+                                &mut llm_loaded_path,
+                                &mut llm_registry,
+                                monitor,
+                                None,
+                            );
+                            monitor.set_hint(&format!("{title}: {label}"));
+                            overlay.show(title, label);
+                        }
+                        Err(e) => {
+                            eprintln!("[mofa-ime] 切换模型失败: {e}");
+                            overlay.show_error("切换模型失败");
+                        }
+                    }
+                    std::thread::sleep(Duration::from_millis(RESULT_OVERLAY_HOLD_MS));
+                    overlay.fade_out_quick();
+                }
+                HotkeySignal::TranscribeClipboard => {
+                    if recorder.is_some() {
+                        continue;
+                    }
+                    match read_clipboard_audio_path().filter(|p| p.is_file()) {
+                        Some(path) => {
+                            transcribe_dropped_file(asr.as_ref(), &path, overlay, monitor);
+                        }
+                        None => {
+                            monitor.set_hint("剪贴板中没有音频文件");
+                            overlay.show_error("剪贴板中没有音频文件");
+                            std::thread::sleep(Duration::from_millis(900));
+                            overlay.fade_out_quick();
+                        }
+                    }
+                }
+                HotkeySignal::Repolish => {
+                    if recorder.is_some() {
+                        continue;
+                    }
+                    let app_cfg = load_app_config();
+                    let front_bundle = frontmost_bundle_id();
+                    let Some(last) = last_injection.as_mut().filter(|last| {
+                        last.can_repolish(
+                            Instant::now(),
+                            app_cfg.repolish_window_ms,
+                            front_bundle.as_deref(),
+                        )
+                    }) else {
+                        overlay.show_error("没有可改写的最近输入");
+                        std::thread::sleep(Duration::from_millis(RESULT_OVERLAY_HOLD_MS));
+                        overlay.fade_out_quick();
+                        continue;
+                    };
+                    let Some(chat) = select_llm(&llm_registry, llm.as_ref(), LlmRole::Polish)
+                    else {
+                        overlay.show_error("LLM 未就绪，无法改写");
+                        std::thread::sleep(Duration::from_millis(RESULT_OVERLAY_HOLD_MS));
+                        overlay.fade_out_quick();
+                        continue;
+                    };
+                    let style = last.next_repolish();
+                    status.set(TrayState::Processing);
+                    overlay.show("改写中", style.label());
+                    let llm_out = refine_within_context(chat, &last.raw_text, |text| {
+                        build_repolish_prompt(text, style)
+                    })
+                    .map_err(|e| eprintln!("[mofa-ime] 改写失败: {e}"))
+                    .ok();
+                    let Some(text) =
+                        repolish_raw_text(&last.raw_text, llm_out, &app_cfg, &load_expansions())
+                    else {
+                        status.set(TrayState::Error);
+                        overlay.show_error("改写失败，保留原文");
+                        std::thread::sleep(Duration::from_millis(900));
+                        overlay.fade_out_quick();
+                        continue;
+                    };
+                    let text = format!("{}{text}", last.prefix);
+                    let strategy = app_cfg.inject_strategy_for(front_bundle.as_deref());
+                    let result = erase_injected_text(&last.injected)
+                        .and_then(|_| inject_text(&text, strategy));
+                    match result {
+                        Ok(()) => {
+                            last.replaced(text.clone(), Instant::now());
+                            add_history_item(&text, overlay);
+                            monitor.set_output(&text);
+                            monitor.set_hint(&format!("已改写: {}", style.label()));
+                            status.set(TrayState::Injected);
+                            let preview = privacy_redact(&text, privacy_mode());
+                            overlay.show(&format!("已改写·{}", style.label()), preview);
+                        }
+                        Err(e) => {
+                            eprintln!("[mofa-ime] 改写注入失败: {e}");
+                            status.set(TrayState::Error);
+                            overlay.show_error("改写注入失败");
+                            // 替换到一半时文本状态未知，不再允许继续改写。
+                            last_injection = None;
+                        }
+                    }
+                    std::thread::sleep(Duration::from_millis(RESULT_OVERLAY_HOLD_MS));
+                    overlay.fade_out_quick();
+                }
+                // 已在上面换成松开处理。
+                HotkeySignal::ForceStop => {}
+                HotkeySignal::RepolishLast => {
+                    if recorder.is_some() {
+                        continue;
+                    }
+                    let Some(raw) = last_raw_text.clone() else {
+                        overlay.show_error("没有可重新润色的上一段");
+                        std::thread::sleep(Duration::from_millis(RESULT_OVERLAY_HOLD_MS));
+                        overlay.fade_out_quick();
+                        continue;
+                    };
+                    let Some(chat) = select_llm(&llm_registry, llm.as_ref(), LlmRole::Polish)
+                    else {
+                        overlay.show_error("LLM 未就绪，无法重新润色");
+                        std::thread::sleep(Duration::from_millis(RESULT_OVERLAY_HOLD_MS));
+                        overlay.fade_out_quick();
+                        continue;
+                    };
+                    status.set(TrayState::Processing);
+                    overlay.show_refining();
+                    let llm_out = refine_within_context(chat, &raw, build_refine_prompt)
+                        .map_err(|e| eprintln!("[mofa-ime] 重新润色失败: {e}"))
+                        .ok();
+                    let app_cfg = load_app_config();
+                    let Some(text) = repolish_raw_text(&raw, llm_out, &app_cfg, &load_expansions())
+                    else {
+                        status.set(TrayState::Error);
+                        overlay.show_error("重新润色失败");
+                        std::thread::sleep(Duration::from_millis(900));
+                        overlay.fade_out_quick();
+                        continue;
+                    };
+                    let front_bundle = frontmost_bundle_id();
+                    let replaceable = last_injection.as_mut().filter(|last| {
+                        last.can_repolish(
+                            Instant::now(),
+                            app_cfg.repolish_window_ms,
+                            front_bundle.as_deref(),
+                        )
+                    });
+                    let preview = privacy_redact(&text, privacy_mode());
+                    match replaceable {
+                        // 仍在改写窗口内且前台未变：退格替换刚才的输入。
+                        Some(last) => {
+                            let text = format!("{}{text}", last.prefix);
+                            let strategy = app_cfg.inject_strategy_for(front_bundle.as_deref());
+                            let result = erase_injected_text(&last.injected)
+                                .and_then(|_| inject_text(&text, strategy));
+                            match result {
+                                Ok(()) => {
+                                    last.replaced(text.clone(), Instant::now());
+                                    status.set(TrayState::Injected);
+                                    overlay.show("已重新润色", preview);
+                                }
+                                Err(e) => {
+                                    eprintln!("[mofa-ime] 重新润色注入失败: {e}");
+                                    status.set(TrayState::Error);
+                                    overlay.show_error("重新润色注入失败");
+                                    last_injection = None;
+                                }
+                            }
+                        }
+                        // 已无法安全替换：只放到剪贴板，由用户自行粘贴。
+                        None => {
+                            copy_text_to_clipboard(&text);
+                            status.set(TrayState::Injected);
+                            overlay.show("已重新润色并复制", preview);
+                        }
+                    }
+                    add_history_item(&text, overlay);
+                    monitor.set_output(&text);
+                    monitor.set_hint("已用当前 LLM 重新润色上一段");
+                    std::thread::sleep(Duration::from_millis(RESULT_OVERLAY_HOLD_MS));
+                    overlay.fade_out_quick();
+                }
+                HotkeySignal::Down { modifiers } => {
+                    last_injection = None;
                     if recorder.is_none() {
-                        match ActiveRecorder::start() {
+                        pressed_at = Some(Instant::now());
+                        latched = false;
+                        let down_cfg = load_app_config();
+                        boost = is_boost_press(modifiers, down_cfg.hotkey.modifiers);
+                        if !recording_cooldown_elapsed(
+                            last_recording_end,
+                            Instant::now(),
+                            down_cfg.record_cooldown_ms,
+                        ) {
+                            continue;
+                        }
+                        PrerollCapture::sync(
+                            &mut preroll,
+                            down_cfg.mic_channel,
+                            down_cfg.preroll_ms,
+                        );
+                        match S::start(down_cfg.mic_channel) {
                             Ok(r) => {
+                                if let Some(p) = preroll.as_ref() {
+                                    p.prepend_to(&r.sample_buffer(), r.sample_rate());
+                                }
                                 let ticker = RecordingTicker::start(
                                     r.sample_buffer(),
                                     r.sample_rate(),
                                     overlay,
+                                    down_cfg.overlay_show_delay_ms,
                                 );
                                 recording_ticker = Some(ticker);
+                                if down_cfg.stop_on_app_switch {
+                                    app_switch = Some(AppSwitchWatcher::start(tx.clone()));
+                                }
+                                if down_cfg.incremental_inject
+                                    && !down_cfg.caption_mode
+                                    && down_cfg.output_sink == OutputSink::Inject
+                                {
+                                    if let Some(session) = asr.as_ref() {
+                                        session.set_language(down_cfg.language.code());
+                                        incremental = Some(IncrementalInjector::start(
+                                            session.clone(),
+                                            r.sample_buffer(),
+                                            r.sample_rate(),
+                                        ));
+                                    }
+                                } else if down_cfg.caption_mode
+                                    || (down_cfg.continuous
+                                        && down_cfg.output_sink == OutputSink::Inject)
+                                {
+                                    if let Some(session) = asr.as_ref() {
+                                        session.set_language(down_cfg.language.code());
+                                        // 字幕要跟得上语速，不经过 LLM 润色。
+                                        let polish = (down_cfg.output_mode == OutputMode::Llm
+                                            && !down_cfg.caption_mode)
+                                            .then(|| {
+                                                select_llm(
+                                                    &llm_registry,
+                                                    llm.as_ref(),
+                                                    LlmRole::Polish,
+                                                )
+                                            })
+                                            .flatten()
+                                            .cloned();
+                                        continuous = Some(ContinuousDictation::start(
+                                            session.clone(),
+                                            polish,
+                                            r.sample_buffer(),
+                                            r.sample_rate(),
+                                            down_cfg.silence_hang_ms,
+                                            if down_cfg.caption_mode {
+                                                ContinuousOutput::Caption
+                                            } else {
+                                                ContinuousOutput::Inject(
+                                                    down_cfg.inject_strategy_for(
+                                                        frontmost_bundle_id().as_deref(),
+                                                    ),
+                                                )
+                                            },
+                                        ));
+                                    }
+                                }
                                 recorder = Some(r);
                                 status.set(TrayState::Recording);
                                 monitor.set_state("录音中");
-                                monitor.set_hint("-");
-                                overlay.show_recording();
+                                let hint = if boost {
+                                    "加速：本段使用最大模型"
+                                } else {
+                                    "-"
+                                };
+                                monitor.set_hint(hint);
                             }
                             Err(e) => {
                                 eprintln!("[mofa-ime] 录音启动失败: {e}");
@@ -159,35 +1002,66 @@ fn spawn_pipeline_worker(
                         }
                     }
                 }
-                HotkeySignal::Up => {
+                HotkeySignal::Up { modifiers } => {
+                    latched = false;
+                    let app_cfg = load_app_config();
+                    // 松开后短时间内又按下：视为手指滑开，继续同一段录音。
+                    if recorder.is_some() && app_cfg.release_grace_ms > 0 {
+                        let grace = Duration::from_millis(app_cfg.release_grace_ms);
+                        match wait_release_grace(&rx, grace) {
+                            ReleaseGrace::Resume => continue,
+                            ReleaseGrace::Finish(next) => pending_signal = next,
+                        }
+                    }
+
+                    // 按住不足 overlay_show_delay_ms 时悬浮窗尚未出现：录音被丢弃就不再弹出。
+                    let overlay_shown = recording_ticker
+                        .as_ref()
+                        .map(|t| t.overlay_shown())
+                        .unwrap_or(true);
                     if let Some(ticker) = recording_ticker.take() {
                         ticker.stop();
                     }
+                    if let Some(watcher) = app_switch.take() {
+                        watcher.stop();
+                    }
+                    // 增量模式下已上屏的文本，最后与完整转写结果对齐。
+                    let incremental_committed = incremental.take().map(|inc| inc.stop());
+                    // 连续听写：已按句注入的部分不再处理，只转写松开前的最后一段。
+                    let continuous_done = continuous.take().map(|c| c.stop());
 
-                    let app_cfg = load_app_config();
                     refresh_models(
                         &model_base,
-                        app_cfg,
+                        &app_cfg,
                         &mut asr,
                         &mut asr_loaded_path,
                         &mut llm,
                         &mut llm_loaded_path,
+                        &mut llm_registry,
                         monitor,
+                        app_cfg.uses_llm().then_some(overlay),
                     );
 
                     let Some(r) = recorder.take() else {
                         overlay.hide();
                         continue;
                     };
+                    last_recording_end = Some(Instant::now());
 
                     status.set(TrayState::Processing);
                     monitor.set_state("识别中");
-                    overlay.show_transcribing();
+                    if overlay_shown {
+                        overlay.show_transcribing();
+                    }
 
                     let samples = match r.stop() {
-                        Ok(s) => s,
+                        Ok(s) => match continuous_done {
+                            Some((consumed, _)) => s.get(consumed..).unwrap_or_default().to_vec(),
+                            None => s,
+                        },
                         Err(e) => {
                             eprintln!("[mofa-ime] 录音结束失败: {e}");
+                            retract_incremental(incremental_committed.as_deref());
                             status.set(TrayState::Error);
                             monitor.set_state("录音结束失败");
                             monitor.set_hint("录音结束失败");
@@ -198,37 +1072,133 @@ fn spawn_pipeline_worker(
                         }
                     };
 
-                    if samples.len() < 3200 {
-                        // < 0.2s @16k
+                    let continuous_injected = continuous_done.map_or(0, |(_, n)| n);
+                    if continuous_injected > 0 && audio_drop_reason(&samples).is_some() {
                         status.set(TrayState::Idle);
-                        monitor.set_state("录音过短");
-                        monitor.set_hint("录音过短");
-                        overlay.show_error("录音过短，请重试");
-                        std::thread::sleep(Duration::from_millis(700));
+                        monitor.set_state("完成");
+                        let hint = if app_cfg.caption_mode {
+                            format!("字幕模式：已显示 {continuous_injected} 句")
+                        } else {
+                            format!("连续听写：已注入 {continuous_injected} 句")
+                        };
+                        monitor.set_hint(&hint);
                         overlay.fade_out_quick();
                         continue;
                     }
-
-                    if audio_rms(&samples) < SILENCE_RMS_THRESHOLD {
+                    if let Some(reason) = audio_drop_reason(&samples) {
+                        retract_incremental(incremental_committed.as_deref());
+                        let (state, hint, message) = if reason == DropReason::TooShort {
+                            ("录音过短", "录音过短", "录音过短，请重试")
+                        } else {
+                            ("无语音", "检测到静音", "未检测到有效语音")
+                        };
                         status.set(TrayState::Idle);
-                        monitor.set_state("无语音");
-                        monitor.set_hint("检测到静音");
-                        overlay.show_error("未检测到有效语音");
+                        monitor.set_state(state);
+                        monitor.set_hint(hint);
+                        if !overlay_shown {
+                            overlay.hide();
+                            continue;
+                        }
+                        play_error_feedback(&error_sound, app_cfg.error_beep);
+                        report_drop(
+                            reason,
+                            app_cfg.debug_drops,
+                            &format!(
+                                "samples={} rms={:.5} speech={:.2}",
+                                samples.len(),
+                                audio_rms(&samples),
+                                mofa_input::audio::vad::speech_ratio(&samples, CAPTURE_TARGET_RATE)
+                            ),
+                            monitor,
+                            overlay,
+                            message,
+                        );
                         std::thread::sleep(Duration::from_millis(760));
                         overlay.fade_out_quick();
                         continue;
                     }
 
-                    let Some(asr_session) = asr.as_ref() else {
+                    if !overlay_shown {
+                        overlay.show_transcribing();
+                    }
+
+                    let samples = if app_cfg.denoise {
+                        mofa_input::audio::denoise::spectral_gate(&samples)
+                    } else {
+                        samples
+                    };
+                    // 长时间思考的停顿既拖慢转写，也容易让 Whisper 在静音里幻听。
+                    let samples = if app_cfg.compress_silence {
+                        mofa_input::audio::vad::compress_silences(
+                            &samples,
+                            CAPTURE_TARGET_RATE,
+                            app_cfg.max_internal_silence_ms,
+                        )
+                    } else {
+                        samples
+                    };
+
+                    let length_path = if boost {
+                        // 纯英文模型只在强制英文时参与挑选。
+                        let english = app_cfg.language == AsrLanguage::En;
+                        let names = AsrModelChoice::ALL
+                            .iter()
+                            .filter_map(|c| c.file_name())
+                            .filter(|name| english || !name.ends_with(".en.bin"));
+                        boosted_model_path(&model_base, names)
+                            .filter(|p| Some(p) != asr_loaded_path.as_ref())
+                    } else if app_cfg.asr_length_adaptive
+                        && app_cfg.asr_model == AsrModelChoice::Auto
+                    {
+                        let secs = samples.len() as f32 / CAPTURE_TARGET_RATE as f32;
+                        choose_asr_model_for_length(&model_base, secs)
+                            .map(|p| prefer_english_variant(p, app_cfg.language))
+                            .filter(|p| Some(p) != asr_loaded_path.as_ref())
+                    } else {
+                        None
+                    };
+                    if let Some(path) = length_path.as_ref() {
+                        if length_asr.as_ref().map(|(p, _)| p) != Some(path) {
+                            length_asr = None;
+                            if let Err(msg) = validate_model_file(path, ModelKind::Asr) {
+                                eprintln!("[mofa-ime] {msg} ({:?})", path);
+                                monitor.set_hint(&msg);
+                            } else {
+                                match mofa_input::asr::AsrSession::new(path) {
+                                    Ok(s) => length_asr = Some((path.clone(), s)),
+                                    Err(e) => {
+                                        eprintln!("[mofa-ime] ASR 加载失败 {:?}: {e}", path)
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    let length_session = length_path.as_ref().and_then(|path| {
+                        length_asr
+                            .as_ref()
+                            .filter(|(p, _)| p == path)
+                            .map(|(_, s)| s)
+                    });
+
+                    let Some(asr_session) = length_session.or(asr.as_ref()) else {
+                        retract_incremental(incremental_committed.as_deref());
                         eprintln!("[mofa-ime] ASR 未加载，跳过");
                         status.set(TrayState::Error);
                         monitor.set_state("ASR 未加载");
                         monitor.set_hint("ASR 模型缺失");
-                        overlay.show_error("Whisper 未就绪");
+                        report_drop(
+                            DropReason::AsrUnavailable,
+                            app_cfg.debug_drops,
+                            "",
+                            monitor,
+                            overlay,
+                            "Whisper 未就绪",
+                        );
                         std::thread::sleep(Duration::from_millis(900));
                         overlay.fade_out_quick();
                         continue;
                     };
+                    asr_session.set_language(app_cfg.language.code());
 
                     let asr_preview = Arc::new(Mutex::new(String::new()));
                     let asr_preview_cb = Arc::clone(&asr_preview);
@@ -251,57 +1221,309 @@ fn spawn_pipeline_worker(
                             Ok(t) => t.trim().to_string(),
                             Err(e) => {
                                 eprintln!("[mofa-ime] ASR 失败: {e}");
+                                retract_incremental(incremental_committed.as_deref());
                                 status.set(TrayState::Error);
                                 monitor.set_state("ASR 失败");
                                 monitor.set_hint("语音识别失败");
-                                overlay.show_error("语音识别失败");
+                                report_drop(
+                                    DropReason::AsrFailed,
+                                    app_cfg.debug_drops,
+                                    &e.to_string(),
+                                    monitor,
+                                    overlay,
+                                    "语音识别失败",
+                                );
                                 std::thread::sleep(Duration::from_millis(900));
                                 overlay.fade_out_quick();
                                 continue;
                             }
                         };
-                    let raw_text = normalize_transcript(&raw_text);
+                    let detected_language = asr_session.last_language();
+                    if let Some(label) =
+                        detected_language_label(app_cfg.language, detected_language.as_deref())
+                    {
+                        eprintln!("[mofa-ime] {label}");
+                        monitor.set_hint(&label);
+                        if app_cfg.show_detected_language {
+                            overlay.set_status(&label);
+                        }
+                    }
+                    let mut raw_text = normalize_transcript(&raw_text);
+                    if app_cfg.coding_mode {
+                        raw_text =
+                            mofa_input::text::apply_symbol_map(&raw_text, &load_symbol_map());
+                    }
                     monitor.set_asr(&raw_text);
                     if !raw_text.is_empty() {
-                        overlay.set_preview(&raw_text);
+                        match pinyin_preview_line(&raw_text, app_cfg.show_pinyin) {
+                            Some(pinyin) => overlay.set_preview_with_pinyin(&raw_text, &pinyin),
+                            None => overlay.set_preview(&raw_text),
+                        }
+                    }
+
+                    // 仅丢弃空文本/单个语气词；strict_filter 开启时再丢弃整句模板幻听句，其余交给 LLM 润色。
+                    if let Some(reason) = transcript_drop_reason(&raw_text, app_cfg.strict_filter) {
+                        retract_incremental(incremental_committed.as_deref());
+                        status.set(TrayState::Idle);
+                        monitor.set_state("无有效内容");
+                        monitor.set_hint("识别结果为空或为模板噪声，已忽略");
+                        report_drop(
+                            reason,
+                            app_cfg.debug_drops,
+                            &format!("text={raw_text:?}"),
+                            monitor,
+                            overlay,
+                            "未识别到有效内容",
+                        );
+                        std::thread::sleep(Duration::from_millis(760));
+                        overlay.fade_out_quick();
+                        continue;
                     }
 
-                    // 注：不再对ASR原文进行过滤，直接送入LLM或输出
-                    // LLM将负责过滤和润色工作
+                    // 字幕模式：松开前的最后一段也只上字幕条，不润色、不注入。
+                    if app_cfg.caption_mode {
+                        caption_push(&raw_text);
+                        status.set(TrayState::Idle);
+                        monitor.set_state("完成");
+                        monitor.set_hint("字幕模式：已更新字幕");
+                        overlay.fade_out_quick();
+                        continue;
+                    }
 
                     std::thread::sleep(Duration::from_millis(ASR_PREVIEW_HOLD_MS));
 
                     let mut final_text = raw_text.clone();
                     let mut mode_text = app_cfg.output_mode.label();
-                    if app_cfg.output_mode == OutputMode::Llm {
+                    if app_cfg.output_mode == OutputMode::Llm && incremental_committed.is_none() {
                         overlay.show_refining();
+                        let boost_llm = if boost {
+                            load_boost_llm(&model_base, llm_loaded_path.as_deref())
+                        } else {
+                            None
+                        };
                         if should_skip_llm_refine(&raw_text) {
                             mode_text = "ASR 原文";
                             monitor.set_hint("英文段落直出 ASR 原文");
-                        } else if let Some(chat) = llm.as_ref() {
-                            let prompt = build_refine_prompt(&raw_text);
-                            chat.clear();
-                            let llm_out = chat.send(&prompt, 384, 0.1).unwrap_or(raw_text.clone());
-                            let llm_out = normalize_transcript(&llm_out);
-                            let llm_out = trim_added_terminal_period(&raw_text, &llm_out);
-                            if !llm_out.is_empty() {
-                                final_text = llm_out;
-                            } else {
-                                // LLM输出为空，回退到ASR原文
-                                mode_text = "ASR 原文";
-                                monitor.set_hint("LLM 输出为空，回退 ASR 原文");
-                                // final_text 保持为 raw_text.clone()
+                        } else if below_llm_min_words(&raw_text, app_cfg.llm_min_words) {
+                            mode_text = "ASR 原文";
+                            monitor.set_hint("内容较短，跳过润色");
+                        } else if let Some(chat) = boost_llm
+                            .as_ref()
+                            .or_else(|| select_llm(&llm_registry, llm.as_ref(), LlmRole::Polish))
+                        {
+                            let llm_out =
+                                match refine_within_context(chat, &raw_text, build_refine_prompt) {
+                                    Ok(out) => Some(trim_added_terminal_period(
+                                        &raw_text,
+                                        &normalize_transcript(&out),
+                                    )),
+                                    Err(e) => {
+                                        eprintln!("[mofa-ime] LLM 润色失败: {e}");
+                                        None
+                                    }
+                                };
+                            match decide_refine_outcome(llm_out, app_cfg.llm_fallback) {
+                                RefineOutcome::Refined(text) => {
+                                    final_text =
+                                        truncate_at_sentence(&text, app_cfg.llm_max_output_chars);
+                                    let (before, after) =
+                                        (text.chars().count(), final_text.chars().count());
+                                    if after < before {
+                                        eprintln!(
+                                            "[mofa-ime] 润色结果过长，已截断 {before} -> {after} 字"
+                                        );
+                                        monitor.set_hint("润色结果过长，已按句截断");
+                                    }
+                                }
+                                RefineOutcome::FallbackRaw => {
+                                    // LLM输出无效，回退到ASR原文
+                                    mode_text = "ASR 原文";
+                                    monitor.set_hint("LLM 输出无效，回退 ASR 原文");
+                                }
+                                RefineOutcome::Failed => {
+                                    status.set(TrayState::Error);
+                                    monitor.set_state("润色失败");
+                                    monitor.set_hint("LLM 输出无效，未发送（llm_fallback=off）");
+                                    report_drop(
+                                        DropReason::LlmEmpty,
+                                        app_cfg.debug_drops,
+                                        "",
+                                        monitor,
+                                        overlay,
+                                        "润色失败，未发送，请重试",
+                                    );
+                                    std::thread::sleep(Duration::from_millis(900));
+                                    overlay.fade_out_quick();
+                                    continue;
+                                }
                             }
                         } else {
-                            // LLM未加载，使用ASR原文
-                            mode_text = "ASR 原文";
-                            monitor.set_hint("LLM 未就绪，使用 ASR 原文");
+                            // LLM未加载：按规则去掉填充词、整理空格与标点
+                            final_text = rule_polish(&raw_text, app_cfg.language);
+                            mode_text = "规则润色";
+                            monitor.set_hint("LLM 未就绪，已按规则整理 ASR 原文");
+                        }
+                    } else if app_cfg.output_mode == OutputMode::Bilingual
+                        && incremental_committed.is_none()
+                    {
+                        // 双语：原文不润色，后面括号附上译文；翻译失败时只发原文。
+                        overlay.show_refining();
+                        let translation = match (
+                            polish_prompt_for(app_cfg.output_mode, false, &raw_text),
+                            select_llm(&llm_registry, llm.as_ref(), LlmRole::Translate),
+                        ) {
+                            (Some(prompt), Some(chat)) => {
+                                chat.clear();
+                                chat.send(&prompt, REFINE_MAX_TOKENS, 0.1)
+                                    .map(|out| normalize_transcript(&out))
+                                    .map_err(|e| eprintln!("[mofa-ime] 翻译失败: {e}"))
+                                    .ok()
+                            }
+                            _ => None,
+                        };
+                        match decide_refine_outcome(translation, true) {
+                            RefineOutcome::Refined(text) => {
+                                final_text = assemble_bilingual(&raw_text, &text);
+                            }
+                            _ => {
+                                mode_text = "ASR 原文";
+                                monitor.set_hint("翻译不可用，只发送原文");
+                            }
                         }
+                    } else if incremental_committed.is_none() {
+                        // light_polish：ASR 模式下只补标点，失败时静默保留原文。
+                        if let (Some(prompt), Some(chat)) = (
+                            polish_prompt_for(app_cfg.output_mode, app_cfg.light_polish, &raw_text),
+                            select_llm(&llm_registry, llm.as_ref(), LlmRole::Polish),
+                        ) {
+                            chat.clear();
+                            let llm_out = chat
+                                .send(&prompt, 384, 0.0)
+                                .map(|out| normalize_transcript(&out))
+                                .map_err(|e| eprintln!("[mofa-ime] 轻量润色失败: {e}"))
+                                .ok();
+                            if let RefineOutcome::Refined(text) =
+                                decide_refine_outcome(llm_out, true)
+                            {
+                                final_text = text;
+                                mode_text = "ASR 原文（标点整理）";
+                            }
+                        }
+                    }
+
+                    if incremental_committed.is_none() {
+                        final_text = finish_output_text(&final_text, &app_cfg, &load_expansions());
+                    }
+
+                    if app_cfg.confirm_before_inject && incremental_committed.is_none() {
+                        monitor.set_state("待确认");
+                        overlay.hide();
+                        match confirm_text_before_inject(&final_text) {
+                            Some(edited) => final_text = edited,
+                            None => {
+                                status.set(TrayState::Idle);
+                                monitor.set_state("已放弃");
+                                monitor.set_hint("确认框中放弃发送");
+                                if app_cfg.debug_drops {
+                                    let reason = DropReason::ConfirmDiscarded;
+                                    eprintln!("[mofa-ime] 丢弃 {:?}: {}", reason, reason.detail());
+                                }
+                                continue;
+                            }
+                        }
+                    }
+
+                    // 松开时按住 Shift：只复制不注入（增量模式已上屏，不适用）。
+                    if incremental_committed.is_none()
+                        && release_copy_only(modifiers, app_cfg.hotkey.modifiers)
+                    {
+                        monitor.set_output(&final_text);
+                        copy_text_to_clipboard(&final_text);
+                        let audio = app_cfg.history_audio.then_some(samples.as_slice());
+                        add_history_item_with_audio(&final_text, audio, overlay);
+                        status.set(TrayState::Injected);
+                        monitor.set_state("已复制");
+                        monitor.set_hint("Shift 松开：结果已复制到剪贴板");
+                        overlay.show("已复制", "结果已复制到剪贴板");
+                        let hold = Duration::from_millis(RESULT_OVERLAY_HOLD_MS);
+                        pending_signal = pending_signal.or_else(|| hold_result_overlay(&rx, hold));
+                        if pending_signal.is_none() {
+                            overlay.fade_out_quick();
+                        }
+                        continue;
+                    }
+
+                    // output_sink=file：追加写入文件，不注入前台应用。
+                    if app_cfg.output_sink == OutputSink::File && incremental_committed.is_none() {
+                        monitor.set_output(&final_text);
+                        match append_to_output_file(&app_cfg.output_file, &final_text) {
+                            Ok(path) => {
+                                let audio = app_cfg.history_audio.then_some(samples.as_slice());
+                                add_history_item_with_audio(&final_text, audio, overlay);
+                                status.set(TrayState::Injected);
+                                monitor.set_state("已写入");
+                                monitor.set_hint(&format!("已追加到 {}", path.display()));
+                                let preview = privacy_redact(&final_text, privacy_mode());
+                                overlay.show("已写入文件", preview);
+                                let hold = Duration::from_millis(RESULT_OVERLAY_HOLD_MS);
+                                pending_signal =
+                                    pending_signal.or_else(|| hold_result_overlay(&rx, hold));
+                            }
+                            Err(e) => {
+                                eprintln!("[mofa-ime] 写入输出文件失败: {e:#}");
+                                status.set(TrayState::Error);
+                                monitor.set_state("写入失败");
+                                monitor.set_hint("输出文件写入失败");
+                                overlay.show_error("输出文件写入失败");
+                                std::thread::sleep(Duration::from_millis(900));
+                            }
+                        }
+                        if pending_signal.is_none() {
+                            overlay.fade_out_quick();
+                        }
+                        continue;
+                    }
+
+                    // 自动发送时去掉末尾换行，只由补发的回车发送一次。
+                    let front_bundle = app_cfg
+                        .inject_target_bundle
+                        .clone()
+                        .or_else(frontmost_bundle_id);
+                    let press_enter = should_press_enter(
+                        app_cfg.auto_send,
+                        app_cfg.profile_for(front_bundle.as_deref()),
+                        front_bundle.as_deref(),
+                        &final_text,
+                    );
+                    if press_enter {
+                        final_text = final_text.trim_end_matches(['\r', '\n']).to_string();
+                    }
+                    let mut prefix = String::new();
+                    if !app_cfg.inject_prefix.is_empty() && incremental_committed.is_none() {
+                        prefix = expand_time_tokens(&app_cfg.inject_prefix, &LocalTime::now());
+                        final_text = format!("{prefix}{final_text}");
                     }
 
                     monitor.set_output(&final_text);
 
-                    if let Err(e) = inject_text(&final_text) {
+                    let inject_result = match incremental_committed.as_deref() {
+                        Some(committed) => reconcile_incremental(committed, &final_text),
+                        None => match app_cfg.inject_target_bundle.as_deref() {
+                            // 注入落在目标应用里，按目标应用的配置选注入方式。
+                            Some(bundle_id) => inject_into_target(
+                                &final_text,
+                                app_cfg.inject_strategy_for(Some(bundle_id)),
+                                bundle_id,
+                                app_cfg.inject_target_restore_focus,
+                                press_enter,
+                            ),
+                            None => {
+                                let strategy = app_cfg.inject_strategy_for(front_bundle.as_deref());
+                                inject_text(&final_text, strategy)
+                            }
+                        },
+                    };
+                    if let Err(e) = inject_result {
                         eprintln!("[mofa-ime] 注入失败: {e}");
                         status.set(TrayState::Error);
                         monitor.set_state("发送失败");
@@ -312,18 +1534,586 @@ fn spawn_pipeline_worker(
                         continue;
                     }
 
+                    // 指定目标应用时回车已在切回原应用前补发。
+                    if press_enter && app_cfg.inject_target_bundle.is_none() {
+                        std::thread::sleep(Duration::from_millis(60));
+                        if let Err(e) = post_return_key() {
+                            eprintln!("[mofa-ime] 自动发送回车失败: {e}");
+                        }
+                    }
+
+                    last_raw_text = Some(raw_text.clone());
+                    // 已回车发送或注入到指定应用时无法原地替换，不提供改写。
+                    if !press_enter && app_cfg.inject_target_bundle.is_none() {
+                        last_injection = Some(LastInjection {
+                            raw_text: raw_text.clone(),
+                            prefix,
+                            injected: final_text.clone(),
+                            at: Instant::now(),
+                            bundle_id: front_bundle,
+                            next_style: 0,
+                        });
+                    }
+
                     // Add to history - store the actual sent text (LLM refined or ASR raw)
-                    add_history_item(&final_text, overlay);
+                    let audio = app_cfg.history_audio.then_some(samples.as_slice());
+                    add_history_item_with_audio(&final_text, audio, overlay);
 
                     monitor.set_hint(&format!("发送模式: {mode_text}"));
 
                     status.set(TrayState::Injected);
                     monitor.set_state("已发送");
-                    overlay.show_injected();
-                    std::thread::sleep(Duration::from_millis(RESULT_OVERLAY_HOLD_MS));
-                    overlay.fade_out_quick();
+                    if app_cfg.result_display.uses_notification() {
+                        post_notification("MoFA IME", privacy_redact(&final_text, privacy_mode()));
+                    }
+                    if app_cfg.result_display.uses_overlay() {
+                        overlay.show_injected();
+                        let hold = Duration::from_millis(RESULT_OVERLAY_HOLD_MS);
+                        pending_signal = pending_signal.or_else(|| hold_result_overlay(&rx, hold));
+                    }
+                    if pending_signal.is_none() {
+                        overlay.fade_out_quick();
+                    }
                 }
             }
         }
+
+        // 退出时停掉仍在进行的录音，释放音频设备。
+        if let Some(ticker) = recording_ticker.take() {
+            ticker.stop();
+        }
+        if let Some(watcher) = app_switch.take() {
+            watcher.stop();
+        }
+        if let Some(inc) = incremental.take() {
+            inc.stop();
+        }
+        if let Some(c) = continuous.take() {
+            c.stop();
+        }
+        if let Some(r) = recorder.take() {
+            let _ = r.stop();
+        }
     });
 }
+
+#[cfg(test)]
+mod pipeline_tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    struct CountingModel {
+        warmups: Cell<usize>,
+    }
+
+    impl Warmup for CountingModel {
+        fn warmup(&self) {
+            self.warmups.set(self.warmups.get() + 1);
+        }
+    }
+
+    fn load() -> CountingModel {
+        CountingModel {
+            warmups: Cell::new(0),
+        }
+    }
+
+    #[test]
+    fn warmup_runs_once_per_load() {
+        for _ in 0..2 {
+            let model = warmed(load(), true);
+            assert_eq!(model.warmups.get(), 1);
+        }
+    }
+
+    #[test]
+    fn warmup_skipped_when_disabled() {
+        assert_eq!(warmed(load(), false).warmups.get(), 0);
+    }
+
+    struct CountingSound(Cell<usize>);
+
+    impl FeedbackSound for CountingSound {
+        fn play_error(&self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn error_sound_plays_only_when_enabled() {
+        let sound = CountingSound(Cell::new(0));
+        play_error_feedback(&sound, false);
+        assert_eq!(sound.0.get(), 0);
+        play_error_feedback(&sound, true);
+        assert_eq!(sound.0.get(), 1);
+    }
+
+    #[test]
+    fn cooldown_gate_blocks_presses_inside_the_window() {
+        let end = Instant::now();
+        assert!(recording_cooldown_elapsed(None, end, 150));
+        assert!(!recording_cooldown_elapsed(
+            Some(end),
+            end + Duration::from_millis(80),
+            150
+        ));
+        assert!(recording_cooldown_elapsed(
+            Some(end),
+            end + Duration::from_millis(150),
+            150
+        ));
+        assert!(recording_cooldown_elapsed(Some(end), end, 0));
+    }
+
+    #[test]
+    fn cooldown_gate_tolerates_clock_order() {
+        let now = Instant::now();
+        let end = now + Duration::from_millis(10);
+        assert!(!recording_cooldown_elapsed(Some(end), now, 150));
+    }
+
+    /// FakeSource 下一次 `start` 交出的录音；工作线程里调用，所以放在全局而不是 thread_local。
+    static FAKE_AUDIO: Mutex<Vec<f32>> = Mutex::new(Vec::new());
+
+    /// 不碰麦克风的录音来源：`stop` 原样返回预先放入 FAKE_AUDIO 的 16k 缓冲。
+    struct FakeSource {
+        samples: Arc<Mutex<Vec<f32>>>,
+    }
+
+    impl AudioSource for FakeSource {
+        fn start(_mic: MicChannel) -> Result<Self> {
+            let samples = FAKE_AUDIO.lock().unwrap().clone();
+            Ok(Self {
+                samples: Arc::new(Mutex::new(samples)),
+            })
+        }
+
+        fn sample_buffer(&self) -> Arc<Mutex<Vec<f32>>> {
+            Arc::clone(&self.samples)
+        }
+
+        fn sample_rate(&self) -> u32 {
+            CAPTURE_TARGET_RATE
+        }
+
+        fn stop(self) -> Result<Vec<f32>> {
+            Ok(self.samples.lock().unwrap().clone())
+        }
+    }
+
+    /// 0.5s 静音 + 0.5s 200Hz 正弦 + 0.5s 静音。
+    fn spoken_buffer() -> Vec<f32> {
+        let rate = CAPTURE_TARGET_RATE as usize;
+        let mut samples = vec![0.0f32; rate / 2];
+        samples.extend(
+            (0..rate / 2)
+                .map(|i| 0.3 * (i as f32 * 200.0 * std::f32::consts::TAU / rate as f32).sin()),
+        );
+        samples.extend(vec![0.0f32; rate / 2]);
+        samples
+    }
+
+    fn read_tray_state(home: &Path) -> String {
+        fs::read_to_string(home.join(".mofa/state"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    fn wait_for_tray_state(home: &Path, done: impl Fn(&str) -> bool) -> String {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let state = read_tray_state(home);
+            if done(&state) || Instant::now() >= deadline {
+                return state;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    /// 按下、松开一次热键，返回这段录音结束后的托盘状态。
+    fn press_and_release(tx: &Sender<HotkeySignal>, home: &Path, samples: Vec<f32>) -> String {
+        *FAKE_AUDIO.lock().unwrap() = samples;
+        tx.send(HotkeySignal::Down { modifiers: 0 }).unwrap();
+        assert_eq!(wait_for_tray_state(home, |s| s == "recording"), "recording");
+        tx.send(HotkeySignal::Up { modifiers: 0 }).unwrap();
+        wait_for_tray_state(home, |s| s != "recording" && s != "processing")
+    }
+
+    /// 整条工作线程跑一遍：HOME 指向空目录（默认配置、没有模型、状态文件也写在这里）。
+    /// 过短与静音在 ASR 之前被丢弃，回到空闲；有声录音通过过滤，停在“ASR 未加载”。
+    #[test]
+    fn worker_drops_or_forwards_fake_recordings() {
+        let home = std::env::temp_dir().join(format!("mofa-worker-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).unwrap();
+        let saved_home = std::env::var_os("HOME");
+        std::env::set_var("HOME", &home);
+
+        let (tx, rx) = mpsc::channel();
+        let overlay = OverlayHandle {
+            history_window_ptr: 0,
+            history_title_ptr: 0,
+            history_tab_control_ptr: 0,
+            history_scroll_view_ptr: 0,
+            history_list_view_ptr: 0,
+            clipboard_scroll_view_ptr: 0,
+            clipboard_list_view_ptr: 0,
+            snippet_scroll_view_ptr: 0,
+            snippet_list_view_ptr: 0,
+            history_close_btn_ptr: 0,
+        };
+        let monitor = MonitorHandle {
+            state_item_ptr: 0,
+            asr_item_ptr: 0,
+            output_item_ptr: 0,
+            hint_item_ptr: 0,
+        };
+        spawn_pipeline_worker::<FakeSource>(
+            rx,
+            tx.clone(),
+            StatusHandle { button_ptr: 0 },
+            monitor,
+            overlay,
+        );
+
+        let too_short = press_and_release(&tx, &home, vec![0.0; 1600]);
+        let silent = press_and_release(&tx, &home, vec![0.0; 16_000]);
+        let spoken = press_and_release(&tx, &home, spoken_buffer());
+
+        match saved_home {
+            Some(h) => std::env::set_var("HOME", h),
+            None => std::env::remove_var("HOME"),
+        }
+        let _ = fs::remove_dir_all(&home);
+        assert_eq!(too_short, "idle");
+        assert_eq!(silent, "idle");
+        assert_eq!(spoken, "error");
+    }
+
+    /// 真模型转写冒烟测试：只在 MOFA_TEST_ASR_MODEL 指向一个 ggml 模型文件时运行，
+    /// 不读用户配置，也不碰 ~/.mofa/models。
+    #[test]
+    fn fake_source_feeds_the_transcriber() {
+        let Some(path) = std::env::var_os("MOFA_TEST_ASR_MODEL").map(PathBuf::from) else {
+            return;
+        };
+        let asr = mofa_input::asr::AsrSession::new(&path).unwrap();
+        *FAKE_AUDIO.lock().unwrap() = spoken_buffer();
+        let samples = FakeSource::start(MicChannel::Mix).unwrap().stop().unwrap();
+        assert!(asr.transcribe(&samples).is_ok());
+    }
+
+    #[test]
+    fn audio_guards_map_to_drop_reasons() {
+        assert_eq!(audio_drop_reason(&[0.0; 3199]), Some(DropReason::TooShort));
+        assert_eq!(audio_drop_reason(&[0.0; 16_000]), Some(DropReason::Silence));
+        assert_eq!(audio_drop_reason(&spoken_buffer()), None);
+    }
+
+    #[test]
+    fn transcript_guards_map_to_drop_reasons() {
+        assert_eq!(
+            transcript_drop_reason("", true),
+            Some(DropReason::EmptyTranscript)
+        );
+        assert_eq!(
+            transcript_drop_reason("嗯。", false),
+            Some(DropReason::EmptyTranscript)
+        );
+        assert_eq!(
+            transcript_drop_reason("谢谢观看", true),
+            Some(DropReason::TemplateNoise)
+        );
+        assert_eq!(transcript_drop_reason("谢谢观看", false), None);
+        assert_eq!(transcript_drop_reason("明天见", true), None);
+    }
+
+    #[test]
+    fn every_drop_reason_has_a_detail() {
+        let all = [
+            DropReason::TooShort,
+            DropReason::Silence,
+            DropReason::AsrUnavailable,
+            DropReason::AsrFailed,
+            DropReason::EmptyTranscript,
+            DropReason::TemplateNoise,
+            DropReason::LlmEmpty,
+            DropReason::ConfirmDiscarded,
+        ];
+        for reason in all {
+            assert!(!reason.detail().is_empty(), "{reason:?}");
+        }
+    }
+
+    #[test]
+    fn quick_press_within_grace_resumes_recording() {
+        let (tx, rx) = mpsc::channel();
+        let presser = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            tx.send(HotkeySignal::Down { modifiers: 0 }).unwrap();
+        });
+        let outcome = wait_release_grace(&rx, Duration::from_millis(500));
+        presser.join().unwrap();
+        assert!(matches!(outcome, ReleaseGrace::Resume));
+    }
+
+    #[test]
+    fn press_after_grace_finishes_and_is_kept() {
+        let (tx, rx) = mpsc::channel();
+        let outcome = wait_release_grace(&rx, Duration::from_millis(30));
+        assert!(matches!(outcome, ReleaseGrace::Finish(None)));
+        // 宽限期后的按下留在通道里，由主循环作为新录音处理。
+        tx.send(HotkeySignal::Down { modifiers: 0 }).unwrap();
+        assert!(matches!(rx.try_recv(), Ok(HotkeySignal::Down { .. })));
+    }
+
+    #[test]
+    fn other_signals_during_grace_are_handed_back() {
+        let (tx, rx) = mpsc::channel();
+        tx.send(HotkeySignal::CycleModel(ModelKind::Asr)).unwrap();
+        let outcome = wait_release_grace(&rx, Duration::from_millis(500));
+        assert!(matches!(
+            outcome,
+            ReleaseGrace::Finish(Some(HotkeySignal::CycleModel(ModelKind::Asr)))
+        ));
+
+        tx.send(HotkeySignal::ForceStop).unwrap();
+        let outcome = wait_release_grace(&rx, Duration::from_millis(500));
+        assert!(matches!(
+            outcome,
+            ReleaseGrace::Finish(Some(HotkeySignal::ForceStop))
+        ));
+    }
+
+    #[test]
+    fn pinyin_line_only_for_enabled_chinese_previews() {
+        assert_eq!(
+            pinyin_preview_line("你好", true),
+            Some("nǐ hǎo".to_string())
+        );
+        assert_eq!(pinyin_preview_line("你好", false), None);
+        assert_eq!(pinyin_preview_line("hello", true), None);
+    }
+
+    fn last_injection(raw_text: &str, injected: &str) -> LastInjection {
+        LastInjection {
+            raw_text: raw_text.to_string(),
+            prefix: String::new(),
+            injected: injected.to_string(),
+            at: Instant::now(),
+            bundle_id: Some("com.apple.Notes".to_string()),
+            next_style: 0,
+        }
+    }
+
+    #[test]
+    fn repolish_cycles_instructions_on_the_stored_raw_text() {
+        let mut last = last_injection("明天开会讨论预算", "明天开会，讨论预算。");
+        assert_eq!(last.next_repolish(), RepolishStyle::Shorter);
+
+        last.replaced("明天开会议预算。".to_string(), Instant::now());
+        assert_eq!(last.next_repolish(), RepolishStyle::Formal);
+        // 改写只替换已注入文本，提示词仍基于 ASR 原文，不叠加在上一次改写结果上。
+        assert_eq!(last.raw_text, "明天开会讨论预算");
+        assert_eq!(last.injected, "明天开会议预算。");
+        assert_eq!(last.next_repolish(), RepolishStyle::Casual);
+        assert_eq!(last.next_repolish(), RepolishStyle::Shorter);
+    }
+
+    #[test]
+    fn repolish_only_within_window_in_the_same_app() {
+        let last = last_injection("你好", "你好。");
+        let now = last.at + Duration::from_millis(500);
+        assert!(last.can_repolish(now, 1000, Some("com.apple.Notes")));
+        assert!(!last.can_repolish(now, 400, Some("com.apple.Notes")));
+        assert!(!last.can_repolish(now, 1000, Some("com.tinyspeck.slackmacgap")));
+        assert!(!last.can_repolish(now, 1000, None));
+    }
+
+    #[test]
+    fn repolished_text_gets_the_same_post_processing() {
+        let expansions = mofa_input::text::ExpansionMap::parse("btw => by the way");
+        let cfg = AppConfig {
+            strip_markdown: true,
+            auto_space_cjk: true,
+            ..AppConfig::default()
+        };
+        assert_eq!(
+            finish_output_text("**好的**btw明天见", &cfg, &expansions),
+            "好的 by the way 明天见"
+        );
+        assert_eq!(
+            finish_output_text("**好的**btw明天见", &AppConfig::default(), &expansions),
+            "**好的**by the way明天见"
+        );
+    }
+
+    /// 模拟连续几次 refresh_models：没有推迟且尚未加载时才真正加载 LLM，返回加载次数。
+    fn count_llm_loads(lazy: bool, on_demand: &[bool]) -> usize {
+        let mut loaded = false;
+        let mut loads = 0;
+        for &needs_llm in on_demand {
+            if !loaded && !defer_llm_load(lazy, loaded, needs_llm) {
+                loaded = true;
+                loads += 1;
+            }
+        }
+        loads
+    }
+
+    #[test]
+    fn lazy_llm_waits_for_first_use_and_loads_once() {
+        assert_eq!(count_llm_loads(true, &[false, false, false]), 0);
+        assert_eq!(count_llm_loads(true, &[false, true, true, false, true]), 1);
+        assert_eq!(count_llm_loads(false, &[false, false]), 1);
+    }
+
+    #[test]
+    fn loaded_llm_is_never_deferred() {
+        assert!(defer_llm_load(true, false, false));
+        assert!(!defer_llm_load(true, true, false));
+        assert!(!defer_llm_load(true, false, true));
+        assert!(!defer_llm_load(false, false, false));
+    }
+
+    #[test]
+    fn privacy_mode_keeps_transcript_out_of_history_and_logs() {
+        let transcript = "我的银行卡密码是123456";
+        let context = format!("text={transcript:?}");
+
+        assert!(should_record_history(transcript, false));
+        assert!(!should_record_history(transcript, true));
+        assert!(!should_record_history("   ", false));
+
+        assert!(drop_log_line(DropReason::LlmEmpty, &context, false).contains(transcript));
+        let line = drop_log_line(DropReason::LlmEmpty, &context, true);
+        assert!(!line.contains(transcript));
+        assert!(line.contains(DropReason::LlmEmpty.detail()));
+
+        assert_eq!(privacy_redact(transcript, true), PRIVACY_PLACEHOLDER);
+        assert_eq!(privacy_redact(transcript, false), transcript);
+    }
+
+    #[test]
+    fn press_during_result_hold_ends_it_early() {
+        let (tx, rx) = mpsc::channel();
+        let presser = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            tx.send(HotkeySignal::Down { modifiers: 0 }).unwrap();
+        });
+        let started = Instant::now();
+        let next = hold_result_overlay(&rx, Duration::from_secs(5));
+        presser.join().unwrap();
+        assert!(matches!(next, Some(HotkeySignal::Down { .. })));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn release_during_result_hold_does_not_end_it() {
+        let (tx, rx) = mpsc::channel();
+        tx.send(HotkeySignal::Up { modifiers: 0 }).unwrap();
+        let started = Instant::now();
+        assert!(hold_result_overlay(&rx, Duration::from_millis(60)).is_none());
+        assert!(started.elapsed() >= Duration::from_millis(60));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn repolish_from_stored_raw_text_is_post_processed() {
+        let raw = "好的btw明天见";
+        let expansions = mofa_input::text::ExpansionMap::parse("btw => by the way");
+        let cfg = AppConfig {
+            strip_markdown: true,
+            auto_space_cjk: true,
+            ..AppConfig::default()
+        };
+        // 原文没有句末标点时去掉 LLM 补的句号，其余与主流程一样整理。
+        assert_eq!(
+            repolish_raw_text(
+                raw,
+                Some("**好的**  btw明天见。".to_string()),
+                &cfg,
+                &expansions
+            ),
+            Some("好的 by the way 明天见".to_string())
+        );
+        // LLM 失败或输出无效时不回退原文：上一段已经注入过了。
+        assert_eq!(repolish_raw_text(raw, None, &cfg, &expansions), None);
+        assert_eq!(
+            repolish_raw_text(raw, Some("[Error: context]".to_string()), &cfg, &expansions),
+            None
+        );
+    }
+
+    /// 依次喂入 (按下?, 距本次按下的毫秒数)，返回每一步的处理与结束时是否仍在录音。
+    fn run_hotkey_mode(mode: HotkeyMode, events: &[(bool, u64)]) -> (Vec<HotkeyModeAction>, bool) {
+        let (mut recording, mut latched) = (false, false);
+        let mut actions = Vec::new();
+        for &(is_down, held_ms) in events {
+            let action = hotkey_mode_action(mode, is_down, recording, latched, held_ms);
+            match (action, is_down) {
+                (HotkeyModeAction::Pass, true) => recording = true,
+                (HotkeyModeAction::Pass, false) | (HotkeyModeAction::Stop, _) => {
+                    recording = false;
+                    latched = false;
+                }
+                (HotkeyModeAction::Latch, _) => latched = true,
+                (HotkeyModeAction::Ignore, _) => {}
+            }
+            actions.push(action);
+        }
+        (actions, recording)
+    }
+
+    #[test]
+    fn hybrid_short_press_behaves_like_hold() {
+        let (actions, recording) = run_hotkey_mode(HotkeyMode::Hybrid, &[(true, 0), (false, 300)]);
+        assert_eq!(
+            actions,
+            vec![HotkeyModeAction::Pass, HotkeyModeAction::Pass]
+        );
+        assert!(!recording);
+    }
+
+    #[test]
+    fn hybrid_long_press_latches_until_the_next_press() {
+        let events = [
+            (true, 0),
+            (false, HYBRID_LATCH_MS),
+            (true, 5_000),
+            (false, 5_100),
+        ];
+        let (actions, recording) = run_hotkey_mode(HotkeyMode::Hybrid, &events);
+        assert_eq!(
+            actions,
+            vec![
+                HotkeyModeAction::Pass,
+                HotkeyModeAction::Latch,
+                HotkeyModeAction::Stop,
+                HotkeyModeAction::Ignore,
+            ]
+        );
+        assert!(!recording);
+    }
+
+    #[test]
+    fn toggle_and_hold_modes_ignore_press_duration() {
+        let events = [(true, 0), (false, 100), (true, 2_000), (false, 2_100)];
+        let (actions, _) = run_hotkey_mode(HotkeyMode::Toggle, &events);
+        assert_eq!(
+            actions,
+            vec![
+                HotkeyModeAction::Pass,
+                HotkeyModeAction::Ignore,
+                HotkeyModeAction::Stop,
+                HotkeyModeAction::Ignore,
+            ]
+        );
+        let (actions, _) = run_hotkey_mode(HotkeyMode::Hold, &[(true, 0), (false, 5_000)]);
+        assert_eq!(
+            actions,
+            vec![HotkeyModeAction::Pass, HotkeyModeAction::Pass]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+// 退出协调：托盘“退出”触发 applicationWillTerminate:，通知后台线程停止并等待其收尾。
+
+const SHUTDOWN_WAIT_MS: u64 = 600;
+
+static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+static ACTIVE_WORKERS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// 后台线程持有期间计入活动线程数，线程退出（含 panic）时自动注销。
+struct WorkerGuard;
+
+impl WorkerGuard {
+    fn register() -> Self {
+        ACTIVE_WORKERS.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for WorkerGuard {
+    fn drop(&mut self) {
+        ACTIVE_WORKERS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn shutdown_requested() -> bool {
+    SHUTDOWN.load(Ordering::SeqCst)
+}
+
+fn request_shutdown() {
+    SHUTDOWN.store(true, Ordering::SeqCst);
+}
+
+/// 等待所有已注册线程退出；超时返回 false（不再阻塞退出）。
+fn wait_for_workers(timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while ACTIVE_WORKERS.load(Ordering::SeqCst) > 0 {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    true
+}
+
+fn create_app_delegate() -> id {
+    static CLASS: OnceLock<usize> = OnceLock::new();
+    let ptr = *CLASS.get_or_init(|| {
+        let superclass = class!(NSObject);
+        let mut decl = ClassDecl::new("MofaAppDelegate", superclass).unwrap();
+
+        extern "C" fn will_terminate(_this: &Object, _sel: Sel, _notification: id) {
+            request_shutdown();
+            remove_state_file();
+            if !wait_for_workers(Duration::from_millis(SHUTDOWN_WAIT_MS)) {
+                eprintln!(
+                    "[mofa-ime] 退出时仍有 {} 个后台线程未结束",
+                    ACTIVE_WORKERS.load(Ordering::SeqCst)
+                );
+            }
+        }
+
+        unsafe {
+            decl.add_method(
+                sel!(applicationWillTerminate:),
+                will_terminate as extern "C" fn(&Object, Sel, id),
+            );
+        }
+        decl.register() as *const Class as usize
+    });
+
+    unsafe {
+        let class = &*(ptr as *const Class);
+        let delegate: id = msg_send![class, alloc];
+        msg_send![delegate, init]
+    }
+}
+
+unsafe fn install_app_delegate(app: id) -> id {
+    let delegate = create_app_delegate();
+    let _: () = msg_send![app, setDelegate: delegate];
+    delegate
+}
+
+#[cfg(test)]
+mod shutdown_tests {
+    use super::*;
+
+    #[test]
+    fn shutdown_waits_for_registered_workers() {
+        let guard = WorkerGuard::register();
+        assert!(!wait_for_workers(Duration::from_millis(40)));
+
+        let worker = std::thread::spawn(move || {
+            let _guard = guard;
+            while !shutdown_requested() {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        });
+        request_shutdown();
+        assert!(wait_for_workers(Duration::from_secs(2)));
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn panicking_worker_still_unregisters() {
+        let worker = std::thread::spawn(|| {
+            let _guard = WorkerGuard::register();
+            panic!("worker failed");
+        });
+        assert!(worker.join().is_err());
+        assert!(wait_for_workers(Duration::from_secs(2)));
+    }
+}
@@ -0,0 +1,476 @@
+// 增量上屏：录音过程中周期性转写已录音频，把稳定下来的分段先写入输入框，松开后再对齐最终结果。
+
+const STREAMING_INTERVAL_MS: u64 = 1200;
+// 至少 1s 音频才开始转写，避免对极短片段反复推理。
+const STREAMING_MIN_SAMPLES_16K: usize = 16_000;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct CommitPlan {
+    backspaces: usize,
+    insert: String,
+}
+
+/// 是否附着在前一个字符上、与之组成同一个字形（组合符、变体选择符、肤色修饰、ZWJ 之后的字符）。
+fn extends_cluster(prev: char, ch: char) -> bool {
+    prev == '\u{200D}'
+        || matches!(
+            ch,
+            '\u{0300}'..='\u{036F}'
+                | '\u{1AB0}'..='\u{1AFF}'
+                | '\u{20D0}'..='\u{20FF}'
+                | '\u{FE00}'..='\u{FE0F}'
+                | '\u{FE20}'..='\u{FE2F}'
+                | '\u{200D}'
+                | '\u{1F3FB}'..='\u{1F3FF}'
+        )
+}
+
+fn is_regional_indicator(ch: char) -> bool {
+    matches!(ch, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+/// 近似的字形切分：一次退格删掉一个字形，所以按字形而不是按 char 计数。
+/// 覆盖组合符、emoji ZWJ 序列、肤色与国旗；韩文字母拼合等少见情形仍按 char 计。
+fn grapheme_clusters(text: &str) -> Vec<&str> {
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    let mut regional_run = 0usize;
+    for (idx, ch) in text.char_indices() {
+        let joins = prev.is_some_and(|p| {
+            extends_cluster(p, ch) || (is_regional_indicator(ch) && regional_run % 2 == 1)
+        });
+        if !joins && idx > 0 {
+            clusters.push(&text[start..idx]);
+            start = idx;
+        }
+        regional_run = if is_regional_indicator(ch) {
+            regional_run + 1
+        } else {
+            0
+        };
+        prev = Some(ch);
+    }
+    if start < text.len() {
+        clusters.push(&text[start..]);
+    }
+    clusters
+}
+
+/// 已上屏文本 -> 目标文本：先退格到公共前缀，再补上其余部分。
+/// 分段被修订时（前文变化）即产生回退。按字形比较，避免退格删掉半个 emoji 或留下孤立的组合符。
+fn plan_incremental_commit(committed: &str, target: &str) -> CommitPlan {
+    let committed = grapheme_clusters(committed);
+    let target = grapheme_clusters(target);
+    let common = committed
+        .iter()
+        .zip(&target)
+        .take_while(|(a, b)| a == b)
+        .count();
+    CommitPlan {
+        backspaces: committed.len() - common,
+        insert: target[common..].concat(),
+    }
+}
+
+/// 连续两次转写结果一致、且不是最后一段的分段视为已稳定。
+fn stable_segment_text(previous: &[String], current: &[String]) -> String {
+    let mut out = String::new();
+    for (idx, seg) in current.iter().enumerate() {
+        if idx + 1 >= current.len() || previous.get(idx) != Some(seg) {
+            break;
+        }
+        out.push_str(seg);
+        out.push(' ');
+    }
+    normalize_transcript(&out)
+}
+
+fn apply_commit_plan(plan: &CommitPlan) -> Result<()> {
+    if plan.backspaces > 0 {
+        post_backspaces(plan.backspaces)?;
+    }
+    if !plan.insert.is_empty() && !try_insert_via_ax(&plan.insert) {
+        type_text_via_events(&plan.insert)?;
+    }
+    Ok(())
+}
+
+struct IncrementalInjector {
+    stop: Arc<AtomicBool>,
+    committed: Arc<Mutex<String>>,
+    join: Option<std::thread::JoinHandle<()>>,
+}
+
+impl IncrementalInjector {
+    fn start(
+        asr: mofa_input::asr::AsrSession,
+        samples: Arc<Mutex<Vec<f32>>>,
+        sample_rate: u32,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let committed = Arc::new(Mutex::new(String::new()));
+        let stop_flag = Arc::clone(&stop);
+        let committed_out = Arc::clone(&committed);
+
+        let join = std::thread::spawn(move || {
+            let mut previous: Vec<String> = Vec::new();
+            while !stop_flag.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(STREAMING_INTERVAL_MS));
+                if stop_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                let snapshot = samples.lock().map(|buf| buf.clone()).unwrap_or_default();
+                let snapshot = resample_to_16k(&snapshot, sample_rate);
+                if snapshot.len() < STREAMING_MIN_SAMPLES_16K {
+                    continue;
+                }
+                let current: Vec<String> = match asr.transcribe_segments(&snapshot) {
+                    Ok(segments) => segments.into_iter().map(|s| s.text).collect(),
+                    Err(e) => {
+                        eprintln!("[mofa-ime] 增量转写失败: {e}");
+                        continue;
+                    }
+                };
+                let target = stable_segment_text(&previous, &current);
+                previous = current;
+                // 松开热键后不再上屏，剩余部分交给最终对齐。
+                if target.is_empty() || stop_flag.load(Ordering::SeqCst) {
+                    continue;
+                }
+
+                let Ok(mut committed) = committed_out.lock() else {
+                    break;
+                };
+                let plan = plan_incremental_commit(&committed, &target);
+                if plan.backspaces == 0 && plan.insert.is_empty() {
+                    continue;
+                }
+                match apply_commit_plan(&plan) {
+                    Ok(()) => *committed = target,
+                    Err(e) => eprintln!("[mofa-ime] 增量上屏失败: {e}"),
+                }
+            }
+        });
+
+        Self {
+            stop,
+            committed,
+            join: Some(join),
+        }
+    }
+
+    /// 停止后台转写，返回已上屏的文本。
+    fn stop(mut self) -> String {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(join) = self.join.take() {
+            let _ = join.join();
+        }
+        self.committed.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+}
+
+/// 松开后把已上屏文本对齐为最终结果。
+fn reconcile_incremental(committed: &str, final_text: &str) -> Result<()> {
+    apply_commit_plan(&plan_incremental_commit(committed, final_text))
+}
+
+/// 松开后这次录音被丢弃（静音、识别失败等）时，退格删掉已上屏的半截文本。
+fn retract_incremental(committed: Option<&str>) {
+    let Some(committed) = committed.filter(|c| !c.is_empty()) else {
+        return;
+    };
+    if let Err(e) = reconcile_incremental(committed, "") {
+        eprintln!("[mofa-ime] 撤回增量上屏失败: {e}");
+    }
+}
+
+// 连续听写（continuous=true）：录音不停，每次停顿把这一句单独转写（LLM 模式下再润色）并注入。
+const CONTINUOUS_POLL_MS: u64 = 200;
+const CONTINUOUS_FRAME_MS: u64 = 30;
+/// 低于该 RMS 的帧视为静音。
+const CONTINUOUS_SILENCE_RMS: f32 = 0.008;
+
+/// 从 `from` 起找“说过话之后静音满 `silence_ms`”的断点，返回静音段起点；尚未出现时返回 None。
+/// 静音不满 `silence_ms` 就又出现语音（句中的自然停顿）时重新计时，不会切句。
+fn next_silence_boundary(
+    samples: &[f32],
+    from: usize,
+    sample_rate: u32,
+    silence_ms: u64,
+) -> Option<usize> {
+    let frame = (sample_rate as u64 * CONTINUOUS_FRAME_MS / 1000).max(1) as usize;
+    let needed = (silence_ms / CONTINUOUS_FRAME_MS).max(1) as usize;
+    let mut heard_speech = false;
+    let mut quiet_frames = 0usize;
+    let mut quiet_start = from;
+    for (idx, chunk) in samples.get(from..)?.chunks_exact(frame).enumerate() {
+        if audio_rms(chunk) >= CONTINUOUS_SILENCE_RMS {
+            heard_speech = true;
+            quiet_frames = 0;
+            continue;
+        }
+        if quiet_frames == 0 {
+            quiet_start = from + idx * frame;
+        }
+        quiet_frames += 1;
+        if heard_speech && quiet_frames >= needed {
+            return Some(quiet_start);
+        }
+    }
+    None
+}
+
+/// 两段连续注入之间：两侧都是英文/数字时补空格。
+fn continuous_separator(previous_last: Option<char>, next: &str) -> &'static str {
+    let prev_ascii = previous_last.is_some_and(|c| c.is_ascii_graphic());
+    let next_ascii = next
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric());
+    if prev_ascii && next_ascii {
+        " "
+    } else {
+        ""
+    }
+}
+
+/// 连续听写每句的去向：注入前台应用，或显示在字幕条上（caption_mode）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ContinuousOutput {
+    Inject(InjectStrategy),
+    Caption,
+}
+
+struct ContinuousDictation {
+    stop: Arc<AtomicBool>,
+    /// 已按句处理到的位置（录音原始采样率下的样本数）
+    consumed: Arc<AtomicUsize>,
+    injected: Arc<AtomicUsize>,
+    sample_rate: u32,
+    join: Option<std::thread::JoinHandle<()>>,
+}
+
+impl ContinuousDictation {
+    /// 后台线程只读录音缓冲，处理一句期间录音照常进行，下一句在处理完后接着切分。
+    fn start(
+        asr: mofa_input::asr::AsrSession,
+        polish: Option<mofa_input::llm::ChatSession>,
+        samples: Arc<Mutex<Vec<f32>>>,
+        sample_rate: u32,
+        silence_ms: u64,
+        output: ContinuousOutput,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let consumed = Arc::new(AtomicUsize::new(0));
+        let injected = Arc::new(AtomicUsize::new(0));
+        let stop_flag = Arc::clone(&stop);
+        let consumed_out = Arc::clone(&consumed);
+        let injected_out = Arc::clone(&injected);
+
+        let join = std::thread::spawn(move || {
+            let mut last_char: Option<char> = None;
+            while !stop_flag.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(CONTINUOUS_POLL_MS));
+                let offset = consumed_out.load(Ordering::SeqCst);
+                let tail = samples
+                    .lock()
+                    .map(|buf| buf.get(offset..).map(<[f32]>::to_vec).unwrap_or_default())
+                    .unwrap_or_default();
+                let Some(cut) = next_silence_boundary(&tail, 0, sample_rate, silence_ms) else {
+                    continue;
+                };
+                consumed_out.store(offset + cut, Ordering::SeqCst);
+
+                let utterance = resample_to_16k(&tail[..cut], sample_rate);
+                if audio_drop_reason(&utterance).is_some() {
+                    continue;
+                }
+                let raw = match asr.transcribe(&utterance) {
+                    Ok(text) => normalize_transcript(&text),
+                    Err(e) => {
+                        eprintln!("[mofa-ime] 连续听写转写失败: {e}");
+                        continue;
+                    }
+                };
+                if should_drop_transcript(&raw, false) {
+                    continue;
+                }
+                let text = match polish.as_ref() {
+                    Some(chat) if !should_skip_llm_refine(&raw) => {
+                        let out = refine_within_context(chat, &raw, build_refine_prompt)
+                            .map(|out| normalize_transcript(&out))
+                            .map_err(|e| eprintln!("[mofa-ime] 连续听写润色失败: {e}"))
+                            .ok();
+                        match decide_refine_outcome(out, true) {
+                            RefineOutcome::Refined(text) => trim_added_terminal_period(&raw, &text),
+                            _ => raw,
+                        }
+                    }
+                    _ => raw,
+                };
+
+                let strategy = match output {
+                    ContinuousOutput::Inject(strategy) => strategy,
+                    ContinuousOutput::Caption => {
+                        caption_push(&text);
+                        injected_out.fetch_add(1, Ordering::SeqCst);
+                        continue;
+                    }
+                };
+                let text = format!("{}{text}", continuous_separator(last_char, &text));
+                match inject_text(&text, strategy) {
+                    Ok(()) => {
+                        last_char = text.chars().last();
+                        injected_out.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Err(e) => eprintln!("[mofa-ime] 连续听写注入失败: {e}"),
+                }
+            }
+        });
+
+        Self {
+            stop,
+            consumed,
+            injected,
+            sample_rate,
+            join: Some(join),
+        }
+    }
+
+    /// 停止后台切分，返回（已处理部分对应的 16k 样本数，已注入的句数）；剩余尾段交给常规流程。
+    fn stop(mut self) -> (usize, usize) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(join) = self.join.take() {
+            let _ = join.join();
+        }
+        let consumed = self.consumed.load(Ordering::SeqCst) as u64;
+        let consumed_16k = consumed * CAPTURE_TARGET_RATE as u64 / self.sample_rate.max(1) as u64;
+        (consumed_16k as usize, self.injected.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod streaming_tests {
+    use super::*;
+
+    fn plan(backspaces: usize, insert: &str) -> CommitPlan {
+        CommitPlan {
+            backspaces,
+            insert: insert.to_string(),
+        }
+    }
+
+    #[test]
+    fn appended_segment_is_inserted_without_backspaces() {
+        assert_eq!(plan_incremental_commit("", "你好"), plan(0, "你好"));
+        assert_eq!(
+            plan_incremental_commit("你好", "你好，世界"),
+            plan(0, "，世界")
+        );
+        assert_eq!(plan_incremental_commit("same", "same"), plan(0, ""));
+    }
+
+    #[test]
+    fn revised_segment_backspaces_to_common_prefix() {
+        assert_eq!(
+            plan_incremental_commit("我想去北京", "我想去背景图"),
+            plan(2, "背景图")
+        );
+        assert_eq!(plan_incremental_commit("hello world", "hello"), plan(6, ""));
+        assert_eq!(plan_incremental_commit("剩下的", ""), plan(3, ""));
+    }
+
+    #[test]
+    fn backspaces_count_grapheme_clusters() {
+        // 👍🏽 与 👨‍👩‍👧 各是一个字形，退格一次即可删掉。
+        assert_eq!(plan_incremental_commit("ok 👍🏽", "ok"), plan(2, ""));
+        assert_eq!(plan_incremental_commit("家 👨‍👩‍👧", "家"), plan(2, ""));
+        assert_eq!(plan_incremental_commit("🇨🇳🇺🇸", "🇨🇳"), plan(1, ""));
+        // e + 组合重音被修订为 e 时整体删掉重打，不留下孤立的组合符。
+        assert_eq!(plan_incremental_commit("cafe\u{301}", "cafe"), plan(1, "e"));
+    }
+
+    #[test]
+    fn only_segments_unchanged_across_two_passes_are_stable() {
+        let seg = |s: &[&str]| s.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let previous = seg(&["今天开会", "讨论预算"]);
+        assert_eq!(
+            stable_segment_text(&previous, &seg(&["今天开会", "讨论预算", "和人"])),
+            "今天开会 讨论预算"
+        );
+        assert_eq!(
+            stable_segment_text(&previous, &seg(&["今天开会", "讨论预算"])),
+            "今天开会"
+        );
+        assert_eq!(
+            stable_segment_text(&previous, &seg(&["今天开个会", "讨论预算"])),
+            ""
+        );
+    }
+
+    const SILENCE_MS: u64 = 700;
+
+    /// 以 30ms 帧拼出测试音频：true 为语音帧，false 为静音帧（16kHz 下每帧 480 个样本）。
+    fn frames(pattern: &[(bool, usize)]) -> Vec<f32> {
+        pattern
+            .iter()
+            .flat_map(|&(speech, n)| std::iter::repeat_n(if speech { 0.2 } else { 0.0 }, n * 480))
+            .collect()
+    }
+
+    #[test]
+    fn silence_boundary_splits_buffer_into_utterances() {
+        // 语音 10 帧 + 静音 30 帧（900ms）+ 语音 10 帧 + 静音 5 帧
+        let samples = frames(&[(true, 10), (false, 30), (true, 10), (false, 5)]);
+        let first = next_silence_boundary(&samples, 0, 16_000, SILENCE_MS);
+        assert_eq!(first, Some(10 * 480));
+
+        // 第二句后的停顿不足 700ms，尚不切分。
+        assert_eq!(
+            next_silence_boundary(&samples, first.unwrap(), 16_000, SILENCE_MS),
+            None
+        );
+    }
+
+    #[test]
+    fn leading_silence_alone_is_not_a_boundary() {
+        let samples = frames(&[(false, 40)]);
+        assert_eq!(next_silence_boundary(&samples, 0, 16_000, SILENCE_MS), None);
+        let samples = frames(&[(false, 40), (true, 5), (false, 24)]);
+        assert_eq!(
+            next_silence_boundary(&samples, 0, 16_000, SILENCE_MS),
+            Some(45 * 480)
+        );
+        assert_eq!(
+            next_silence_boundary(&samples, samples.len() + 1, 16_000, SILENCE_MS),
+            None
+        );
+    }
+
+    #[test]
+    fn continuous_separator_spaces_only_between_latin_words() {
+        assert_eq!(continuous_separator(Some('.'), "Next"), " ");
+        assert_eq!(continuous_separator(Some('。'), "下一句"), "");
+        assert_eq!(continuous_separator(Some('d'), "你好"), "");
+        assert_eq!(continuous_separator(None, "Hello"), "");
+    }
+
+    #[test]
+    fn pause_shorter_than_hang_time_does_not_end_the_sentence() {
+        // 语音 + 停顿 450ms + 语音 + 停顿 900ms
+        let samples = frames(&[(true, 10), (false, 15), (true, 10), (false, 30)]);
+        // 默认 600ms：句中 450ms 的停顿不切，切在第二段语音之后。
+        assert_eq!(
+            next_silence_boundary(&samples, 0, 16_000, 600),
+            Some(35 * 480)
+        );
+        // 调短到 300ms 时同一停顿就会切句。
+        assert_eq!(
+            next_silence_boundary(&samples, 0, 16_000, 300),
+            Some(10 * 480)
+        );
+        // 调到 1200ms 时两段停顿都不够长。
+        assert_eq!(next_silence_boundary(&samples, 0, 16_000, 1200), None);
+    }
+}
@@ -11,10 +11,21 @@ fn choose_llm_model(base: &Path, choice: LlmModelChoice) -> Option<PathBuf> {
             return Some(selected);
         }
     }
-    choose_llm_model_auto(base)
+    choose_llm_model_auto(base, false)
 }
 
-fn choose_llm_model_auto(base: &Path) -> Option<PathBuf> {
+fn choose_llm_model_auto(base: &Path, low_memory: bool) -> Option<PathBuf> {
+    if low_memory {
+        return [
+            "qwen2.5-0.5b-q4_k_m.gguf",
+            "qwen2.5-coder-0.5b-q4_k_m.gguf",
+            "qwen2.5-1.5b-q4_k_m.gguf",
+        ]
+        .into_iter()
+        .map(|name| base.join(name))
+        .find(|p| p.exists());
+    }
+
     let mem_gb = total_memory_gb().unwrap_or(32);
 
     let preferred = if mem_gb <= 8 {
@@ -68,26 +79,304 @@ fn choose_llm_model_auto(base: &Path) -> Option<PathBuf> {
         .find(|p| p.exists())
 }
 
-fn choose_asr_model(base: &Path, choice: AsrModelChoice) -> Option<PathBuf> {
+fn choose_asr_model(base: &Path, choice: AsrModelChoice, language: AsrLanguage) -> Option<PathBuf> {
     if let Some(file_name) = choice.file_name() {
         let selected = base.join(file_name);
         if selected.exists() {
-            return Some(selected);
+            return Some(prefer_english_variant(selected, language));
+        }
+    }
+    choose_asr_model_auto(base, false).map(|p| prefer_english_variant(p, language))
+}
+
+/// whisper.cpp 的纯英文模型文件名：ggml-base.bin -> ggml-base.en.bin；`.en` 与 large 没有对应版本。
+fn english_variant_name(file_name: &str) -> Option<String> {
+    let stem = file_name.strip_suffix(".bin")?;
+    if stem.ends_with(".en") || stem.contains("large") {
+        return None;
+    }
+    Some(format!("{stem}.en.bin"))
+}
+
+/// `language=en` 时若同尺寸的 `.en` 模型已安装就改用它（英文更快更准）；否则原样返回。
+fn pick_asr_language_variant(
+    file_name: &str,
+    language: AsrLanguage,
+    installed: impl Fn(&str) -> bool,
+) -> Option<String> {
+    if language != AsrLanguage::En {
+        return None;
+    }
+    english_variant_name(file_name).filter(|name| installed(name))
+}
+
+fn prefer_english_variant(path: PathBuf, language: AsrLanguage) -> PathBuf {
+    let Some(base) = path.parent() else {
+        return path;
+    };
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| pick_asr_language_variant(name, language, |n| base.join(n).exists()))
+        .map(|name| base.join(name))
+        .unwrap_or(path)
+}
+
+fn choose_asr_model_auto(base: &Path, low_memory: bool) -> Option<PathBuf> {
+    let candidates: &[&str] = if low_memory {
+        &["ggml-tiny.bin", "ggml-base.bin"]
+    } else {
+        &[
+            "ggml-small.bin",
+            "ggml-base.bin",
+            "ggml-tiny.bin",
+            "ggml-medium.bin",
+        ]
+    };
+    candidates
+        .iter()
+        .map(|name| base.join(name))
+        .find(|p| p.exists())
+}
+
+/// 在 `all` 中从 `current` 之后循环查找下一个可用的选项；没有其它可用项时保持不变。
+fn cycle_to_next_installed<T: Copy + PartialEq>(
+    all: &[T],
+    current: T,
+    installed: impl Fn(T) -> bool,
+) -> T {
+    let start = all.iter().position(|c| *c == current).unwrap_or(0);
+    (1..all.len())
+        .map(|offset| all[(start + offset) % all.len()])
+        .find(|c| installed(*c))
+        .unwrap_or(current)
+}
+
+/// 切换到下一个已安装的模型并写入配置，返回新选择的名称；“自动”始终可选。
+fn cycle_model_in_config(base: &Path, kind: ModelKind) -> Result<&'static str> {
+    let cfg = load_app_config();
+    let (key, token, label) = match kind {
+        ModelKind::Asr => {
+            let next = cycle_to_next_installed(&AsrModelChoice::ALL, cfg.asr_model, |c| {
+                c.file_name().is_none_or(|name| base.join(name).exists())
+            });
+            ("asr_model", next.token(), next.label())
+        }
+        ModelKind::Llm => {
+            let next = cycle_to_next_installed(&LlmModelChoice::ALL, cfg.llm_model, |c| {
+                c.file_name().is_none_or(|name| base.join(name).exists())
+            });
+            ("llm_model", next.token(), next.label())
+        }
+    };
+    write_config_values(&hotkey_config_path(), &[(key, token.to_string())])?;
+    Ok(label)
+}
+
+/// 短于该时长的录音优先用小模型（速度优先），其余优先用大模型（准确率优先）。
+const ASR_SHORT_CLIP_SECS: f32 = 5.0;
+
+/// 按录音时长给出 ASR 候选顺序，取第一个已安装的模型。
+fn pick_asr_for_length(
+    duration_secs: f32,
+    installed: impl Fn(&str) -> bool,
+) -> Option<&'static str> {
+    let candidates: &[&'static str] = if duration_secs < ASR_SHORT_CLIP_SECS {
+        &[
+            "ggml-base.bin",
+            "ggml-tiny.bin",
+            "ggml-small.bin",
+            "ggml-medium.bin",
+        ]
+    } else {
+        &[
+            "ggml-small.bin",
+            "ggml-medium.bin",
+            "ggml-base.bin",
+            "ggml-tiny.bin",
+        ]
+    };
+    candidates.iter().copied().find(|name| installed(name))
+}
+
+fn choose_asr_model_for_length(base: &Path, duration_secs: f32) -> Option<PathBuf> {
+    pick_asr_for_length(duration_secs, |name| base.join(name).exists()).map(|name| base.join(name))
+}
+
+/// 加速手势：在已安装的模型中取文件最大的一个（只传入已安装的文件名与大小）。
+fn pick_boost_model<'a>(installed: &[(&'a str, u64)]) -> Option<&'a str> {
+    installed
+        .iter()
+        .max_by_key(|(_, size)| *size)
+        .map(|(name, _)| *name)
+}
+
+fn boosted_model_path(
+    base: &Path,
+    file_names: impl Iterator<Item = &'static str>,
+) -> Option<PathBuf> {
+    let installed: Vec<(&str, u64)> = file_names
+        .filter_map(|name| fs::metadata(base.join(name)).ok().map(|m| (name, m.len())))
+        .collect();
+    pick_boost_model(&installed).map(|name| base.join(name))
+}
+
+// LLM 除权重外还需 KV cache（n_ctx=8192）与 Metal 缓冲。
+const LLM_RUNTIME_OVERHEAD_MB: u64 = 768;
+const LOW_MEMORY_LLM_MB: u64 = 400 + LLM_RUNTIME_OVERHEAD_MB;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MemoryDowngrade {
+    None,
+    Llm,
+    AsrAndLlm,
+}
+
+/// 模型可用内存预算：默认总内存的 40%，其余留给系统与前台应用；`memory_budget_gb=` 可覆盖。
+fn model_memory_budget_mb(mem_gb: u64, override_gb: Option<u64>) -> u64 {
+    match override_gb {
+        Some(gb) => gb * 1024,
+        None => mem_gb * 1024 * 2 / 5,
+    }
+}
+
+/// 自动选型的 ASR + LLM 超出预算时，先降级 LLM，仍不够再降级 ASR。
+fn decide_memory_downgrade(budget: u64, asr_mb: u64, llm_mb: u64) -> MemoryDowngrade {
+    if asr_mb + llm_mb <= budget {
+        MemoryDowngrade::None
+    } else if llm_mb == 0 || asr_mb + LOW_MEMORY_LLM_MB.min(llm_mb) <= budget {
+        MemoryDowngrade::Llm
+    } else {
+        MemoryDowngrade::AsrAndLlm
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LlmRole {
+    Polish,
+    Translate,
+}
+
+impl LlmRole {
+    const ALL: [LlmRole; 2] = [LlmRole::Polish, LlmRole::Translate];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Polish => "润色",
+            Self::Translate => "翻译",
+        }
+    }
+}
+
+/// 按用途额外加载的 LLM；未配置或未加载的用途回退到主 LLM。
+/// 会话类型参数只为测试时换成轻量替身，运行时总是 `ChatSession`。
+struct LlmRegistry<S = mofa_input::llm::ChatSession> {
+    entries: Vec<(LlmRole, PathBuf, S)>,
+}
+
+impl<S> Default for LlmRegistry<S> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
         }
     }
-    choose_asr_model_auto(base)
 }
 
-fn choose_asr_model_auto(base: &Path) -> Option<PathBuf> {
-    [
-        "ggml-small.bin",
-        "ggml-base.bin",
-        "ggml-tiny.bin",
-        "ggml-medium.bin",
-    ]
-    .into_iter()
-    .map(|name| base.join(name))
-    .find(|p| p.exists())
+impl<S> LlmRegistry<S> {
+    fn get(&self, role: LlmRole) -> Option<&S> {
+        self.entries
+            .iter()
+            .find(|(r, _, _)| *r == role)
+            .map(|(_, _, s)| s)
+    }
+
+    fn loaded_path(&self, role: LlmRole) -> Option<&Path> {
+        self.entries
+            .iter()
+            .find(|(r, _, _)| *r == role)
+            .map(|(_, p, _)| p.as_path())
+    }
+
+    fn loaded_mb(&self) -> u64 {
+        self.entries
+            .iter()
+            .map(|(_, p, _)| model_file_mb(Some(p)) + LLM_RUNTIME_OVERHEAD_MB)
+            .sum()
+    }
+
+    fn insert(&mut self, role: LlmRole, path: PathBuf, session: S) {
+        self.remove(role);
+        self.entries.push((role, path, session));
+    }
+
+    fn remove(&mut self, role: LlmRole) {
+        self.entries.retain(|(r, _, _)| *r != role);
+    }
+}
+
+/// 按用途取 LLM：有专用模型用专用模型，否则用主 LLM。
+fn select_llm<'a, S>(
+    registry: &'a LlmRegistry<S>,
+    primary: Option<&'a S>,
+    role: LlmRole,
+) -> Option<&'a S> {
+    registry.get(role).or(primary)
+}
+
+/// 再加载一个用途模型后是否仍在内存预算内；超出则拒绝加载。
+fn role_model_fits_budget(budget_mb: u64, used_mb: u64, candidate_mb: u64) -> bool {
+    used_mb + candidate_mb <= budget_mb
+}
+
+/// 显式选择的模型不做降级，超出预算时只给出提示，避免悄悄换掉用户选的模型。
+fn memory_budget_warning(budget_mb: u64, asr_mb: u64, llm_mb: u64) -> Option<String> {
+    let total_mb = asr_mb + llm_mb;
+    (total_mb > budget_mb).then(|| {
+        format!(
+            "模型约占 {:.1}GB，超出内存预算 {:.1}GB，可能卡顿",
+            total_mb as f64 / 1024.0,
+            budget_mb as f64 / 1024.0
+        )
+    })
+}
+
+/// GGUF 文件以 ASCII `GGUF` 开头。
+fn is_gguf_header(header: &[u8]) -> bool {
+    header.starts_with(b"GGUF")
+}
+
+/// whisper.cpp 的 ggml 模型以小端 u32 0x67676d6c 开头，即字节 `lmgg`。
+fn is_whisper_ggml_header(header: &[u8]) -> bool {
+    header.starts_with(&0x6767_6d6c_u32.to_le_bytes())
+}
+
+fn read_model_header(path: &Path) -> Option<[u8; 4]> {
+    use std::io::Read;
+    let mut header = [0u8; 4];
+    fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+    Some(header)
+}
+
+/// 加载前检查文件头，避免把改名的其它格式文件交给底层库后报出难懂的错误。
+fn validate_model_file(path: &Path, kind: ModelKind) -> std::result::Result<(), String> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let valid = read_model_header(path).is_some_and(|header| match kind {
+        ModelKind::Asr => is_whisper_ggml_header(&header),
+        ModelKind::Llm => is_gguf_header(&header),
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("模型文件格式不正确: {name}"))
+    }
+}
+
+fn model_file_mb(path: Option<&Path>) -> u64 {
+    path.and_then(|p| fs::metadata(p).ok())
+        .map(|m| m.len() / 1024 / 1024)
+        .unwrap_or(0)
 }
 
 fn normalize_transcript(text: &str) -> String {
@@ -107,6 +396,74 @@ fn normalize_transcript(text: &str) -> String {
     out.trim().to_string()
 }
 
+/// Whisper 在静音/噪声上常见的“幻听”模板句（视频字幕套话）。
+/// 整句（忽略大小写、空白与标点）等于某条模板才算，句中只是提到这些词的正常输入不受影响。
+fn is_template_noise_text(text: &str) -> bool {
+    const PATTERNS: [&str; 12] = [
+        "谢谢观看",
+        "感谢观看",
+        "谢谢大家观看",
+        "感谢您的观看",
+        "请不吝点赞订阅转发打赏支持明镜与点点栏目",
+        "点赞订阅",
+        "订阅我的频道",
+        "字幕由amara.org社区提供",
+        "thank you for watching",
+        "thanks for watching",
+        "please subscribe",
+        "thank you for watching please subscribe",
+    ];
+    let key = template_key(text);
+    !key.is_empty() && PATTERNS.iter().any(|p| template_key(p) == key)
+}
+
+/// 模板比较用的归一化：小写，只保留字母数字（含汉字）。
+fn template_key(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 是否丢弃该转写：空文本或只有一个语气词/符号（"嗯"、"♪"）始终丢弃；"好"、"是"这类单字回答保留。
+/// `strict` 时额外丢弃模板噪声句。
+fn should_drop_transcript(text: &str, strict: bool) -> bool {
+    let mut core = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_ascii_punctuation() && !is_cjk_punctuation(*c));
+    match (core.next(), core.next()) {
+        (None, _) => return true,
+        (Some(ch), None) if FILLER_CHARS.contains(&ch) || !ch.is_alphanumeric() => return true,
+        _ => {}
+    }
+    strict && is_template_noise_text(text)
+}
+
+fn is_cjk_punctuation(ch: char) -> bool {
+    matches!(
+        ch,
+        '。' | '，'
+            | '、'
+            | '；'
+            | '：'
+            | '！'
+            | '？'
+            | '…'
+            | '“'
+            | '”'
+            | '‘'
+            | '’'
+            | '（'
+            | '）'
+            | '《'
+            | '》'
+            | '【'
+            | '】'
+            | '—'
+            | '～'
+    )
+}
+
 fn audio_rms(samples: &[f32]) -> f32 {
     if samples.is_empty() {
         return 0.0;
@@ -122,6 +479,16 @@ fn audio_rms(samples: &[f32]) -> f32 {
     mean_square.sqrt() as f32
 }
 
+/// 自动检测语言时展示 Whisper 判定的语言，如“识别 [zh]”；强制了语言或没有结果时不展示。
+fn detected_language_label(configured: AsrLanguage, detected: Option<&str>) -> Option<String> {
+    if configured != AsrLanguage::Auto {
+        return None;
+    }
+    detected
+        .filter(|code| !code.is_empty())
+        .map(|code| format!("识别 [{code}]"))
+}
+
 fn build_refine_prompt(raw_text: &str) -> String {
     format!(
         "你是输入法润色器。将 ASR 文本整理为可直接发送的自然表达。\n\
@@ -141,6 +508,98 @@ fn build_refine_prompt(raw_text: &str) -> String {
     )
 }
 
+/// ASR 模式下的轻量整理：只补标点/大小写、改明显误识，不改写措辞。
+fn build_light_polish_prompt(raw_text: &str) -> String {
+    format!(
+        "你是标点校对器。为下面的 ASR 文本补全标点、修正英文大小写，并改正明显的同音误字。\n\
+规则：\n\
+1) 不改写、不增删、不调整语序，原有词句逐字保留；\n\
+2) 不确定是否为误识时保留原词；\n\
+3) 只输出处理后的文本，不解释。\n\n{}",
+        raw_text
+    )
+}
+
+/// 双语模式的翻译提示词：中文译为英文，英文译为中文，只要译文。
+fn build_translate_prompt(raw_text: &str) -> String {
+    format!(
+        "你是翻译器。将下面的 ASR 文本翻译成另一种语言：中文译为英文，英文译为中文。\n\
+规则：\n\
+1) 忠实原意，不增删信息，专名、数字、代码、URL 原样保留；\n\
+2) 译文自然简洁，不加引号或括号；\n\
+3) 只输出译文，不输出原文，不解释。\n\n{}",
+        raw_text
+    )
+}
+
+/// 双语模式的注入文本：`原文 (译文)`；译文为空或与原文相同时只保留原文。
+fn assemble_bilingual(original: &str, translation: &str) -> String {
+    let translation = translation
+        .trim()
+        .trim_start_matches(['(', '（'])
+        .trim_end_matches([')', '）'])
+        .trim();
+    if translation.is_empty() || translation == original.trim() {
+        return original.to_string();
+    }
+    format!("{original} ({translation})")
+}
+
+/// 按发送模式选择提示词；双语模式用翻译提示词，ASR 模式仅在开启 light_polish 时做轻量整理。
+fn polish_prompt_for(mode: OutputMode, light_polish: bool, raw_text: &str) -> Option<String> {
+    match mode {
+        OutputMode::Llm => Some(build_refine_prompt(raw_text)),
+        OutputMode::Bilingual => Some(build_translate_prompt(raw_text)),
+        OutputMode::Asr if light_polish => Some(build_light_polish_prompt(raw_text)),
+        OutputMode::Asr => None,
+    }
+}
+
+/// 改写热键的备选指令；改写窗口内连按依次切换。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RepolishStyle {
+    Shorter,
+    Formal,
+    Casual,
+}
+
+impl RepolishStyle {
+    const ALL: [RepolishStyle; 3] = [
+        RepolishStyle::Shorter,
+        RepolishStyle::Formal,
+        RepolishStyle::Casual,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            RepolishStyle::Shorter => "更简短",
+            RepolishStyle::Formal => "更正式",
+            RepolishStyle::Casual => "更口语",
+        }
+    }
+
+    fn instruction(self) -> &'static str {
+        match self {
+            RepolishStyle::Shorter => "在不丢失关键信息的前提下尽量精简，删去铺垫与重复",
+            RepolishStyle::Formal => "改为正式、书面的表达，适合工作邮件或文档",
+            RepolishStyle::Casual => "改为轻松自然的口语表达，适合聊天",
+        }
+    }
+}
+
+/// 基于同一段 ASR 原文按指定指令重新润色（不叠加在上一次润色结果上）。
+fn build_repolish_prompt(raw_text: &str, style: RepolishStyle) -> String {
+    format!(
+        "你是输入法润色器。将下面的 ASR 文本整理为可直接发送的文本，并且{}。\n\
+规则：\n\
+1) 保留原意与事实，不新增信息；\n\
+2) 专名、数字、代码、URL 原样保留；\n\
+3) 只输出最终文本，不解释、不提问。\n\n{}",
+        style.instruction(),
+        raw_text
+    )
+}
+
 fn should_skip_llm_refine(raw_text: &str) -> bool {
     let t = raw_text.trim();
     if t.is_empty() {
@@ -166,6 +625,53 @@ fn should_skip_llm_refine(raw_text: &str) -> bool {
     english_letters >= 16 && english_ratio >= 0.9
 }
 
+/// 按语言计词：每个汉字算一词，连续的字母/数字（可含撇号）算一词，标点与空白不计。
+fn count_words(text: &str) -> usize {
+    let mut words = 0;
+    let mut in_word = false;
+    for ch in text.chars() {
+        if ('\u{3400}'..='\u{9FFF}').contains(&ch) {
+            words += 1;
+            in_word = false;
+        } else if ch.is_alphanumeric() || (in_word && ch == '\'') {
+            if !in_word {
+                words += 1;
+                in_word = true;
+            }
+        } else {
+            in_word = false;
+        }
+    }
+    words
+}
+
+/// `llm_min_words`：过短的话语不值得润色（模型容易自行加框架语），直接用 ASR 原文。
+fn below_llm_min_words(raw_text: &str, min_words: usize) -> bool {
+    min_words > 0 && count_words(raw_text) < min_words
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RefineOutcome {
+    Refined(String),
+    FallbackRaw,
+    Failed,
+}
+
+/// llama 封装在失败时返回 `[Error: ...]` 文本，而不是错误码。
+fn is_invalid_llm_output(text: &str) -> bool {
+    let t = text.trim();
+    t.is_empty() || t.starts_with("[Error:")
+}
+
+/// LLM 输出无效（调用失败/为空/错误文本）时，按 `llm_fallback` 决定回退 ASR 原文还是报错。
+fn decide_refine_outcome(llm_out: Option<String>, fallback_enabled: bool) -> RefineOutcome {
+    match llm_out {
+        Some(text) if !is_invalid_llm_output(&text) => RefineOutcome::Refined(text),
+        _ if fallback_enabled => RefineOutcome::FallbackRaw,
+        _ => RefineOutcome::Failed,
+    }
+}
+
 fn has_terminal_punctuation(text: &str) -> bool {
     match text.trim_end().chars().last() {
         Some(ch) => matches!(ch, '。' | '！' | '？' | '.' | '!' | '?' | '…'),
@@ -173,6 +679,88 @@ fn has_terminal_punctuation(text: &str) -> bool {
     }
 }
 
+/// 超过 `max` 个字符时截断到 `max` 之前最后一个句末标点；找不到句末则硬截断。
+/// 英文句点需后跟空白或位于文本末尾，避免在 "3.14" 这类数字中截断。
+fn truncate_at_sentence(text: &str, max: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if max == 0 || chars.len() <= max {
+        return text.to_string();
+    }
+
+    let mut cut = None;
+    for i in (0..max).rev() {
+        let is_end = match chars[i] {
+            '。' | '！' | '？' | '…' | '!' | '?' => true,
+            '.' => chars.get(i + 1).is_none_or(|c| c.is_whitespace()),
+            _ => false,
+        };
+        if is_end {
+            cut = Some(i + 1);
+            break;
+        }
+    }
+
+    let out: String = chars[..cut.unwrap_or(max)].iter().collect();
+    out.trim_end().to_string()
+}
+
+/// 润色回复的 token 上限（即 chat.send 的 max_tokens）。
+const REFINE_MAX_TOKENS: i32 = 384;
+/// 聊天模板（角色标记等）额外占用的 token。
+const CHAT_TEMPLATE_RESERVE_TOKENS: usize = 64;
+
+/// 润色提示词超出上下文时，每段原文的字数上限；放得下或上下文未知（0）时返回 None。
+/// 按原文实际的字/token 比例折算扣除指令与回复预留后的剩余预算。
+fn refine_chunk_chars(
+    raw_chars: usize,
+    raw_tokens: usize,
+    instruction_tokens: usize,
+    context_tokens: usize,
+) -> Option<usize> {
+    if context_tokens == 0 {
+        return None;
+    }
+    let budget =
+        context_tokens.saturating_sub(REFINE_MAX_TOKENS as usize + CHAT_TEMPLATE_RESERVE_TOKENS);
+    if instruction_tokens + raw_tokens <= budget {
+        return None;
+    }
+    let room = budget.saturating_sub(instruction_tokens).max(1);
+    Some((raw_chars * room / raw_tokens.max(1)).max(1))
+}
+
+/// 按句把文本切成不超过 `max_chars` 字的若干段；单句超长时按字硬切。
+fn split_at_sentences(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let head = truncate_at_sentence(rest, max_chars);
+        if head.is_empty() {
+            break;
+        }
+        rest = rest[head.len()..].trim_start();
+        chunks.push(head);
+    }
+    chunks
+}
+
+/// 拼接分段润色结果：两侧都是英文/数字时补一个空格，中文直接相连。
+fn join_refined_chunks(parts: &[String]) -> String {
+    let mut out = String::new();
+    for part in parts.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let prev_ascii = out.chars().last().is_some_and(|c| c.is_ascii_graphic());
+        let next_ascii = part
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric());
+        if prev_ascii && next_ascii {
+            out.push(' ');
+        }
+        out.push_str(part);
+    }
+    out
+}
+
 fn trim_added_terminal_period(raw_text: &str, refined_text: &str) -> String {
     fn strip_trailing_punct(s: &str) -> (&str, &str) {
         let mut cut = s.len();
@@ -221,6 +809,233 @@ fn trim_added_terminal_period(raw_text: &str, refined_text: &str) -> String {
     out
 }
 
+/// strip_markdown：去掉 LLM 输出里的 Markdown 标记（加粗、行内代码、列表符号、标题、引用），保留文字。
+/// 代码块内容原样保留，只删围栏行；不成对的 `**`、反引号视为正文保留（如乘号、shell 片段）。
+fn strip_markdown(text: &str) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") && !line.trim()[3..].contains("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            out.push(line.to_string());
+            continue;
+        }
+        out.push(strip_inline_markdown(&strip_markdown_line_prefix(line)));
+    }
+    out.join("\n")
+}
+
+/// 行首的标题（`#`~`######`）、引用（`>`）与无序列表符号（`-`、`*`、`+`、`•`）。
+fn strip_markdown_line_prefix(line: &str) -> String {
+    let body = line.trim_start();
+    let indent = &line[..line.len() - body.len()];
+    let hashes = body.chars().take_while(|&c| c == '#').count();
+    let rest = if (1..=6).contains(&hashes) && body[hashes..].starts_with(' ') {
+        &body[hashes + 1..]
+    } else if let Some(rest) = body.strip_prefix("> ") {
+        rest
+    } else if let Some(rest) = ["- ", "* ", "+ ", "• "]
+        .iter()
+        .find_map(|marker| body.strip_prefix(marker))
+    {
+        rest
+    } else {
+        body
+    };
+    format!("{indent}{}", rest.trim_start())
+}
+
+/// 成对的反引号去掉（内容原样保留），其余部分去掉成对的 `**`。
+fn strip_inline_markdown(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('`') {
+        let run = rest[start..].chars().take_while(|&c| c == '`').count();
+        let fence = &rest[start..start + run];
+        let after = &rest[start + run..];
+        match after.find(fence) {
+            Some(end) if end > 0 => {
+                out.push_str(&strip_bold_pairs(&rest[..start]));
+                out.push_str(after[..end].trim());
+                rest = &after[end + run..];
+            }
+            _ => {
+                out.push_str(&strip_bold_pairs(&rest[..start + run]));
+                rest = after;
+            }
+        }
+    }
+    out.push_str(&strip_bold_pairs(rest));
+    out
+}
+
+/// `**文字**` -> `文字`；标记内侧紧挨空白（如 `2 ** 3`）时不算加粗。
+fn strip_bold_pairs(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("**") {
+        let after = &rest[start + 2..];
+        match after.find("**") {
+            Some(end)
+                if end > 0
+                    && !after.starts_with(char::is_whitespace)
+                    && !after[..end].ends_with(char::is_whitespace) =>
+            {
+                out.push_str(&rest[..start]);
+                out.push_str(&after[..end]);
+                rest = &after[end + 2..];
+            }
+            _ => {
+                out.push_str(&rest[..start + 2]);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 整句/整个分句只有这些内容时视为口头填充词（比较时忽略大小写）。
+const FILLER_CLAUSES: &[&str] = &[
+    "嗯",
+    "呃",
+    "额",
+    "那个",
+    "这个",
+    "就是说",
+    "um",
+    "uh",
+    "erm",
+    "hmm",
+    "like",
+    "you know",
+];
+/// 出现在任何位置都可去掉的英文填充词。
+const FILLER_WORDS: &[&str] = &["um", "umm", "uh", "uhh", "erm", "hmm"];
+/// 出现在任何位置都可去掉的中文语气填充字。
+const FILLER_CHARS: &[char] = &['嗯', '呃'];
+
+fn is_clause_delimiter(ch: char) -> bool {
+    matches!(
+        ch,
+        ',' | '.' | '!' | '?' | ';' | ':' | '，' | '。' | '！' | '？' | '；' | '：' | '、'
+    )
+}
+
+/// 未安装 LLM 时的规则润色：去掉口头填充词与重复词、整理空格，中文语境下把半角标点换成全角，
+/// 英文句首大写。不增删句末标点（与 `trim_added_terminal_period` 保持一致）。
+fn rule_polish(raw_text: &str, language: AsrLanguage) -> String {
+    let text = normalize_transcript(raw_text);
+    let text: String = text.chars().filter(|c| !FILLER_CHARS.contains(c)).collect();
+
+    // 按标点切分句，丢掉只含填充词的分句（连同其后的逗号）。
+    let mut clauses: Vec<(String, Option<char>)> = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        // 半角标点后紧跟字符（"3.14"、"1,000"）时不是分句。
+        let attached = ch.is_ascii() && chars.peek().is_some_and(|n| !n.is_whitespace());
+        if is_clause_delimiter(ch) && !attached {
+            clauses.push((std::mem::take(&mut current), Some(ch)));
+        } else {
+            current.push(ch);
+        }
+    }
+    clauses.push((current, None));
+
+    let mut out = String::new();
+    for (clause, delimiter) in clauses {
+        let words: Vec<&str> = clause
+            .split_whitespace()
+            .filter(|w| !FILLER_WORDS.contains(&w.to_ascii_lowercase().as_str()))
+            .collect();
+        let mut kept: Vec<&str> = Vec::with_capacity(words.len());
+        for word in words {
+            // "the the" 这类口吃重复只保留一个。
+            let repeated = kept.last().is_some_and(|prev| {
+                prev.eq_ignore_ascii_case(word) && word.chars().all(|c| c.is_ascii_alphabetic())
+            });
+            if !repeated {
+                kept.push(word);
+            }
+        }
+        let clause = kept.join(" ");
+        let is_filler =
+            clause.is_empty() || FILLER_CLAUSES.contains(&clause.to_lowercase().as_str());
+        match delimiter {
+            Some(',' | '，' | '、') | None if is_filler => {}
+            Some(d) if is_filler => {
+                // 句末标点保留在上一分句后面。
+                if !out.is_empty() && !out.ends_with(is_clause_delimiter) {
+                    out.push(d);
+                }
+            }
+            _ => {
+                if !out.is_empty() && !out.ends_with(' ') {
+                    out.push(' ');
+                }
+                out.push_str(&clause);
+                out.extend(delimiter);
+            }
+        }
+    }
+
+    let cjk_context = match language {
+        AsrLanguage::Zh => true,
+        AsrLanguage::En => false,
+        AsrLanguage::Auto => out.chars().any(is_cjk_char),
+    };
+    let chars: Vec<char> = out.chars().collect();
+    let mut polished = String::with_capacity(out.len());
+    let mut sentence_start = true;
+    for (idx, &ch) in chars.iter().enumerate() {
+        let prev = polished.chars().last();
+        let next = chars.get(idx + 1).copied();
+        if ch == ' ' {
+            // 标点前、全角标点后、两个汉字之间不要空格。
+            let drop = match (prev, next) {
+                (Some(p), Some(n)) => {
+                    is_clause_delimiter(n)
+                        || is_cjk_punctuation(p)
+                        || (is_cjk_char(p) && is_cjk_char(n))
+                }
+                _ => true,
+            };
+            if !drop {
+                polished.push(' ');
+            }
+            continue;
+        }
+        let ch = if cjk_context && prev.is_some_and(is_cjk_char) {
+            match ch {
+                ',' => '，',
+                '?' => '？',
+                '!' => '！',
+                ';' => '；',
+                ':' => '：',
+                _ => ch,
+            }
+        } else if sentence_start && !cjk_context {
+            ch.to_ascii_uppercase()
+        } else {
+            ch
+        };
+        if ch.is_alphanumeric() {
+            sentence_start = false;
+        } else if matches!(ch, '。' | '！' | '？')
+            || (matches!(ch, '.' | '!' | '?') && matches!(next, None | Some(' ')))
+        {
+            // "3.14"、"e.g" 中的句点不算句末。
+            sentence_start = true;
+        }
+        polished.push(ch);
+    }
+    polished.trim().to_string()
+}
+
 fn total_memory_gb() -> Option<u64> {
     let name = CString::new("hw.memsize").ok()?;
     let mut value: u64 = 0;
@@ -240,3 +1055,550 @@ fn total_memory_gb() -> Option<u64> {
         None
     }
 }
+
+#[cfg(test)]
+mod text_model_tests {
+    use super::*;
+
+    #[test]
+    fn memory_budget_defaults_to_forty_percent_of_ram() {
+        assert_eq!(model_memory_budget_mb(8, None), 3276);
+        assert_eq!(model_memory_budget_mb(16, None), 6553);
+        assert_eq!(model_memory_budget_mb(16, Some(4)), 4096);
+    }
+
+    #[test]
+    fn downgrade_decision_across_memory_sizes() {
+        // medium ASR (~1.5GB) + 7B LLM (~4.7GB + 运行开销)
+        let (asr, llm) = (1_500, 4_700 + LLM_RUNTIME_OVERHEAD_MB);
+        assert_eq!(
+            decide_memory_downgrade(model_memory_budget_mb(64, None), asr, llm),
+            MemoryDowngrade::None
+        );
+        assert_eq!(
+            decide_memory_downgrade(model_memory_budget_mb(8, None), asr, llm),
+            MemoryDowngrade::Llm
+        );
+        assert_eq!(
+            decide_memory_downgrade(model_memory_budget_mb(4, None), asr, llm),
+            MemoryDowngrade::AsrAndLlm
+        );
+    }
+
+    #[test]
+    fn valid_llm_output_is_used() {
+        assert_eq!(
+            decide_refine_outcome(Some("今天开会。".into()), false),
+            RefineOutcome::Refined("今天开会。".into())
+        );
+    }
+
+    #[test]
+    fn invalid_llm_output_falls_back_only_when_enabled() {
+        for out in [
+            None,
+            Some(String::new()),
+            Some("  \n".into()),
+            Some("[Error: timeout]".into()),
+        ] {
+            assert_eq!(
+                decide_refine_outcome(out.clone(), true),
+                RefineOutcome::FallbackRaw
+            );
+            assert_eq!(decide_refine_outcome(out, false), RefineOutcome::Failed);
+        }
+    }
+
+    #[test]
+    fn empty_and_lone_filler_transcripts_are_dropped() {
+        for text in ["", "  ", "。", "...", "嗯。", "♪"] {
+            assert!(should_drop_transcript(text, false), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn single_character_answers_are_kept() {
+        for text in ["好", "是。", "对！", "I"] {
+            assert!(!should_drop_transcript(text, true), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn template_phrase_survives_when_strict_filter_is_off() {
+        assert!(should_drop_transcript("谢谢观看。", true));
+        assert!(!should_drop_transcript("谢谢观看。", false));
+        assert!(!should_drop_transcript("Thanks for watching!", false));
+    }
+
+    #[test]
+    fn registry_routes_roles_and_falls_back_to_primary() {
+        let mut registry: LlmRegistry<&str> = LlmRegistry::default();
+        registry.insert(LlmRole::Translate, PathBuf::from("/m/big.gguf"), "big");
+        let primary = Some(&"small");
+        assert_eq!(
+            select_llm(&registry, primary, LlmRole::Translate),
+            Some(&"big")
+        );
+        assert_eq!(
+            select_llm(&registry, primary, LlmRole::Polish),
+            Some(&"small")
+        );
+        assert_eq!(select_llm(&registry, None, LlmRole::Polish), None);
+        assert_eq!(
+            registry.loaded_path(LlmRole::Translate),
+            Some(Path::new("/m/big.gguf"))
+        );
+    }
+
+    #[test]
+    fn registry_keeps_one_session_per_role() {
+        let mut registry: LlmRegistry<&str> = LlmRegistry::default();
+        registry.insert(LlmRole::Polish, PathBuf::from("/m/a.gguf"), "a");
+        registry.insert(LlmRole::Polish, PathBuf::from("/m/b.gguf"), "b");
+        assert_eq!(registry.get(LlmRole::Polish), Some(&"b"));
+        // 模型文件不存在时只计运行时开销。
+        assert_eq!(registry.loaded_mb(), LLM_RUNTIME_OVERHEAD_MB);
+        registry.remove(LlmRole::Polish);
+        assert_eq!(registry.get(LlmRole::Polish), None);
+    }
+
+    #[test]
+    fn role_model_is_refused_beyond_budget() {
+        assert!(role_model_fits_budget(8000, 3000, 5000));
+        assert!(!role_model_fits_budget(8000, 3000, 5001));
+        assert!(!role_model_fits_budget(0, 0, 1));
+    }
+
+    #[test]
+    fn truncates_at_last_english_sentence_end() {
+        let text = "First point. Second point! Third point goes on";
+        assert_eq!(truncate_at_sentence(text, 30), "First point. Second point!");
+        // 数字里的句点不算句末。
+        assert_eq!(
+            truncate_at_sentence("Pi is 3.14159 roughly. More", 16),
+            "Pi is 3.14159 ro"
+        );
+        assert_eq!(
+            truncate_at_sentence("Pi is 3.14. More text", 12),
+            "Pi is 3.14."
+        );
+    }
+
+    #[test]
+    fn truncates_at_last_chinese_sentence_end() {
+        let text = "今天开会。讨论预算？下周再定后续安排";
+        assert_eq!(truncate_at_sentence(text, 12), "今天开会。讨论预算？");
+        assert_eq!(truncate_at_sentence(text, 6), "今天开会。");
+    }
+
+    #[test]
+    fn hard_cut_without_boundary_and_no_op_within_limit() {
+        assert_eq!(
+            truncate_at_sentence("没有任何标点的一长串文字", 5),
+            "没有任何标"
+        );
+        assert_eq!(truncate_at_sentence("short", 10), "short");
+        assert_eq!(truncate_at_sentence("不限制。", 0), "不限制。");
+    }
+
+    #[test]
+    fn short_clips_prefer_small_models() {
+        let all = |_: &str| true;
+        assert_eq!(pick_asr_for_length(2.0, all), Some("ggml-base.bin"));
+        let only_tiny_and_small = |name: &str| name == "ggml-tiny.bin" || name == "ggml-small.bin";
+        assert_eq!(
+            pick_asr_for_length(2.0, only_tiny_and_small),
+            Some("ggml-tiny.bin")
+        );
+        // 只装了大模型时短句也用它。
+        assert_eq!(
+            pick_asr_for_length(2.0, |name: &str| name == "ggml-medium.bin"),
+            Some("ggml-medium.bin")
+        );
+    }
+
+    #[test]
+    fn long_dictation_prefers_accurate_models() {
+        assert_eq!(
+            pick_asr_for_length(ASR_SHORT_CLIP_SECS, |_: &str| true),
+            Some("ggml-small.bin")
+        );
+        let no_small = |name: &str| name != "ggml-small.bin";
+        assert_eq!(pick_asr_for_length(30.0, no_small), Some("ggml-medium.bin"));
+        assert_eq!(
+            pick_asr_for_length(30.0, |name: &str| name == "ggml-tiny.bin"),
+            Some("ggml-tiny.bin")
+        );
+        assert_eq!(pick_asr_for_length(30.0, |_: &str| false), None);
+    }
+
+    #[test]
+    fn cycle_skips_models_that_are_not_installed() {
+        let all = ["auto", "tiny", "base", "small", "medium"];
+        let installed = |c: &str| matches!(c, "auto" | "base" | "medium");
+        assert_eq!(cycle_to_next_installed(&all, "auto", installed), "base");
+        assert_eq!(cycle_to_next_installed(&all, "base", installed), "medium");
+        // 末尾回到开头。
+        assert_eq!(cycle_to_next_installed(&all, "medium", installed), "auto");
+        // 当前选择本身未安装时也能切走。
+        assert_eq!(cycle_to_next_installed(&all, "tiny", installed), "base");
+    }
+
+    #[test]
+    fn cycle_stays_put_without_other_installed_models() {
+        let all = ["auto", "tiny", "base"];
+        assert_eq!(
+            cycle_to_next_installed(&all, "auto", |c: &str| c == "auto"),
+            "auto"
+        );
+        assert_eq!(
+            cycle_to_next_installed(&all, "tiny", |_: &str| false),
+            "tiny"
+        );
+    }
+
+    #[test]
+    fn asr_mode_uses_the_light_prompt_only_when_enabled() {
+        let raw = "今天下午三点开会";
+        assert_eq!(
+            polish_prompt_for(OutputMode::Asr, true, raw),
+            Some(build_light_polish_prompt(raw))
+        );
+        assert_eq!(polish_prompt_for(OutputMode::Asr, false, raw), None);
+        // 其它模式不受 light_polish 影响。
+        assert_eq!(
+            polish_prompt_for(OutputMode::Llm, true, raw),
+            Some(build_refine_prompt(raw))
+        );
+    }
+
+    #[test]
+    fn light_prompt_is_narrower_than_full_refine() {
+        let raw = "hello 世界";
+        let light = build_light_polish_prompt(raw);
+        let full = build_refine_prompt(raw);
+        assert_ne!(light, full);
+        assert!(light.ends_with(raw));
+        assert!(light.contains("不改写"));
+        assert!(light.len() < full.len());
+    }
+
+    #[test]
+    fn magic_bytes_identify_model_formats() {
+        assert!(is_gguf_header(b"GGUF"));
+        assert!(!is_gguf_header(b"lmgg"));
+        assert!(is_whisper_ggml_header(b"lmgg"));
+        assert!(!is_whisper_ggml_header(b"GGUF"));
+        assert!(!is_gguf_header(b"\0\0\0\0"));
+        assert!(!is_whisper_ggml_header(b"<htm"));
+        assert!(!is_gguf_header(b"GG"));
+    }
+
+    #[test]
+    fn validation_names_the_offending_file() {
+        let dir = std::env::temp_dir().join(format!("mofa-model-magic-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let llm = dir.join("qwen2.5-0.5b-q4_k_m.gguf");
+        let asr = dir.join("ggml-base.bin");
+        fs::write(&llm, b"GGUF\x03\0\0\0").unwrap();
+        fs::write(&asr, b"lmgg\x01\0\0\0").unwrap();
+
+        assert_eq!(validate_model_file(&llm, ModelKind::Llm), Ok(()));
+        assert_eq!(validate_model_file(&asr, ModelKind::Asr), Ok(()));
+        // 改名的其它格式文件、被截断或缺失的文件都判为格式不正确。
+        assert_eq!(
+            validate_model_file(&llm, ModelKind::Asr),
+            Err("模型文件格式不正确: qwen2.5-0.5b-q4_k_m.gguf".to_string())
+        );
+        fs::write(&asr, b"GG").unwrap();
+        assert!(validate_model_file(&asr, ModelKind::Asr).is_err());
+        assert!(validate_model_file(&dir.join("missing.bin"), ModelKind::Asr).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prompt_within_context_is_sent_whole() {
+        assert_eq!(refine_chunk_chars(800, 400, 300, 0), None);
+        assert_eq!(refine_chunk_chars(100, 50, 300, 4096), None);
+        let budget = 4096 - REFINE_MAX_TOKENS as usize - CHAT_TEMPLATE_RESERVE_TOKENS;
+        assert_eq!(refine_chunk_chars(100, budget - 300, 300, 4096), None);
+    }
+
+    #[test]
+    fn over_context_prompt_is_chunked_by_char_ratio() {
+        // 预算 1000 - 384 - 64 = 552，扣掉指令 300 剩 252 token；原文 2 字/token。
+        assert_eq!(refine_chunk_chars(800, 400, 300, 1000), Some(504));
+        // 指令本身就放不下时每段至少一个字，不会死循环。
+        assert_eq!(refine_chunk_chars(800, 400, 900, 1000), Some(2));
+    }
+
+    #[test]
+    fn chunks_break_at_sentence_ends() {
+        assert_eq!(
+            split_at_sentences("第一句。第二句。第三句。", 8),
+            vec!["第一句。第二句。", "第三句。"]
+        );
+        assert_eq!(
+            split_at_sentences("One. Two. Three.", 10),
+            vec!["One. Two.", "Three."]
+        );
+        assert_eq!(
+            split_at_sentences("abcdefghij", 4),
+            vec!["abcd", "efgh", "ij"]
+        );
+        assert!(split_at_sentences("   ", 4).is_empty());
+    }
+
+    #[test]
+    fn chunks_rejoin_with_spaces_only_between_latin_words() {
+        let parts = ["第一句。".to_string(), "第二句。".to_string()];
+        assert_eq!(join_refined_chunks(&parts), "第一句。第二句。");
+        let parts = ["One.".to_string(), " ".to_string(), "Two.".to_string()];
+        assert_eq!(join_refined_chunks(&parts), "One. Two.");
+    }
+
+    #[test]
+    fn words_are_counted_per_language() {
+        assert_eq!(count_words("Send it now."), 3);
+        assert_eq!(count_words("don't stop"), 2);
+        assert_eq!(count_words("好的，谢谢"), 4);
+        assert_eq!(count_words("用 Rust 写 3 个 demo"), 6);
+        assert_eq!(count_words("  ，。!"), 0);
+    }
+
+    #[test]
+    fn short_utterances_skip_polishing() {
+        assert!(below_llm_min_words("OK thanks", 3));
+        assert!(!below_llm_min_words("see you tomorrow", 3));
+        assert!(below_llm_min_words("好的", 3));
+        assert!(!below_llm_min_words("明天见", 3));
+        // 默认 0：总是润色。
+        assert!(!below_llm_min_words("嗯", 0));
+    }
+
+    #[test]
+    fn budget_warning_only_when_models_exceed_it() {
+        assert_eq!(memory_budget_warning(6_000, 1_500, 4_000), None);
+        assert_eq!(memory_budget_warning(6_000, 1_500, 4_500), None);
+        assert_eq!(
+            memory_budget_warning(4_096, 1_536, 5_632),
+            Some("模型约占 7.0GB，超出内存预算 4.0GB，可能卡顿".to_string())
+        );
+        // 推迟加载的 LLM 不计入。
+        assert_eq!(memory_budget_warning(1_024, 600, 0), None);
+    }
+
+    #[test]
+    fn boost_picks_the_largest_installed_model() {
+        assert_eq!(
+            pick_boost_model(&[
+                ("ggml-base.bin", 148),
+                ("ggml-large-v3.bin", 3_100),
+                ("ggml-small.bin", 488)
+            ]),
+            Some("ggml-large-v3.bin")
+        );
+        assert_eq!(pick_boost_model(&[]), None);
+    }
+
+    #[test]
+    fn boosted_model_path_skips_missing_files() {
+        let dir = std::env::temp_dir().join(format!("mofa-boost-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("ggml-base.bin"), vec![0u8; 16]).unwrap();
+        fs::write(dir.join("ggml-small.bin"), vec![0u8; 64]).unwrap();
+
+        let names = ["ggml-base.bin", "ggml-small.bin", "ggml-large-v3.bin"];
+        assert_eq!(
+            boosted_model_path(&dir, names.into_iter()),
+            Some(dir.join("ggml-small.bin"))
+        );
+        assert_eq!(
+            boosted_model_path(&dir, ["ggml-large-v3.bin"].into_iter()),
+            None
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rule_polish_removes_chinese_fillers() {
+        assert_eq!(
+            rule_polish("嗯，那个，我们明天开会。", AsrLanguage::Zh),
+            "我们明天开会。"
+        );
+        assert_eq!(
+            rule_polish("嗯我觉得，这个方案可以", AsrLanguage::Zh),
+            "我觉得，这个方案可以"
+        );
+        assert_eq!(rule_polish("那个人是谁?", AsrLanguage::Zh), "那个人是谁？");
+    }
+
+    #[test]
+    fn rule_polish_removes_english_fillers() {
+        assert_eq!(
+            rule_polish("um, like, we ship it.", AsrLanguage::En),
+            "We ship it."
+        );
+        assert_eq!(
+            rule_polish("I like the the plan", AsrLanguage::En),
+            "I like the plan"
+        );
+        assert_eq!(
+            rule_polish("uh pi is 3.14, right", AsrLanguage::En),
+            "Pi is 3.14, right"
+        );
+    }
+
+    #[test]
+    fn strip_markdown_removes_bold_and_inline_code_marks() {
+        assert_eq!(
+            strip_markdown("请**务必**运行 `cargo test`。"),
+            "请务必运行 cargo test。"
+        );
+        // 不成对或两侧有空格的标记属于正文。
+        assert_eq!(strip_markdown("2 ** 3 = 8"), "2 ** 3 = 8");
+        assert_eq!(strip_markdown("用 ` 分隔"), "用 ` 分隔");
+    }
+
+    #[test]
+    fn strip_markdown_removes_list_markers_and_headers() {
+        let text = "## 待办\n- 买菜\n* 写周报\n  + 缩进项\n> 引用\n1. 编号保留";
+        assert_eq!(
+            strip_markdown(text),
+            "待办\n买菜\n写周报\n  缩进项\n引用\n1. 编号保留"
+        );
+        assert_eq!(strip_markdown("#hashtag"), "#hashtag");
+    }
+
+    #[test]
+    fn strip_markdown_keeps_code_fence_contents() {
+        let text = "示例：\n```rust\nlet x = **p;\n- not a list\n```\n完";
+        assert_eq!(
+            strip_markdown(text),
+            "示例：\nlet x = **p;\n- not a list\n完"
+        );
+    }
+
+    #[test]
+    fn bilingual_appends_translation_in_parentheses() {
+        assert_eq!(
+            assemble_bilingual("今天开会", "We have a meeting today."),
+            "今天开会 (We have a meeting today.)"
+        );
+        // 模型自带的括号（含全角）去掉后再包一层
+        assert_eq!(assemble_bilingual("hello", "（你好）"), "hello (你好)");
+        // 空译文或与原文相同只保留原文
+        assert_eq!(assemble_bilingual("hello", "  "), "hello");
+        assert_eq!(assemble_bilingual("OK", " (OK) "), "OK");
+    }
+
+    #[test]
+    fn bilingual_mode_selects_translate_prompt() {
+        let raw = "今天开会";
+        assert_eq!(
+            polish_prompt_for(OutputMode::Bilingual, false, raw),
+            Some(build_translate_prompt(raw))
+        );
+        assert_eq!(
+            polish_prompt_for(OutputMode::Bilingual, true, raw),
+            Some(build_translate_prompt(raw))
+        );
+        assert_eq!(
+            polish_prompt_for(OutputMode::Llm, false, raw),
+            Some(build_refine_prompt(raw))
+        );
+        assert_eq!(polish_prompt_for(OutputMode::Asr, false, raw), None);
+        assert!(build_translate_prompt(raw).ends_with(raw));
+    }
+
+    #[test]
+    fn output_mode_tokens_round_trip() {
+        for mode in OutputMode::ALL {
+            assert_eq!(OutputMode::from_token(mode.token()), Some(mode));
+        }
+        assert_eq!(
+            OutputMode::from_token(" Bilingual "),
+            Some(OutputMode::Bilingual)
+        );
+        assert_eq!(OutputMode::from_token("translate"), None);
+    }
+
+    #[test]
+    fn english_variant_is_picked_only_when_forced_and_installed() {
+        let installed = |name: &str| name == "ggml-base.en.bin";
+        assert_eq!(
+            pick_asr_language_variant("ggml-base.bin", AsrLanguage::En, installed),
+            Some("ggml-base.en.bin".to_string())
+        );
+        assert_eq!(
+            pick_asr_language_variant("ggml-base.bin", AsrLanguage::Auto, installed),
+            None
+        );
+        assert_eq!(
+            pick_asr_language_variant("ggml-small.bin", AsrLanguage::En, installed),
+            None
+        );
+        assert_eq!(english_variant_name("ggml-base.en.bin"), None);
+        assert_eq!(english_variant_name("ggml-large-v3.bin"), None);
+    }
+
+    #[test]
+    fn choose_asr_model_prefers_installed_english_variant() {
+        let dir = std::env::temp_dir().join(format!("mofa-asr-en-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("ggml-base.bin"), b"").unwrap();
+        fs::write(dir.join("ggml-base.en.bin"), b"").unwrap();
+
+        let choose = |language| choose_asr_model(&dir, AsrModelChoice::Auto, language);
+        assert_eq!(choose(AsrLanguage::En), Some(dir.join("ggml-base.en.bin")));
+        assert_eq!(choose(AsrLanguage::Zh), Some(dir.join("ggml-base.bin")));
+        // 显式指定的 .en 模型在任何语言下都直接使用
+        assert_eq!(
+            choose_asr_model(&dir, AsrModelChoice::BaseEn, AsrLanguage::Auto),
+            Some(dir.join("ggml-base.en.bin"))
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn english_only_choices_round_trip() {
+        for choice in AsrModelChoice::ALL {
+            assert_eq!(AsrModelChoice::from_token(choice.token()), Some(choice));
+        }
+        assert_eq!(
+            AsrModelChoice::from_token("base.en"),
+            Some(AsrModelChoice::BaseEn)
+        );
+    }
+
+    #[test]
+    fn detected_language_is_shown_only_under_auto() {
+        assert_eq!(
+            detected_language_label(AsrLanguage::Auto, Some("zh")),
+            Some("识别 [zh]".to_string())
+        );
+        assert_eq!(
+            detected_language_label(AsrLanguage::Auto, Some("en")),
+            Some("识别 [en]".to_string())
+        );
+        assert_eq!(detected_language_label(AsrLanguage::Auto, Some("")), None);
+        assert_eq!(detected_language_label(AsrLanguage::Auto, None), None);
+        // 强制语言时 Whisper 返回的就是配置值，不必再展示
+        assert_eq!(detected_language_label(AsrLanguage::En, Some("en")), None);
+    }
+
+    #[test]
+    fn template_filter_matches_whole_utterance_only() {
+        assert!(should_drop_transcript("Thanks for watching!", true));
+        assert!(should_drop_transcript("字幕由 Amara.org 社区提供", true));
+        for text in [
+            "这期视频的字幕由小王整理",
+            "记得点赞订阅这个项目的更新",
+            "He said please subscribe to the newsletter.",
+            "谢谢观看这个演示的各位",
+        ] {
+            assert!(!should_drop_transcript(text, true), "{text:?}");
+        }
+    }
+}
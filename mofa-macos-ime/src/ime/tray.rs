@@ -27,6 +27,50 @@ impl TrayState {
             TrayState::Error => "exclamationmark.triangle.fill",
         }
     }
+
+    /// 写入 `~/.mofa/state` 的状态名，供外部状态栏读取。
+    fn token(self) -> &'static str {
+        match self {
+            TrayState::Idle => "idle",
+            TrayState::Recording => "recording",
+            TrayState::Processing => "processing",
+            TrayState::Injected => "injected",
+            TrayState::Error => "error",
+        }
+    }
+}
+
+fn state_file_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".mofa/state"))
+}
+
+/// 状态变化时写入状态文件：先写临时文件再 rename，读取方不会读到半行；状态未变则跳过。
+fn write_state_file(state: TrayState) {
+    static LAST_WRITTEN: Mutex<Option<&'static str>> = Mutex::new(None);
+
+    let token = state.token();
+    let mut last = LAST_WRITTEN.lock().unwrap_or_else(|e| e.into_inner());
+    if *last == Some(token) {
+        return;
+    }
+    let Some(path) = state_file_path() else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let tmp = path.with_extension("tmp");
+    let written = fs::write(&tmp, format!("{token}\n")).and_then(|_| fs::rename(&tmp, &path));
+    match written {
+        Ok(()) => *last = Some(token),
+        Err(e) => eprintln!("[mofa-ime] 写入状态文件失败: {e}"),
+    }
+}
+
+fn remove_state_file() {
+    if let Some(path) = state_file_path() {
+        let _ = fs::remove_file(path);
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -36,6 +80,7 @@ struct StatusHandle {
 
 impl StatusHandle {
     fn set(self, state: TrayState) {
+        write_state_file(state);
         let button_ptr = self.button_ptr;
         let title = state.title().to_string();
         let symbol = state.symbol_name().to_string();
@@ -63,11 +108,19 @@ impl MonitorHandle {
     }
 
     fn set_asr(self, text: &str) {
-        self.set_item(self.asr_item_ptr, "识别", text);
+        self.set_item(
+            self.asr_item_ptr,
+            "识别",
+            privacy_redact(text, privacy_mode()),
+        );
     }
 
     fn set_output(self, text: &str) {
-        self.set_item(self.output_item_ptr, "发送", text);
+        self.set_item(
+            self.output_item_ptr,
+            "发送",
+            privacy_redact(text, privacy_mode()),
+        );
     }
 
     fn set_hint(self, text: &str) {
@@ -85,12 +138,9 @@ impl MonitorHandle {
     }
 }
 
+// 主浮层与悬浮球的窗口指针放在 OVERLAY_WINDOWS 中（配置变更时会重建），需要时在主线程读取。
 #[derive(Clone, Copy)]
 struct OverlayHandle {
-    window_ptr: usize,
-    status_badge_ptr: usize,
-    status_label_ptr: usize,
-    preview_label_ptr: usize,
     // History window
     history_window_ptr: usize,
     history_title_ptr: usize,
@@ -99,9 +149,9 @@ struct OverlayHandle {
     history_list_view_ptr: usize,
     clipboard_scroll_view_ptr: usize,
     clipboard_list_view_ptr: usize,
+    snippet_scroll_view_ptr: usize,
+    snippet_list_view_ptr: usize,
     history_close_btn_ptr: usize,
-    // Floating orb (常驻悬浮球)
-    orb_window_ptr: usize,
 }
 
 impl OverlayHandle {
@@ -130,29 +180,46 @@ impl OverlayHandle {
     }
 
     fn set_preview(self, text: &str) {
-        let line = wrap_preview_text(text);
+        let line = privacy_redact(text, privacy_mode()).to_string();
         self.update(true, None, Some(line));
     }
 
+    /// 预览下方附带一行拼音（show_pinyin）；其它预览更新会清空拼音行。
+    fn set_preview_with_pinyin(self, text: &str, pinyin: &str) {
+        if privacy_mode() {
+            self.set_preview(text);
+            return;
+        }
+        let line = text.to_string();
+        self.update_with_pinyin(true, None, Some(line), Some(pinyin.to_string()));
+    }
+
     fn hide(self) {
         self.update(false, None, None);
     }
 
+    /// overlay=off 时主浮层未创建，所有显示调用直接返回。
+    fn overlay_enabled(self) -> bool {
+        overlay_windows().window != 0
+    }
+
     fn fade_out_quick(self) {
-        let window_ptr = self.window_ptr;
-        let step_ms = (OVERLAY_FADE_TOTAL_MS / OVERLAY_FADE_STEPS.max(1)).max(1);
-        for idx in (0..OVERLAY_FADE_STEPS).rev() {
-            let alpha = idx as f64 / OVERLAY_FADE_STEPS as f64;
-            Queue::main().exec_sync(move || unsafe {
-                let window = window_ptr as id;
+        if !self.overlay_enabled() {
+            return;
+        }
+        // 只在工作线程上 sleep；主队列只接收异步的 alpha 更新，避免阻塞 UI。
+        let (step_ms, alphas) = overlay_fade_schedule(OVERLAY_FADE_TOTAL_MS, OVERLAY_FADE_STEPS);
+        for alpha in alphas {
+            Queue::main().exec_async(move || unsafe {
+                let window = overlay_windows().window as id;
                 if window != nil {
                     let _: () = msg_send![window, setAlphaValue: alpha];
                 }
             });
             std::thread::sleep(Duration::from_millis(step_ms));
         }
-        Queue::main().exec_sync(move || unsafe {
-            let window = window_ptr as id;
+        Queue::main().exec_async(move || unsafe {
+            let window = overlay_windows().window as id;
             if window != nil {
                 window.orderOut_(nil);
                 let _: () = msg_send![window, setAlphaValue: 1.0f64];
@@ -161,24 +228,48 @@ impl OverlayHandle {
     }
 
     fn show(self, status: &str, preview: &str) {
-        self.update(
-            true,
-            Some(status.to_string()),
-            Some(wrap_preview_text(preview)),
-        );
+        self.update(true, Some(status.to_string()), Some(preview.to_string()));
     }
 
     fn update(self, visible: bool, status: Option<String>, preview: Option<String>) {
-        let window_ptr = self.window_ptr;
-        let status_badge_ptr = self.status_badge_ptr;
-        let status_ptr = self.status_label_ptr;
-        let preview_ptr = self.preview_label_ptr;
+        self.update_with_pinyin(visible, status, preview, None);
+    }
+
+    fn update_with_pinyin(
+        self,
+        visible: bool,
+        status: Option<String>,
+        preview: Option<String>,
+        pinyin: Option<String>,
+    ) {
+        if visible {
+            note_orb_activity();
+        }
+        if !self.overlay_enabled() {
+            return;
+        }
         Queue::main().exec_async(move || unsafe {
-            let window = window_ptr as id;
+            let windows = overlay_windows();
+            let window = windows.window as id;
+            let status_badge_ptr = windows.status_badge;
+            let status_ptr = windows.status_label;
+            let preview_ptr = windows.preview_label;
+            let pinyin_label = windows.pinyin_label as id;
             if window == nil {
                 return;
             }
-            let preview_for_layout = preview.map(|p| wrap_preview_text(&p));
+            // 换行在主线程按预览字体实测宽度完成。
+            let preview_for_layout =
+                preview.map(|p| wrap_preview_text_measured(&p, preview_ptr as id));
+            // 只有更新预览时才同步拼音行：给了拼音就显示，否则清空。
+            let pinyin_for_layout = preview_for_layout
+                .as_ref()
+                .map(|_| pinyin.unwrap_or_default());
+            if let Some(py) = pinyin_for_layout.as_ref() {
+                if pinyin_label != nil {
+                    let _: () = msg_send![pinyin_label, setStringValue: ns_string(py)];
+                }
+            }
 
             if let Some(s) = status {
                 let status_badge = status_badge_ptr as id;
@@ -208,12 +299,22 @@ impl OverlayHandle {
                     let preview_ns: id = msg_send![preview_label, stringValue];
                     nsstring_to_rust(preview_ns).unwrap_or_default()
                 };
+                let pinyin_text = match pinyin_for_layout {
+                    Some(py) => py,
+                    None if pinyin_label != nil => {
+                        let pinyin_ns: id = msg_send![pinyin_label, stringValue];
+                        nsstring_to_rust(pinyin_ns).unwrap_or_default()
+                    }
+                    None => String::new(),
+                };
                 layout_overlay_window(
                     window,
                     status_badge,
                     status_label,
                     preview_label,
                     &preview_text,
+                    pinyin_label,
+                    &pinyin_text,
                 );
             }
 
@@ -236,11 +337,14 @@ impl OverlayHandle {
         let history_list_view_ptr = self.history_list_view_ptr;
         let clipboard_scroll_view_ptr = self.clipboard_scroll_view_ptr;
         let clipboard_list_view_ptr = self.clipboard_list_view_ptr;
+        let snippet_scroll_view_ptr = self.snippet_scroll_view_ptr;
+        let snippet_list_view_ptr = self.snippet_list_view_ptr;
         let _close_btn_ptr = self.history_close_btn_ptr;
 
         // Get current data
         let history = get_history_items();
         let clipboard = get_clipboard_items();
+        let snippets = load_snippets();
         let active_tab = get_history_tab_index();
 
         Queue::main().exec_async(move || unsafe {
@@ -255,6 +359,8 @@ impl OverlayHandle {
             let history_list_view = history_list_view_ptr as id;
             let clipboard_scroll_view = clipboard_scroll_view_ptr as id;
             let clipboard_list_view = clipboard_list_view_ptr as id;
+            let snippet_scroll_view = snippet_scroll_view_ptr as id;
+            let snippet_list_view = snippet_list_view_ptr as id;
 
             rebuild_history_list_view(
                 history_scroll_view,
@@ -268,12 +374,19 @@ impl OverlayHandle {
                 &clipboard,
                 active_tab == 1,
             );
+            rebuild_snippet_list_view(
+                snippet_scroll_view,
+                snippet_list_view,
+                &snippets,
+                active_tab == HISTORY_TAB_SNIPPETS,
+            );
             apply_history_tab_ui(
                 active_tab,
                 tab_control,
                 title_label,
                 history_scroll_view,
                 clipboard_scroll_view,
+                snippet_scroll_view,
             );
 
             // Position history window same side as main overlay
@@ -352,10 +465,13 @@ impl OverlayHandle {
         let history_list_view_ptr = self.history_list_view_ptr;
         let clipboard_scroll_view_ptr = self.clipboard_scroll_view_ptr;
         let clipboard_list_view_ptr = self.clipboard_list_view_ptr;
+        let snippet_scroll_view_ptr = self.snippet_scroll_view_ptr;
+        let snippet_list_view_ptr = self.snippet_list_view_ptr;
 
         // Get latest data
         let history = get_history_items();
         let clipboard = get_clipboard_items();
+        let snippets = load_snippets();
         let active_tab = get_history_tab_index();
 
         Queue::main().exec_async(move || unsafe {
@@ -376,6 +492,8 @@ impl OverlayHandle {
             let history_list_view = history_list_view_ptr as id;
             let clipboard_scroll_view = clipboard_scroll_view_ptr as id;
             let clipboard_list_view = clipboard_list_view_ptr as id;
+            let snippet_scroll_view = snippet_scroll_view_ptr as id;
+            let snippet_list_view = snippet_list_view_ptr as id;
 
             rebuild_history_list_view(
                 history_scroll_view,
@@ -389,12 +507,19 @@ impl OverlayHandle {
                 &clipboard,
                 active_tab == 1,
             );
+            rebuild_snippet_list_view(
+                snippet_scroll_view,
+                snippet_list_view,
+                &snippets,
+                active_tab == HISTORY_TAB_SNIPPETS,
+            );
             apply_history_tab_ui(
                 active_tab,
                 tab_control,
                 title_label,
                 history_scroll_view,
                 clipboard_scroll_view,
+                snippet_scroll_view,
             );
         });
     }
@@ -411,9 +536,8 @@ impl OverlayHandle {
     }
 
     fn show_orb(self) {
-        let orb_window_ptr = self.orb_window_ptr;
         Queue::main().exec_async(move || unsafe {
-            let window = orb_window_ptr as id;
+            let window = overlay_windows().orb_window as id;
             if window != nil {
                 window.orderFrontRegardless();
             }
@@ -421,9 +545,8 @@ impl OverlayHandle {
     }
 
     fn hide_orb(self) {
-        let orb_window_ptr = self.orb_window_ptr;
         Queue::main().exec_async(move || unsafe {
-            let window = orb_window_ptr as id;
+            let window = overlay_windows().orb_window as id;
             if window != nil {
                 window.orderOut_(nil);
             }
@@ -486,7 +609,16 @@ unsafe fn rebuild_history_list_view(scroll_view: id, list_view: id, history: &[S
     } else {
         let copy_delegate = create_copy_delegate();
         let copy_btn_width = 32.0;
-        let text_width = (content_width - copy_btn_width - 8.0).max(72.0);
+        // 有录音的条目在复制按钮右侧多一个播放按钮。
+        let has_audio: Vec<bool> = (0..history.len())
+            .map(|i| history_audio_path(i).is_some())
+            .collect();
+        let play_width = if has_audio.contains(&true) {
+            copy_btn_width + 4.0
+        } else {
+            0.0
+        };
+        let text_width = (content_width - copy_btn_width - play_width - 8.0).max(72.0);
 
         for (i, text) in history.iter().enumerate() {
             let row_y = doc_height - ((i as f64 + 1.0) * row_height);
@@ -522,6 +654,24 @@ unsafe fn rebuild_history_list_view(scroll_view: id, list_view: id, history: &[S
             let _: () = msg_send![copy_btn, setTarget: copy_delegate];
             let _: () = msg_send![copy_btn, setAction: sel!(copyHistoryItem:)];
             let _: () = msg_send![list_view, addSubview: copy_btn];
+
+            if has_audio[i] {
+                let play_btn = NSButton::initWithFrame_(
+                    NSButton::alloc(nil),
+                    NSRect::new(
+                        NSPoint::new(text_width + play_width + 4.0, row_y + 8.0),
+                        NSSize::new(copy_btn_width, 24.0),
+                    ),
+                );
+                let _: () = msg_send![play_btn, setBezelStyle: 8usize];
+                let _: () = msg_send![play_btn, setBordered: YES];
+                let _: () = msg_send![play_btn, setButtonType: 0usize];
+                set_status_button_symbol(play_btn, "play.fill");
+                let _: () = msg_send![play_btn, setTag: i as isize];
+                let _: () = msg_send![play_btn, setTarget: copy_delegate];
+                let _: () = msg_send![play_btn, setAction: sel!(playHistoryAudio:)];
+                let _: () = msg_send![list_view, addSubview: play_btn];
+            }
         }
     }
 
@@ -663,6 +813,133 @@ unsafe fn rebuild_clipboard_list_view(
     }
 }
 
+unsafe fn rebuild_snippet_list_view(
+    scroll_view: id,
+    list_view: id,
+    snippets: &[String],
+    scroll_to_top: bool,
+) {
+    if scroll_view == nil || list_view == nil {
+        return;
+    }
+
+    loop {
+        let subviews: id = msg_send![list_view, subviews];
+        let count: usize = msg_send![subviews, count];
+        if count == 0 {
+            break;
+        }
+        let subview: id = msg_send![subviews, objectAtIndex: count - 1];
+        let _: () = msg_send![subview, removeFromSuperview];
+    }
+
+    let scroll_frame: NSRect = msg_send![scroll_view, frame];
+    let visible_height = scroll_frame.size.height.max(SNIPPET_ITEM_HEIGHT);
+    let content_width = (scroll_frame.size.width - 4.0).max(120.0);
+    let row_height = SNIPPET_ITEM_HEIGHT.max(28.0);
+    let row_count = snippets.len().max(1);
+    let doc_height = (row_count as f64 * row_height).max(visible_height);
+    let _: () = msg_send![
+        list_view,
+        setFrame: NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new(content_width, doc_height))
+    ];
+
+    if snippets.is_empty() {
+        let empty_label = NSTextField::initWithFrame_(
+            NSTextField::alloc(nil),
+            NSRect::new(
+                NSPoint::new(2.0, doc_height - row_height + 4.0),
+                NSSize::new(content_width - 4.0, 24.0),
+            ),
+        );
+        let _: () = msg_send![empty_label, setEditable: NO];
+        let _: () = msg_send![empty_label, setSelectable: NO];
+        let _: () = msg_send![empty_label, setBezeled: NO];
+        let _: () = msg_send![empty_label, setBordered: NO];
+        let _: () = msg_send![empty_label, setDrawsBackground: NO];
+        let text_font: id = msg_send![class!(NSFont), systemFontOfSize: 12.0f64];
+        let _: () = msg_send![empty_label, setFont: text_font];
+        let text_color: id =
+            msg_send![class!(NSColor), colorWithCalibratedWhite: 0.72f64 alpha: 1.0f64];
+        let _: () = msg_send![empty_label, setTextColor: text_color];
+        let _: () = msg_send![empty_label, setLineBreakMode: 4usize];
+        let _: () =
+            msg_send![empty_label, setStringValue: ns_string("（在 ~/.mofa/snippets.txt 中添加）")];
+        let cell: id = msg_send![empty_label, cell];
+        if cell != nil {
+            let _: () = msg_send![cell, setAlignment: 1usize];
+        }
+        let _: () = msg_send![list_view, addSubview: empty_label];
+    } else {
+        let delegate = create_snippet_delegate();
+        let btn_width = 32.0;
+        let text_width = (content_width - btn_width * 2.0 - 12.0).max(72.0);
+
+        for (i, snippet) in snippets.iter().enumerate() {
+            let row_y = doc_height - ((i as f64 + 1.0) * row_height);
+            let text_label = NSTextField::initWithFrame_(
+                NSTextField::alloc(nil),
+                NSRect::new(
+                    NSPoint::new(0.0, row_y + 4.0),
+                    NSSize::new(text_width, 24.0),
+                ),
+            );
+            let _: () = msg_send![text_label, setEditable: NO];
+            let _: () = msg_send![text_label, setSelectable: YES];
+            let _: () = msg_send![text_label, setBezeled: NO];
+            let _: () = msg_send![text_label, setBordered: NO];
+            let _: () = msg_send![text_label, setDrawsBackground: NO];
+            let text_font: id = msg_send![class!(NSFont), systemFontOfSize: 13.0f64];
+            let _: () = msg_send![text_label, setFont: text_font];
+            let text_color: id = msg_send![class!(NSColor), whiteColor];
+            let _: () = msg_send![text_label, setTextColor: text_color];
+            let _: () = msg_send![text_label, setLineBreakMode: 4usize];
+            let display = truncate(&snippet.replace('\n', " ⏎ "), 80);
+            let _: () = msg_send![text_label, setStringValue: ns_string(&display)];
+            let _: () = msg_send![list_view, addSubview: text_label];
+
+            for (slot, (symbol, action)) in [
+                ("doc.on.doc", sel!(copySnippet:)),
+                ("text.insert", sel!(injectSnippet:)),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let btn_x = text_width + 4.0 + slot as f64 * (btn_width + 4.0);
+                let btn = NSButton::initWithFrame_(
+                    NSButton::alloc(nil),
+                    NSRect::new(
+                        NSPoint::new(btn_x, row_y + 8.0),
+                        NSSize::new(btn_width, 24.0),
+                    ),
+                );
+                let _: () = msg_send![btn, setBezelStyle: 8usize];
+                let _: () = msg_send![btn, setBordered: YES];
+                let _: () = msg_send![btn, setButtonType: 0usize];
+                set_status_button_symbol(btn, symbol);
+                let _: () = msg_send![btn, setTag: i as isize];
+                let _: () = msg_send![btn, setTarget: delegate];
+                let _: () = msg_send![btn, setAction: action];
+                let _: () = msg_send![list_view, addSubview: btn];
+            }
+        }
+    }
+
+    if scroll_to_top {
+        let clip_view: id = msg_send![scroll_view, contentView];
+        if clip_view != nil {
+            let is_flipped: BOOL = msg_send![list_view, isFlipped];
+            let top_y = if is_flipped == YES {
+                0.0
+            } else {
+                (doc_height - visible_height).max(0.0)
+            };
+            let _: () = msg_send![clip_view, scrollToPoint: NSPoint::new(0.0, top_y)];
+            let _: () = msg_send![scroll_view, reflectScrolledClipView: clip_view];
+        }
+    }
+}
+
 fn truncate_middle(s: &str, max_chars: usize) -> String {
     let chars: Vec<char> = s.chars().collect();
     if chars.len() <= max_chars {
@@ -699,12 +976,170 @@ unsafe fn make_info_item(title: &str, target: id) -> id {
     item
 }
 
+/// 托盘“识别语言”切换项的 tag，与快捷设置的 tag 区间（100-399）错开。
+const LANGUAGE_TOGGLE_TAG: isize = 900;
+
+fn language_toggle_title(language: AsrLanguage) -> String {
+    format!("识别语言: {}（点按切换）", language.label())
+}
+
+/// 点按一次切到下一种语言并写回配置，下一段录音即生效。
+extern "C" fn toggle_language_action(_this: &Object, _cmd: Sel, sender: id) {
+    let next = load_app_config().language.next();
+    let pairs = [("language", next.token().to_string())];
+    if let Err(e) = write_config_values(&hotkey_config_path(), &pairs) {
+        eprintln!("[mofa-ime] 写入识别语言失败: {e}");
+        return;
+    }
+    unsafe {
+        let _: () = msg_send![sender, setTitle: ns_string(&language_toggle_title(next))];
+    }
+}
+
+/// 托盘“快捷设置”子菜单的一项；菜单项 tag = 类别 * 100 + 序号。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum QuickSetting {
+    OutputMode(OutputMode),
+    AsrModel(AsrModelChoice),
+    LlmModel(LlmModelChoice),
+}
+
+impl QuickSetting {
+    fn tag(self) -> isize {
+        fn index<T: PartialEq>(all: &[T], v: &T) -> isize {
+            all.iter().position(|x| x == v).unwrap_or(0) as isize
+        }
+        match self {
+            Self::OutputMode(m) => 100 + index(&OutputMode::ALL, &m),
+            Self::AsrModel(m) => 200 + index(&AsrModelChoice::ALL, &m),
+            Self::LlmModel(m) => 300 + index(&LlmModelChoice::ALL, &m),
+        }
+    }
+
+    fn from_tag(tag: isize) -> Option<Self> {
+        let idx = usize::try_from(tag % 100).ok()?;
+        match tag / 100 {
+            1 => OutputMode::ALL.get(idx).copied().map(Self::OutputMode),
+            2 => AsrModelChoice::ALL.get(idx).copied().map(Self::AsrModel),
+            3 => LlmModelChoice::ALL.get(idx).copied().map(Self::LlmModel),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::OutputMode(m) => m.label(),
+            Self::AsrModel(m) => m.label(),
+            Self::LlmModel(m) => m.label(),
+        }
+    }
+
+    /// 选中该项时写入配置的键值。
+    fn config_pair(self) -> (&'static str, String) {
+        match self {
+            Self::OutputMode(m) => ("output_mode", m.token().to_string()),
+            Self::AsrModel(m) => ("asr_model", m.token().to_string()),
+            Self::LlmModel(m) => ("llm_model", m.token().to_string()),
+        }
+    }
+
+    fn is_current(self, cfg: &AppConfig) -> bool {
+        match self {
+            Self::OutputMode(m) => cfg.output_mode == m,
+            Self::AsrModel(m) => cfg.asr_model == m,
+            Self::LlmModel(m) => cfg.llm_model == m,
+        }
+    }
+}
+
+unsafe fn make_quick_settings_item(title: &str, settings: &[QuickSetting], handler: id) -> id {
+    let submenu = NSMenu::new(nil).autorelease();
+    let _: () = msg_send![submenu, setDelegate: handler];
+    let cfg = load_app_config();
+    for setting in settings {
+        let item = NSMenuItem::alloc(nil)
+            .initWithTitle_action_keyEquivalent_(
+                ns_string(setting.label()),
+                sel!(applyQuickSetting:),
+                ns_string(""),
+            )
+            .autorelease();
+        NSMenuItem::setTarget_(item, handler);
+        let _: () = msg_send![item, setTag: setting.tag()];
+        let on: isize = if setting.is_current(&cfg) { 1 } else { 0 };
+        let _: () = msg_send![item, setState: on];
+        submenu.addItem_(item);
+    }
+    let parent = NSMenuItem::alloc(nil)
+        .initWithTitle_action_keyEquivalent_(ns_string(title), sel!(noopInfo:), ns_string(""))
+        .autorelease();
+    let _: () = msg_send![parent, setSubmenu: submenu];
+    parent
+}
+
+extern "C" fn apply_quick_setting_action(_this: &Object, _cmd: Sel, sender: id) {
+    let tag: isize = unsafe { msg_send![sender, tag] };
+    let Some(setting) = QuickSetting::from_tag(tag) else {
+        return;
+    };
+    let (key, value) = setting.config_pair();
+    if let Err(e) = write_config_values(&hotkey_config_path(), &[(key, value)]) {
+        eprintln!("[mofa-ime] 写入快捷设置失败: {e}");
+    }
+}
+
+/// 菜单展开前按当前配置刷新勾选与语言项标题（配置可能已被设置器修改）。
+extern "C" fn quick_settings_menu_needs_update(_this: &Object, _cmd: Sel, menu: id) {
+    let cfg = load_app_config();
+    unsafe {
+        let count: isize = msg_send![menu, numberOfItems];
+        for i in 0..count {
+            let item: id = msg_send![menu, itemAtIndex: i];
+            let tag: isize = msg_send![item, tag];
+            if tag == LANGUAGE_TOGGLE_TAG {
+                let title = language_toggle_title(cfg.language);
+                let _: () = msg_send![item, setTitle: ns_string(&title)];
+            } else if let Some(setting) = QuickSetting::from_tag(tag) {
+                let on: isize = if setting.is_current(&cfg) { 1 } else { 0 };
+                let _: () = msg_send![item, setState: on];
+            }
+        }
+    }
+}
+
 extern "C" fn open_model_manager_action(_this: &Object, _cmd: Sel, _sender: id) {
     if let Err(e) = spawn_model_manager() {
         eprintln!("[mofa-ime] 打开模型管理器失败: {e}");
     }
 }
 
+extern "C" fn open_log_viewer_action(_this: &Object, _cmd: Sel, _sender: id) {
+    open_log_viewer();
+}
+
+extern "C" fn repolish_last_action(_this: &Object, _cmd: Sel, _sender: id) {
+    if let Some(tx) = ORB_CLICK_TX.get() {
+        let _ = tx.send(OrbCommand::RepolishLast);
+    }
+}
+
+extern "C" fn export_history_action(_this: &Object, _cmd: Sel, sender: id) {
+    let tag: isize = unsafe { msg_send![sender, tag] };
+    let Some(format) = HistoryExportFormat::from_tag(tag) else {
+        return;
+    };
+    match export_history(format) {
+        Ok(path) => {
+            eprintln!("[mofa-ime] 历史已导出到 {:?}", path);
+            post_notification("历史已导出", &path.to_string_lossy());
+        }
+        Err(e) => {
+            eprintln!("[mofa-ime] 导出历史失败: {e:#}");
+            post_notification("导出历史失败", &format!("{e:#}"));
+        }
+    }
+}
+
 extern "C" fn noop_info_action(_this: &Object, _cmd: Sel, _sender: id) {}
 
 fn menu_handler_class() -> *const Class {
@@ -717,6 +1152,30 @@ fn menu_handler_class() -> *const Class {
             sel!(openModelManager:),
             open_model_manager_action as extern "C" fn(&Object, Sel, id),
         );
+        decl.add_method(
+            sel!(applyQuickSetting:),
+            apply_quick_setting_action as extern "C" fn(&Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(toggleLanguage:),
+            toggle_language_action as extern "C" fn(&Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(menuNeedsUpdate:),
+            quick_settings_menu_needs_update as extern "C" fn(&Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(openLogViewer:),
+            open_log_viewer_action as extern "C" fn(&Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(repolishLast:),
+            repolish_last_action as extern "C" fn(&Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(exportHistory:),
+            export_history_action as extern "C" fn(&Object, Sel, id),
+        );
         decl.add_method(
             sel!(noopInfo:),
             noop_info_action as extern "C" fn(&Object, Sel, id),
@@ -799,6 +1258,70 @@ unsafe fn install_status_item(app: id) -> Result<(StatusHandle, MonitorHandle, i
     menu.addItem_(hint_item);
     menu.addItem_(NSMenuItem::separatorItem(nil));
 
+    let output_modes: Vec<QuickSetting> = OutputMode::ALL
+        .iter()
+        .map(|m| QuickSetting::OutputMode(*m))
+        .collect();
+    let asr_models: Vec<QuickSetting> = AsrModelChoice::ALL
+        .iter()
+        .map(|m| QuickSetting::AsrModel(*m))
+        .collect();
+    let llm_models: Vec<QuickSetting> = LlmModelChoice::ALL
+        .iter()
+        .map(|m| QuickSetting::LlmModel(*m))
+        .collect();
+    menu.addItem_(make_quick_settings_item(
+        "发送模式",
+        &output_modes,
+        menu_handler,
+    ));
+    menu.addItem_(make_quick_settings_item(
+        "识别模型",
+        &asr_models,
+        menu_handler,
+    ));
+    menu.addItem_(make_quick_settings_item(
+        "润色模型",
+        &llm_models,
+        menu_handler,
+    ));
+
+    let language_item = NSMenuItem::alloc(nil)
+        .initWithTitle_action_keyEquivalent_(
+            ns_string(&language_toggle_title(load_app_config().language)),
+            sel!(toggleLanguage:),
+            ns_string(""),
+        )
+        .autorelease();
+    NSMenuItem::setTarget_(language_item, menu_handler);
+    let _: () = msg_send![language_item, setTag: LANGUAGE_TOGGLE_TAG];
+    menu.addItem_(language_item);
+    // 主菜单也用同一个 delegate，展开时刷新语言项标题。
+    let _: () = msg_send![menu, setDelegate: menu_handler];
+
+    let repolish_item = NSMenuItem::alloc(nil)
+        .initWithTitle_action_keyEquivalent_(
+            ns_string("重新润色上一段"),
+            sel!(repolishLast:),
+            ns_string(""),
+        )
+        .autorelease();
+    NSMenuItem::setTarget_(repolish_item, menu_handler);
+    menu.addItem_(repolish_item);
+    for (tag, title) in [(0isize, "导出历史 (CSV)"), (1, "导出历史 (JSON)")] {
+        let export_item = NSMenuItem::alloc(nil)
+            .initWithTitle_action_keyEquivalent_(
+                ns_string(title),
+                sel!(exportHistory:),
+                ns_string(""),
+            )
+            .autorelease();
+        NSMenuItem::setTarget_(export_item, menu_handler);
+        let _: () = msg_send![export_item, setTag: tag];
+        menu.addItem_(export_item);
+    }
+    menu.addItem_(NSMenuItem::separatorItem(nil));
+
     let settings_item = NSMenuItem::alloc(nil)
         .initWithTitle_action_keyEquivalent_(
             ns_string("MoFA IME 设置..."),
@@ -809,6 +1332,16 @@ unsafe fn install_status_item(app: id) -> Result<(StatusHandle, MonitorHandle, i
     NSMenuItem::setTarget_(settings_item, menu_handler);
     menu.addItem_(settings_item);
 
+    let log_item = NSMenuItem::alloc(nil)
+        .initWithTitle_action_keyEquivalent_(
+            ns_string("查看日志..."),
+            sel!(openLogViewer:),
+            ns_string("l"),
+        )
+        .autorelease();
+    NSMenuItem::setTarget_(log_item, menu_handler);
+    menu.addItem_(log_item);
+
     menu.addItem_(NSMenuItem::separatorItem(nil));
 
     let quit_item = NSMenuItem::alloc(nil)
@@ -833,3 +1366,72 @@ unsafe fn install_status_item(app: id) -> Result<(StatusHandle, MonitorHandle, i
         menu_handler,
     ))
 }
+
+#[cfg(test)]
+mod tray_tests {
+    use super::*;
+
+    fn all_quick_settings() -> Vec<QuickSetting> {
+        let modes = OutputMode::ALL.iter().map(|m| QuickSetting::OutputMode(*m));
+        let asr = AsrModelChoice::ALL
+            .iter()
+            .map(|m| QuickSetting::AsrModel(*m));
+        let llm = LlmModelChoice::ALL
+            .iter()
+            .map(|m| QuickSetting::LlmModel(*m));
+        modes.chain(asr).chain(llm).collect()
+    }
+
+    #[test]
+    fn quick_setting_tags_round_trip() {
+        for setting in all_quick_settings() {
+            assert_eq!(QuickSetting::from_tag(setting.tag()), Some(setting));
+        }
+        assert_eq!(QuickSetting::from_tag(LANGUAGE_TOGGLE_TAG), None);
+    }
+
+    #[test]
+    fn each_quick_setting_writes_its_own_choice() {
+        let original = "version=2\n# 我的设置\nhotkey=fn\n";
+        for setting in all_quick_settings() {
+            let (key, value) = setting.config_pair();
+            let written = upsert_config_lines(original, &[(key, value)]);
+            assert!(written.contains("# 我的设置\nhotkey=fn\n"), "{written}");
+            assert!(
+                setting.is_current(&parse_app_config(&written)),
+                "{setting:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn language_toggle_cycles_through_the_written_config() {
+        let mut content = upsert_config_lines("", &[("language", "zh".to_string())]);
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            let next = parse_app_config(&content).language.next();
+            content = upsert_config_lines(&content, &[("language", next.token().to_string())]);
+            seen.push(parse_app_config(&content).language);
+        }
+        assert_eq!(seen, [AsrLanguage::En, AsrLanguage::Auto, AsrLanguage::Zh]);
+        assert_eq!(content.matches("language=").count(), 1);
+        assert_eq!(
+            language_toggle_title(AsrLanguage::En),
+            "识别语言: 英文（点按切换）"
+        );
+    }
+
+    #[test]
+    fn tray_states_serialize_to_stable_tokens() {
+        let tokens = [
+            (TrayState::Idle, "idle"),
+            (TrayState::Recording, "recording"),
+            (TrayState::Processing, "processing"),
+            (TrayState::Injected, "injected"),
+            (TrayState::Error, "error"),
+        ];
+        for (state, token) in tokens {
+            assert_eq!(state.token(), token);
+        }
+    }
+}
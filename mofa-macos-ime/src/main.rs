@@ -25,10 +25,10 @@ use std::ffi::{c_void, CStr, CString};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU64, AtomicUsize, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex, OnceLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 
@@ -39,30 +39,80 @@ fn main() {
 
 #[cfg(target_os = "macos")]
 fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(cmd) = parse_cli_command(&args)? {
+        return run_cli_command(cmd);
+    }
     run_app()
 }
 
 #[cfg(target_os = "macos")]
 fn run_app() -> Result<()> {
     let _pool = unsafe { NSAutoreleasePool::new(nil) };
+    init_file_logging();
 
     let app = unsafe { NSApplication::sharedApplication(nil) };
     unsafe {
         app.setActivationPolicy_(NSApplicationActivationPolicyAccessory);
     }
+    let _app_delegate = unsafe { install_app_delegate(app) };
 
     let app_cfg = load_app_config();
     let hotkey_spec = app_cfg.hotkey;
     let hotkey_store = Arc::new(std::sync::atomic::AtomicUsize::new(hotkey_spec.pack()));
     let _ = HOTKEY_STORE.set(Arc::clone(&hotkey_store));
+    FN_HOLD_THRESHOLD_MS.store(app_cfg.fn_hold_ms, Ordering::SeqCst);
+    TYPE_SPEED_CPS.store(app_cfg.type_speed_cps, Ordering::SeqCst);
+    BYPASS_TEXT_REPLACEMENT.store(
+        app_cfg.disable_text_replacement_during_inject,
+        Ordering::SeqCst,
+    );
+    AX_RETRIES.store(app_cfg.ax_retries, Ordering::SeqCst);
+    INJECT_FOCUS_SETTLE_MS.store(app_cfg.inject_focus_settle_ms, Ordering::SeqCst);
+    CLIPBOARD_IMAGE_POLICY.store(app_cfg.clipboard_image.index(), Ordering::SeqCst);
+    CLIPBOARD_IMAGE_MAX_BYTES.store(
+        app_cfg.clipboard_image_max_mb * 1024 * 1024,
+        Ordering::SeqCst,
+    );
+    PRIVACY_MODE.store(app_cfg.privacy_mode, Ordering::SeqCst);
+    CAPTURE_FORMAT.store(app_cfg.capture_format.index(), Ordering::SeqCst);
+    HOTKEY_MODE.store(app_cfg.hotkey_mode.index(), Ordering::SeqCst);
+    OVERLAY_FIXED_ORIGIN.store(
+        pack_overlay_origin(app_cfg.overlay_fixed_origin()),
+        Ordering::SeqCst,
+    );
+    store_cycle_hotkeys(&app_cfg);
 
     let (status_handle, monitor_handle, _status_item, _menu, _menu_handler) =
         unsafe { install_status_item(app)? };
-    let overlay_handle = unsafe { install_overlay(app_cfg.show_floating_orb)? };
+    write_state_file(TrayState::Idle);
+    let overlay_handle = unsafe {
+        install_overlay(
+            app_cfg.show_floating_orb,
+            app_cfg.show_overlay,
+            app_cfg.overlay_style,
+        )?
+    };
+    clear_history_audio_dir();
     spawn_clipboard_watcher(overlay_handle);
+    if app_cfg.overlay_interactive {
+        spawn_overlay_hover_watcher(overlay_handle);
+    }
+    if app_cfg.show_floating_orb && app_cfg.orb_auto_hide_ms > 0 {
+        spawn_orb_auto_hide_watcher(app_cfg.orb_auto_hide_ms);
+    }
 
     let (hotkey_tx, hotkey_rx) = mpsc::channel::<HotkeySignal>();
-    spawn_pipeline_worker(hotkey_rx, status_handle, monitor_handle, overlay_handle);
+    spawn_pipeline_worker::<ActiveRecorder>(
+        hotkey_rx,
+        hotkey_tx.clone(),
+        status_handle,
+        monitor_handle,
+        overlay_handle,
+    );
+    if let Some(pipe_path) = app_cfg.trigger_pipe.clone() {
+        spawn_trigger_pipe_reader(pipe_path, hotkey_tx.clone());
+    }
     spawn_hotkey_config_watcher(Arc::clone(&hotkey_store));
     spawn_orb_config_watcher(overlay_handle);
 
@@ -79,6 +129,7 @@ fn run_app() -> Result<()> {
 }
 
 include!("ime/config.rs");
+include!("ime/config_writer.rs");
 include!("ime/tray.rs");
 include!("ime/overlay.rs");
 include!("ime/hotkey_tap.rs");
@@ -86,3 +137,12 @@ include!("ime/pipeline.rs");
 include!("ime/text_model.rs");
 include!("ime/audio.rs");
 include!("ime/inject.rs");
+include!("ime/confirm.rs");
+include!("ime/streaming.rs");
+include!("ime/caption.rs");
+include!("ime/cli.rs");
+include!("ime/shutdown.rs");
+include!("ime/log_viewer.rs");
+include!("ime/diagnostics.rs");
+include!("ime/notify.rs");
+include!("ime/history_export.rs");
@@ -60,6 +60,32 @@ void llm_free(LlmContext* llm) {
     delete llm;
 }
 
+int llm_tokenize(LlmContext* llm, const char* text, int32_t* tokens, int n_max) {
+    if (!llm || !llm->model || !text) {
+        return 0;
+    }
+    int32_t len = (int32_t)strlen(text);
+    if (len == 0) {
+        return 0;
+    }
+    // add_special=false: count only the text itself, parse_special=true like generation
+    return llama_tokenize(llm->model, text, len, tokens, n_max, false, true);
+}
+
+char* llm_detokenize(LlmContext* llm, const int32_t* tokens, int n_tokens) {
+    std::string out;
+    if (llm && llm->model && tokens) {
+        char piece[256];
+        for (int i = 0; i < n_tokens; i++) {
+            int32_t n = llama_token_to_piece(llm->model, tokens[i], piece, sizeof(piece), 0, true);
+            if (n > 0) {
+                out.append(piece, n);
+            }
+        }
+    }
+    return strdup(out.c_str());
+}
+
 void llm_kv_clear(LlmContext* llm) {
     if (llm && llm->ctx) {
         llama_kv_cache_clear(llm->ctx);
@@ -73,6 +99,13 @@ int llm_kv_count(LlmContext* llm) {
     return 0;
 }
 
+int llm_n_ctx(LlmContext* llm) {
+    if (llm && llm->ctx) {
+        return (int)llama_n_ctx(llm->ctx);
+    }
+    return 0;
+}
+
 void llm_chat_clear(LlmContext* llm) {
     llm->chat_history.clear();
     llm_kv_clear(llm);
@@ -32,6 +32,14 @@ void llm_generate_stream(LlmContext* ctx, const char* prompt, int max_tokens, fl
 // Free string returned by llm_generate
 void llm_free_string(char* str);
 
+// ===== Tokenizer API =====
+
+// Tokenize text without BOS/EOS; returns token count, or -required_size if n_max is too small
+int llm_tokenize(LlmContext* ctx, const char* text, int32_t* tokens, int n_max);
+
+// Convert tokens back to text (returns allocated string, free with llm_free_string)
+char* llm_detokenize(LlmContext* ctx, const int32_t* tokens, int n_tokens);
+
 // ===== KV Cache API =====
 
 // Clear KV cache (start new conversation)
@@ -40,6 +48,9 @@ void llm_kv_clear(LlmContext* ctx);
 // Get number of tokens in cache
 int llm_kv_count(LlmContext* ctx);
 
+// Get context window size in tokens (0 if unavailable)
+int llm_n_ctx(LlmContext* ctx);
+
 // ===== Multi-turn Conversation API =====
 
 // Add user message to history (does not generate)
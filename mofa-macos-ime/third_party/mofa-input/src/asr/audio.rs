@@ -3,6 +3,8 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use std::sync::{Arc, Mutex};
 
+use crate::error::{MofaError, Result};
+
 pub struct AudioRecorder {
     samples: Arc<Mutex<Vec<f32>>>,
     stream: Option<Box<dyn StreamTrait>>,
@@ -19,13 +21,15 @@ impl AudioRecorder {
     }
 
     /// Start recording audio
-    pub fn start_recording(&mut self) -> anyhow::Result<()> {
+    pub fn start_recording(&mut self) -> Result<()> {
         let host = cpal::default_host();
         let device = host
             .default_input_device()
-            .ok_or_else(|| anyhow::anyhow!("No input device available"))?;
+            .ok_or_else(|| MofaError::AudioDevice("no input device available".into()))?;
 
-        let config = device.default_input_config()?;
+        let config = device
+            .default_input_config()
+            .map_err(MofaError::audio_device)?;
         let sample_rate = config.sample_rate().0;
         let channels = config.channels();
 
@@ -38,30 +42,35 @@ impl AudioRecorder {
         let err_fn = |err| eprintln!("Audio stream error: {}", err);
 
         let stream = if config.sample_format() == cpal::SampleFormat::F32 {
-            device.build_input_stream(
-                &config.into(),
-                move |data: &[f32], _| {
-                    if *is_recording.lock().unwrap() {
-                        let mut samples = samples.lock().unwrap();
-                        // Convert to mono if stereo
-                        if channels == 2 {
-                            for chunk in data.chunks(2) {
-                                let mono = (chunk[0] + chunk[1]) / 2.0;
-                                samples.push(mono);
+            device
+                .build_input_stream(
+                    &config.into(),
+                    move |data: &[f32], _| {
+                        if *is_recording.lock().unwrap() {
+                            let mut samples = samples.lock().unwrap();
+                            // Convert to mono if stereo
+                            if channels == 2 {
+                                for chunk in data.chunks(2) {
+                                    let mono = (chunk[0] + chunk[1]) / 2.0;
+                                    samples.push(mono);
+                                }
+                            } else {
+                                samples.extend_from_slice(data);
                             }
-                        } else {
-                            samples.extend_from_slice(data);
                         }
-                    }
-                },
-                err_fn,
-                None,
-            )?
+                    },
+                    err_fn,
+                    None,
+                )
+                .map_err(MofaError::audio_device)?
         } else {
-            return Err(anyhow::anyhow!("Unsupported sample format"));
+            return Err(MofaError::AudioDevice(format!(
+                "unsupported sample format: {:?}",
+                config.sample_format()
+            )));
         };
 
-        stream.play()?;
+        stream.play().map_err(MofaError::audio_device)?;
         self.stream = Some(Box::new(stream));
 
         // Resample to 16kHz if needed
@@ -74,7 +83,7 @@ impl AudioRecorder {
     }
 
     /// Stop recording and return audio samples (16kHz, mono, f32)
-    pub fn stop_recording(&mut self) -> anyhow::Result<Vec<f32>> {
+    pub fn stop_recording(&mut self) -> Result<Vec<f32>> {
         *self.is_recording.lock().unwrap() = false;
         self.stream = None;
 
@@ -1,36 +1,83 @@
 //! Whisper engine implementation
 
 use std::path::Path;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use std::sync::Mutex;
+
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+use crate::error::{MofaError, Result};
 
 pub struct WhisperEngine {
     context: WhisperContext,
+    /// Forced language code ("zh", "en"); None auto-detects
+    language: Option<String>,
+    /// Language code Whisper decoded the last transcription in
+    last_language: Mutex<Option<String>>,
 }
 
 impl WhisperEngine {
-    pub fn new(model_path: &Path) -> anyhow::Result<Self> {
+    pub fn new(model_path: &Path) -> Result<Self> {
         if !model_path.exists() {
-            return Err(anyhow::anyhow!("Model file not found: {:?}", model_path));
+            return Err(MofaError::ModelNotFound(model_path.to_path_buf()));
         }
 
         let ctx_params = WhisperContextParameters::default();
-        let context = WhisperContext::new_with_params(
-            model_path.to_str().unwrap(),
-            ctx_params,
-        )
-        .map_err(|e| anyhow::anyhow!("Failed to load model: {:?}", e))?;
+        let path = model_path
+            .to_str()
+            .ok_or_else(|| MofaError::ModelLoad(format!("non-UTF-8 path: {:?}", model_path)))?;
+        let context =
+            WhisperContext::new_with_params(path, ctx_params).map_err(MofaError::model_load)?;
+
+        Ok(Self {
+            context,
+            language: None,
+            last_language: Mutex::new(None),
+        })
+    }
+
+    /// Force the decoding language (e.g. "zh", "en"); None restores auto-detect
+    pub fn set_language(&mut self, language: Option<&str>) {
+        self.language = language.map(str::to_string);
+    }
 
-        Ok(Self { context })
+    /// Language of the last transcription: the detected one under auto-detect,
+    /// otherwise the forced one
+    pub fn last_language(&self) -> Option<String> {
+        self.last_language.lock().ok()?.clone()
+    }
+
+    fn record_language(&self, state: &WhisperState) {
+        let code = whisper_rs::get_lang_str(state.full_lang_id_from_state()).map(str::to_string);
+        if let Ok(mut last) = self.last_language.lock() {
+            *last = code;
+        }
+    }
+
+    /// Header fields of the loaded model
+    pub fn model_info(&self) -> super::AsrModelInfo {
+        super::AsrModelInfo {
+            model_type: self
+                .context
+                .model_type_readable()
+                .unwrap_or_else(|_| "unknown".to_string()),
+            n_vocab: self.context.model_n_vocab(),
+            n_mels: self.context.model_n_mels(),
+            n_audio_layer: self.context.model_n_audio_layer(),
+            multilingual: self.context.is_multilingual(),
+        }
     }
 
     /// Transcribe audio samples (16kHz, mono, f32)
-    pub fn transcribe(&self, samples: &[f32]) -> anyhow::Result<String> {
+    pub fn transcribe(&self, samples: &[f32]) -> Result<String> {
         let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
         params.set_print_special(false);
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
-        params.set_language(None); // Auto-detect language (supports Chinese-English mixed)
+        // None auto-detects language (supports Chinese-English mixed)
+        params.set_language(self.language.as_deref());
         params.set_translate(false);
         // Raw mode: preserve fillers and repetitions
         params.set_suppress_blank(false);
@@ -38,8 +85,9 @@ impl WhisperEngine {
         params.set_temperature(0.0);
         params.set_max_len(0);
 
-        let mut state = self.context.create_state()?;
-        state.full(params, samples)?;
+        let mut state = self.context.create_state().map_err(MofaError::transcribe)?;
+        state.full(params, samples).map_err(MofaError::transcribe)?;
+        self.record_language(&state);
 
         let num_segments = state.full_n_segments();
         let mut text = String::new();
@@ -54,42 +102,84 @@ impl WhisperEngine {
         Ok(text.trim().to_string())
     }
 
-    /// Transcribe with progress callback
-    pub fn transcribe_with_progress<F>(
-        &self,
-        samples: &[f32],
-        callback: F,
-    ) -> anyhow::Result<String>
-    where
-        F: Fn(&str) + Send + 'static,
-    {
+    /// Transcribe into timestamped segments (16kHz, mono, f32)
+    pub fn transcribe_segments(&self, samples: &[f32]) -> Result<Vec<super::Segment>> {
         let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
         params.set_print_special(false);
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
-        params.set_language(None); // Auto-detect language (supports Chinese-English mixed)
+        params.set_language(self.language.as_deref());
         params.set_translate(false);
-        // Raw mode: preserve fillers and repetitions
-        params.set_suppress_blank(false);
-        params.set_suppress_nst(false);
         params.set_temperature(0.0);
         params.set_max_len(0);
 
-        let mut state = self.context.create_state()?;
-        state.full(params, samples)?;
+        let mut state = self.context.create_state().map_err(MofaError::transcribe)?;
+        state.full(params, samples).map_err(MofaError::transcribe)?;
+        self.record_language(&state);
 
         let num_segments = state.full_n_segments();
-        let mut text = String::new();
+        let mut segments = Vec::new();
         for i in 0..num_segments {
             if let Some(segment) = state.get_segment(i) {
-                if let Ok(txt) = segment.to_str() {
-                    text.push_str(txt);
-                    callback(txt);
+                let Ok(txt) = segment.to_str() else {
+                    continue;
+                };
+                let txt = txt.trim();
+                if txt.is_empty() {
+                    continue;
                 }
+                // whisper timestamps are in units of 10ms
+                segments.push(super::Segment {
+                    start_ms: segment.start_timestamp().max(0) as u64 * 10,
+                    end_ms: segment.end_timestamp().max(0) as u64 * 10,
+                    text: txt.to_string(),
+                });
             }
         }
 
-        Ok(text.trim().to_string())
+        Ok(segments)
+    }
+
+    /// Transcribe with a per-segment callback carrying index and timing
+    pub fn transcribe_with_segments<F>(&self, samples: &[f32], callback: F) -> Result<String>
+    where
+        F: Fn(&super::SegmentUpdate) + Send + 'static,
+    {
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        // None auto-detects language (supports Chinese-English mixed)
+        params.set_language(self.language.as_deref());
+        params.set_translate(false);
+        // Raw mode: preserve fillers and repetitions
+        params.set_suppress_blank(false);
+        params.set_suppress_nst(false);
+        params.set_temperature(0.0);
+        params.set_max_len(0);
+
+        let mut state = self.context.create_state().map_err(MofaError::transcribe)?;
+        state.full(params, samples).map_err(MofaError::transcribe)?;
+        self.record_language(&state);
+
+        let num_segments = state.full_n_segments();
+        let segments = (0..num_segments)
+            .filter_map(|i| state.get_segment(i))
+            .filter_map(|segment| {
+                let txt = segment.to_str().ok()?.to_string();
+                Some((txt, segment.start_timestamp(), segment.end_timestamp()))
+            });
+
+        Ok(super::forward_segments(segments, callback))
+    }
+
+    /// Transcribe with progress callback (segment text only)
+    pub fn transcribe_with_progress<F>(&self, samples: &[f32], callback: F) -> Result<String>
+    where
+        F: Fn(&str) + Send + 'static,
+    {
+        self.transcribe_with_segments(samples, move |update| callback(&update.text))
     }
 }
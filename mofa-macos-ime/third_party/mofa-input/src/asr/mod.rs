@@ -4,13 +4,55 @@
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+use crate::error::{MofaError, Result};
+
 pub mod audio;
 pub mod engine;
 pub mod funasr;
+pub mod subtitle;
 
 pub use engine::WhisperEngine;
 pub use funasr::{FunAsrEngine, FunAsrModelSize};
 pub use funasr::engine::FunAsrSession;
+pub use subtitle::{format_timecode, segments_to_srt, segments_to_vtt};
+
+/// A transcribed segment with timestamps
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// A segment delivered to a progress callback as soon as it is decoded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SegmentUpdate {
+    /// 0-based position among the segments delivered for this call
+    pub index: usize,
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Forward raw `(text, start, end)` segments (whisper timestamps, 10ms units) to `callback`
+/// as numbered updates; returns the concatenated, trimmed text
+pub(crate) fn forward_segments<I, F>(segments: I, callback: F) -> String
+where
+    I: IntoIterator<Item = (String, i64, i64)>,
+    F: Fn(&SegmentUpdate),
+{
+    let mut text = String::new();
+    for (index, (txt, start, end)) in segments.into_iter().enumerate() {
+        text.push_str(&txt);
+        callback(&SegmentUpdate {
+            index,
+            text: txt,
+            start_ms: start.max(0) as u64 * 10,
+            end_ms: end.max(0) as u64 * 10,
+        });
+    }
+    text.trim().to_string()
+}
 
 /// Whisper model sizes
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -81,6 +123,35 @@ impl WhisperModelSize {
     }
 }
 
+/// Loaded Whisper model header info
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsrModelInfo {
+    /// "tiny" / "base" / "small" / "medium" / "large"
+    pub model_type: String,
+    pub n_vocab: i32,
+    pub n_mels: i32,
+    pub n_audio_layer: i32,
+    /// `.en` models are English-only; language detection needs a multilingual model
+    pub multilingual: bool,
+}
+
+impl AsrModelInfo {
+    /// e.g. "Whisper Small (多语言)"
+    pub fn label(&self) -> String {
+        let mut chars = self.model_type.chars();
+        let name = match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+            None => "Unknown".to_string(),
+        };
+        let lang = if self.multilingual {
+            "多语言"
+        } else {
+            "仅英文"
+        };
+        format!("Whisper {name} ({lang})")
+    }
+}
+
 /// Thread-safe ASR session
 #[derive(Clone)]
 pub struct AsrSession {
@@ -88,26 +159,62 @@ pub struct AsrSession {
 }
 
 impl AsrSession {
-    pub fn new(model_path: &Path) -> anyhow::Result<Self> {
+    pub fn new(model_path: &Path) -> Result<Self> {
+        if !model_path.exists() {
+            return Err(MofaError::ModelNotFound(model_path.to_path_buf()));
+        }
         let engine = WhisperEngine::new(model_path)?;
         Ok(Self {
             engine: Arc::new(Mutex::new(engine)),
         })
     }
 
+    /// Info about the loaded model
+    pub fn model_info(&self) -> AsrModelInfo {
+        let engine = self.engine.lock().unwrap();
+        engine.model_info()
+    }
+
+    /// Force the decoding language (e.g. "zh", "en"); None restores auto-detect
+    pub fn set_language(&self, language: Option<&str>) {
+        let mut engine = self.engine.lock().unwrap();
+        engine.set_language(language);
+    }
+
+    /// Language code ("zh", "en", ...) of the last transcription on this
+    /// session; under auto-detect this is what Whisper decided on
+    pub fn last_language(&self) -> Option<String> {
+        let engine = self.engine.lock().unwrap();
+        engine.last_language()
+    }
+
     /// Transcribe audio samples (16kHz, mono, f32)
-    pub fn transcribe(&self, samples: &[f32]) -> anyhow::Result<String> {
+    pub fn transcribe(&self, samples: &[f32]) -> Result<String> {
         let engine = self.engine.lock().unwrap();
         engine.transcribe(samples)
     }
 
-    /// Transcribe with progress callback
-    pub fn transcribe_with_progress<F>(&self, samples: &[f32], callback: F) -> anyhow::Result<String>
+    /// Transcribe into timestamped segments
+    pub fn transcribe_segments(&self, samples: &[f32]) -> Result<Vec<Segment>> {
+        let engine = self.engine.lock().unwrap();
+        engine.transcribe_segments(samples)
+    }
+
+    /// Transcribe with a per-segment callback carrying index and timing
+    pub fn transcribe_with_segments<F>(&self, samples: &[f32], callback: F) -> Result<String>
     where
-        F: Fn(&str) + Send + 'static,
+        F: Fn(&SegmentUpdate) + Send + 'static,
     {
         let engine = self.engine.lock().unwrap();
-        engine.transcribe_with_progress(samples, callback)
+        engine.transcribe_with_segments(samples, callback)
+    }
+
+    /// Transcribe with progress callback (segment text only)
+    pub fn transcribe_with_progress<F>(&self, samples: &[f32], callback: F) -> Result<String>
+    where
+        F: Fn(&str) + Send + 'static,
+    {
+        self.transcribe_with_segments(samples, move |update| callback(&update.text))
     }
 }
 
@@ -116,3 +223,67 @@ pub fn is_model_available(model: WhisperModelSize) -> bool {
     let path = model.path();
     path.exists() && path.metadata().map(|m| m.len() > 1000).unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(model_type: &str, multilingual: bool) -> AsrModelInfo {
+        AsrModelInfo {
+            model_type: model_type.to_string(),
+            n_vocab: if multilingual { 51865 } else { 51864 },
+            n_mels: 80,
+            n_audio_layer: 4,
+            multilingual,
+        }
+    }
+
+    #[test]
+    fn label_reflects_model_type_and_language_support() {
+        assert_eq!(info("small", true).label(), "Whisper Small (多语言)");
+        assert_eq!(info("tiny", false).label(), "Whisper Tiny (仅英文)");
+        assert_eq!(info("", true).label(), "Whisper Unknown (多语言)");
+    }
+
+    /// Needs `~/.mofa/models/ggml-tiny.bin`; skipped when it is not installed.
+    #[test]
+    fn loaded_tiny_model_reports_header_fields() {
+        if !is_model_available(WhisperModelSize::Tiny) {
+            return;
+        }
+        let session = AsrSession::new(&WhisperModelSize::Tiny.path()).unwrap();
+        let info = session.model_info();
+        assert_eq!(info.model_type, "tiny");
+        assert_eq!(info.n_mels, 80);
+        assert!(info.multilingual);
+    }
+
+    #[test]
+    fn forward_segments_numbers_updates_and_converts_timestamps() {
+        let seen = Mutex::new(Vec::new());
+        let stream = vec![
+            (" Hello".to_string(), 0, 150),
+            (" world.".to_string(), 150, 320),
+            (" Bye".to_string(), -1, 400),
+        ];
+        let text = forward_segments(stream, |update| seen.lock().unwrap().push(update.clone()));
+
+        assert_eq!(text, "Hello world. Bye");
+        let seen = seen.into_inner().unwrap();
+        assert_eq!(
+            seen.iter().map(|u| u.index).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            seen[1],
+            SegmentUpdate {
+                index: 1,
+                text: " world.".to_string(),
+                start_ms: 1500,
+                end_ms: 3200,
+            }
+        );
+        // Negative timestamps clamp to zero
+        assert_eq!((seen[2].start_ms, seen[2].end_ms), (0, 4000));
+    }
+}
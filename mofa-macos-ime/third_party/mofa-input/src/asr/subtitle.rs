@@ -0,0 +1,159 @@
+//! SRT / WebVTT export for timestamped segments
+
+use super::Segment;
+
+/// Max display width of one caption line (CJK counts as 2)
+const CAPTION_LINE_WIDTH: usize = 42;
+
+/// `HH:MM:SS,mmm` (SRT) or `HH:MM:SS.mmm` (VTT)
+pub fn format_timecode(ms: u64, decimal_sep: char) -> String {
+    let hours = ms / 3_600_000;
+    let minutes = (ms / 60_000) % 60;
+    let seconds = (ms / 1000) % 60;
+    let millis = ms % 1000;
+    format!("{hours:02}:{minutes:02}:{seconds:02}{decimal_sep}{millis:03}")
+}
+
+fn char_width(ch: char) -> usize {
+    if ch.is_ascii() {
+        1
+    } else {
+        2
+    }
+}
+
+/// Split caption text into lines no wider than `CAPTION_LINE_WIDTH`.
+/// Existing line breaks are kept; blank lines are dropped since they end a cue.
+fn caption_lines(text: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            continue;
+        }
+        let mut line = String::new();
+        let mut width = 0usize;
+        for word in raw.split_inclusive(' ') {
+            let word_width: usize = word.chars().map(char_width).sum();
+            if width > 0 && width + word_width > CAPTION_LINE_WIDTH {
+                lines.push(line.trim_end().to_string());
+                line.clear();
+                width = 0;
+            }
+            // A single long run (e.g. CJK without spaces) is broken by width.
+            for ch in word.chars() {
+                let w = char_width(ch);
+                if width > 0 && width + w > CAPTION_LINE_WIDTH {
+                    lines.push(line.trim_end().to_string());
+                    line.clear();
+                    width = 0;
+                }
+                line.push(ch);
+                width += w;
+            }
+        }
+        let line = line.trim_end();
+        if !line.is_empty() {
+            lines.push(line.to_string());
+        }
+    }
+    lines
+}
+
+fn cue_body(segment: &Segment, decimal_sep: char) -> Option<String> {
+    let lines = caption_lines(&segment.text);
+    if lines.is_empty() {
+        return None;
+    }
+    let end_ms = segment.end_ms.max(segment.start_ms);
+    Some(format!(
+        "{} --> {}\n{}\n",
+        format_timecode(segment.start_ms, decimal_sep),
+        format_timecode(end_ms, decimal_sep),
+        lines.join("\n")
+    ))
+}
+
+/// Render segments as SubRip (.srt)
+pub fn segments_to_srt(segments: &[Segment]) -> String {
+    let mut out = String::new();
+    let mut index = 0usize;
+    for segment in segments {
+        if let Some(body) = cue_body(segment, ',') {
+            index += 1;
+            out.push_str(&format!("{index}\n{body}\n"));
+        }
+    }
+    out
+}
+
+/// Render segments as WebVTT (.vtt)
+pub fn segments_to_vtt(segments: &[Segment]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for segment in segments {
+        if let Some(body) = cue_body(segment, '.') {
+            out.push_str(&body);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seg(start_ms: u64, end_ms: u64, text: &str) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn timecode_uses_format_separator() {
+        assert_eq!(format_timecode(0, ','), "00:00:00,000");
+        assert_eq!(format_timecode(3_723_004, ','), "01:02:03,004");
+        assert_eq!(format_timecode(59_999, '.'), "00:00:59.999");
+    }
+
+    #[test]
+    fn srt_numbers_cues_and_skips_blank_segments() {
+        let segments = [
+            seg(0, 1500, "Hello"),
+            seg(1500, 1600, "  "),
+            seg(2000, 3250, "你好"),
+        ];
+        assert_eq!(
+            segments_to_srt(&segments),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello\n\n\
+             2\n00:00:02,000 --> 00:00:03,250\n你好\n\n"
+        );
+    }
+
+    #[test]
+    fn vtt_has_header_and_clamps_end_before_start() {
+        assert_eq!(
+            segments_to_vtt(&[seg(5000, 4000, "late")]),
+            "WEBVTT\n\n00:00:05.000 --> 00:00:05.000\nlate\n\n"
+        );
+    }
+
+    #[test]
+    fn long_captions_wrap_into_lines() {
+        let text = "the quick brown fox jumps over the lazy dog and keeps running";
+        let lines = caption_lines(text);
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.len() <= CAPTION_LINE_WIDTH));
+        assert_eq!(lines.join(" "), text);
+
+        let cjk = "一".repeat(30);
+        let lines = caption_lines(&cjk);
+        assert_eq!(lines, ["一".repeat(21), "一".repeat(9)]);
+        assert_eq!(
+            caption_lines("line one\n\nline two"),
+            ["line one", "line two"]
+        );
+    }
+}
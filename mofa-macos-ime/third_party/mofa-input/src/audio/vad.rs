@@ -0,0 +1,203 @@
+//! Lightweight energy + zero-crossing-rate voice activity detection
+//!
+//! Frame energies are compared against the quietest frames of the same
+//! buffer, so a steady hum or fan (flat energy) is not mistaken for speech,
+//! while quiet voiced speech still passes on its zero-crossing rate.
+
+/// Frame length in milliseconds
+const FRAME_MS: usize = 20;
+/// Below this RMS a frame is treated as silence regardless of anything else
+const ABS_MIN_RMS: f32 = 0.0015;
+/// Fraction of lowest-energy frames used as the noise floor
+const FLOOR_PERCENTILE: f32 = 0.1;
+/// Frames this far above the floor count as speech outright
+const STRONG_RATIO: f32 = 3.0;
+/// Frames this far above the floor count as speech if their ZCR looks voiced
+const WEAK_RATIO: f32 = 1.5;
+/// Zero crossings per sample typical of voiced speech
+const VOICED_ZCR: (f32, f32) = (0.01, 0.25);
+/// Minimum total speech needed for `detect_speech`
+const MIN_SPEECH_MS: usize = 100;
+
+fn frame_stats(frame: &[f32]) -> (f32, f32) {
+    if frame.is_empty() {
+        return (0.0, 0.0);
+    }
+    let energy = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+    let crossings = frame
+        .windows(2)
+        .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+        .count();
+    (energy.sqrt(), crossings as f32 / frame.len() as f32)
+}
+
+/// Per-frame speech flags and the frame length in samples
+fn speech_mask(samples: &[f32], sample_rate: u32) -> (Vec<bool>, usize) {
+    let frame_len = (sample_rate as usize * FRAME_MS / 1000).max(1);
+    let stats: Vec<(f32, f32)> = samples.chunks(frame_len).map(frame_stats).collect();
+    if stats.is_empty() {
+        return (Vec::new(), frame_len);
+    }
+
+    let mut energies: Vec<f32> = stats.iter().map(|(rms, _)| *rms).collect();
+    energies.sort_by(|a, b| a.total_cmp(b));
+    let floor_idx = ((energies.len() as f32 * FLOOR_PERCENTILE) as usize).min(energies.len() - 1);
+    let floor = energies[floor_idx].max(ABS_MIN_RMS);
+
+    let mask = stats
+        .iter()
+        .map(|(rms, zcr)| {
+            *rms >= ABS_MIN_RMS
+                && (*rms >= floor * STRONG_RATIO
+                    || (*rms >= floor * WEAK_RATIO && *zcr >= VOICED_ZCR.0 && *zcr <= VOICED_ZCR.1))
+        })
+        .collect();
+    (mask, frame_len)
+}
+
+fn speech_frames(samples: &[f32], sample_rate: u32) -> (usize, usize) {
+    let (mask, _) = speech_mask(samples, sample_rate);
+    (mask.iter().filter(|speech| **speech).count(), mask.len())
+}
+
+/// Fraction of 20ms frames classified as speech (0.0..=1.0)
+pub fn speech_ratio(samples: &[f32], sample_rate: u32) -> f32 {
+    let (speech, total) = speech_frames(samples, sample_rate);
+    if total == 0 {
+        0.0
+    } else {
+        speech as f32 / total as f32
+    }
+}
+
+/// True if the buffer contains at least ~100ms of speech-like frames
+pub fn detect_speech(samples: &[f32], sample_rate: u32) -> bool {
+    let (speech, _) = speech_frames(samples, sample_rate);
+    speech * FRAME_MS >= MIN_SPEECH_MS
+}
+
+/// Shorten pauses between speech to at most `max_silence_ms`
+///
+/// Only gaps with speech on both sides are touched; leading and trailing
+/// silence and all speech frames are copied unchanged. A shortened gap keeps
+/// its first and last halves, so the audio around each word boundary stays
+/// intact and a sentence-sized pause remains.
+pub fn compress_silences(samples: &[f32], sample_rate: u32, max_silence_ms: u32) -> Vec<f32> {
+    let (mask, frame_len) = speech_mask(samples, sample_rate);
+    let max_frames = (max_silence_ms as usize / FRAME_MS).max(1);
+    let (Some(first), Some(last)) = (
+        mask.iter().position(|speech| *speech),
+        mask.iter().rposition(|speech| *speech),
+    ) else {
+        return samples.to_vec();
+    };
+
+    let mut out = Vec::with_capacity(samples.len());
+    out.extend_from_slice(&samples[..first * frame_len]);
+    let mut idx = first;
+    while idx <= last {
+        let run_end = mask[idx..=last]
+            .iter()
+            .position(|speech| *speech != mask[idx])
+            .map_or(last + 1, |len| idx + len);
+        let start = idx * frame_len;
+        let end = (run_end * frame_len).min(samples.len());
+        if mask[idx] || run_end - idx <= max_frames {
+            out.extend_from_slice(&samples[start..end]);
+        } else {
+            let head = max_frames / 2 * frame_len;
+            let tail = (max_frames - max_frames / 2) * frame_len;
+            out.extend_from_slice(&samples[start..start + head]);
+            out.extend_from_slice(&samples[end - tail..end]);
+        }
+        idx = run_end;
+    }
+    out.extend_from_slice(&samples[((last + 1) * frame_len).min(samples.len())..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16_000;
+
+    /// Deterministic white noise in [-amp, amp]
+    fn noise(len: usize, amp: f32) -> Vec<f32> {
+        let mut state = 0x9e37_79b9_u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amp
+            })
+            .collect()
+    }
+
+    /// Broadband noise with a 4 Hz syllable-like envelope over a quiet background
+    fn speech_like(secs: usize) -> Vec<f32> {
+        noise(RATE as usize * secs, 1.0)
+            .into_iter()
+            .enumerate()
+            .map(|(i, n)| {
+                let t = i as f32 / RATE as f32;
+                let envelope = (t * 4.0 * std::f32::consts::TAU).sin().max(0.0);
+                n * (0.005 + 0.2 * envelope)
+            })
+            .collect()
+    }
+
+    fn hum(secs: usize) -> Vec<f32> {
+        (0..RATE as usize * secs)
+            .map(|i| 0.05 * (i as f32 * 120.0 * std::f32::consts::TAU / RATE as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn modulated_broadband_signal_is_speech() {
+        let samples = speech_like(2);
+        assert!(detect_speech(&samples, RATE));
+        let ratio = speech_ratio(&samples, RATE);
+        assert!(ratio > 0.2 && ratio < 0.9, "ratio {ratio}");
+    }
+
+    #[test]
+    fn steady_tone_and_silence_are_not_speech() {
+        assert!(!detect_speech(&hum(2), RATE));
+        assert!(!detect_speech(&vec![0.0; RATE as usize], RATE));
+        assert!(!detect_speech(&noise(RATE as usize, 0.001), RATE));
+        assert_eq!(speech_ratio(&[], RATE), 0.0);
+    }
+
+    #[test]
+    fn long_internal_gap_is_shortened_and_speech_kept() {
+        let second = RATE as usize;
+        let speech = noise(second, 0.2);
+        let mut samples = vec![0.0; second / 2];
+        samples.extend_from_slice(&speech);
+        samples.extend(vec![0.0; 3 * second]);
+        samples.extend_from_slice(&speech);
+        samples.extend(vec![0.0; second / 2]);
+
+        let out = compress_silences(&samples, RATE, 800);
+        // 3s gap -> 0.8s; leading/trailing silence untouched
+        assert_eq!(out.len(), samples.len() - 3 * second + second * 8 / 10);
+        assert_eq!(&out[second / 2..second / 2 + second], &speech[..]);
+        let tail = out.len() - second / 2;
+        assert_eq!(&out[tail - second..tail], &speech[..]);
+        assert!(out[tail..].iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn short_gaps_and_silence_only_input_are_unchanged() {
+        let second = RATE as usize;
+        let mut samples = noise(second, 0.2);
+        samples.extend(vec![0.0; second / 2]);
+        samples.extend(noise(second, 0.2));
+        assert_eq!(compress_silences(&samples, RATE, 800), samples);
+
+        let silence = vec![0.0; 2 * second];
+        assert_eq!(compress_silences(&silence, RATE, 800), silence);
+    }
+}
@@ -0,0 +1,110 @@
+//! Error type for the public library API
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Errors surfaced by the `asr`, `llm` and `audio` modules
+#[derive(Debug)]
+pub enum MofaError {
+    /// Model file missing on disk
+    ModelNotFound(PathBuf),
+    /// Model file exists but could not be loaded
+    ModelLoad(String),
+    /// Audio device or audio file could not be opened/read
+    AudioDevice(String),
+    /// Whisper inference failed
+    Transcribe(String),
+    /// LLM inference/tokenization failed
+    Llm(String),
+}
+
+pub type Result<T> = std::result::Result<T, MofaError>;
+
+impl fmt::Display for MofaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ModelNotFound(path) => write!(f, "model file not found: {}", path.display()),
+            Self::ModelLoad(msg) => write!(f, "failed to load model: {msg}"),
+            Self::AudioDevice(msg) => write!(f, "audio error: {msg}"),
+            Self::Transcribe(msg) => write!(f, "transcription failed: {msg}"),
+            Self::Llm(msg) => write!(f, "LLM error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for MofaError {}
+
+impl MofaError {
+    pub(crate) fn model_load(err: impl fmt::Display) -> Self {
+        Self::ModelLoad(err.to_string())
+    }
+
+    pub(crate) fn audio_device(err: impl fmt::Display) -> Self {
+        Self::AudioDevice(err.to_string())
+    }
+
+    pub(crate) fn transcribe(err: impl fmt::Display) -> Self {
+        Self::Transcribe(err.to_string())
+    }
+
+    pub(crate) fn llm(err: impl fmt::Display) -> Self {
+        Self::Llm(err.to_string())
+    }
+}
+
+impl From<hound::Error> for MofaError {
+    fn from(err: hound::Error) -> Self {
+        Self::AudioDevice(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_variant_names_what_failed() {
+        let cases = [
+            (
+                MofaError::ModelNotFound(PathBuf::from("/m/ggml-tiny.bin")),
+                "model file not found: /m/ggml-tiny.bin",
+            ),
+            (
+                MofaError::model_load("bad header"),
+                "failed to load model: bad header",
+            ),
+            (
+                MofaError::AudioDevice("no input".into()),
+                "audio error: no input",
+            ),
+            (
+                MofaError::audio_device("stream closed"),
+                "audio error: stream closed",
+            ),
+            (
+                MofaError::transcribe("whisper_full -1"),
+                "transcription failed: whisper_full -1",
+            ),
+            (MofaError::llm("ctx full"), "LLM error: ctx full"),
+        ];
+        for (err, message) in cases {
+            assert_eq!(err.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn hound_errors_become_audio_errors() {
+        let err: MofaError = hound::Error::Unsupported.into();
+        assert!(matches!(err, MofaError::AudioDevice(_)));
+        assert_eq!(
+            err.to_string(),
+            format!("audio error: {}", hound::Error::Unsupported)
+        );
+    }
+
+    #[test]
+    fn usable_as_std_error() {
+        let err: Box<dyn std::error::Error> = Box::new(MofaError::llm("oops"));
+        assert_eq!(err.to_string(), "LLM error: oops");
+    }
+}
@@ -1,4 +1,8 @@
+pub mod error;
 pub mod llm;
 pub mod asr;
 pub mod audio;
 pub mod gui;
+pub mod text;
+
+pub use error::{MofaError, Result};
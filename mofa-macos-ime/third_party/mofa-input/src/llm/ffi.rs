@@ -1,6 +1,8 @@
 use std::ffi::{c_char, c_float, c_int, c_void, CStr, CString};
 use std::path::Path;
 
+use crate::error::{MofaError, Result};
+
 pub struct LlmEngine {
     ctx: *mut c_void,
 }
@@ -20,6 +22,11 @@ extern "C" {
     fn llm_free_string(s: *mut c_char);
 
     fn llm_kv_count(ctx: *mut c_void) -> c_int;
+    fn llm_n_ctx(ctx: *mut c_void) -> c_int;
+
+    fn llm_tokenize(ctx: *mut c_void, text: *const c_char, tokens: *mut i32, n_max: c_int)
+        -> c_int;
+    fn llm_detokenize(ctx: *mut c_void, tokens: *const i32, n_tokens: c_int) -> *mut c_char;
 
     fn llm_chat_add_user(ctx: *mut c_void, message: *const c_char);
     fn llm_chat_respond(ctx: *mut c_void, max_tokens: c_int, temperature: c_float) -> *mut c_char;
@@ -37,20 +44,26 @@ extern "C" fn token_callback(token: *const c_char, user_data: *mut c_void) {
 }
 
 impl LlmEngine {
-    pub fn new(model_path: &Path) -> anyhow::Result<Self> {
-        let path_str = CString::new(model_path.to_str().ok_or_else(|| anyhow::anyhow!("Invalid path"))?)?;
+    pub fn new(model_path: &Path) -> Result<Self> {
+        let path = model_path
+            .to_str()
+            .ok_or_else(|| MofaError::ModelLoad(format!("non-UTF-8 path: {:?}", model_path)))?;
+        let path_str = CString::new(path).map_err(MofaError::model_load)?;
         let ctx = unsafe { llm_init(path_str.as_ptr()) };
         if ctx.is_null() {
-            return Err(anyhow::anyhow!("Failed to initialize LLM"));
+            return Err(MofaError::ModelLoad(format!(
+                "llm_init failed for {:?}",
+                model_path
+            )));
         }
         Ok(Self { ctx })
     }
 
-    pub fn generate(&self, prompt: &str, max_tokens: i32, temperature: f32) -> anyhow::Result<String> {
-        let c_prompt = CString::new(prompt)?;
+    pub fn generate(&self, prompt: &str, max_tokens: i32, temperature: f32) -> Result<String> {
+        let c_prompt = CString::new(prompt).map_err(MofaError::llm)?;
         let result = unsafe { llm_generate(self.ctx, c_prompt.as_ptr(), max_tokens, temperature) };
         if result.is_null() {
-            return Err(anyhow::anyhow!("Generation failed"));
+            return Err(MofaError::Llm("generation failed".into()));
         }
         let s = unsafe { CStr::from_ptr(result).to_string_lossy().into_owned() };
         unsafe { llm_free_string(result) };
@@ -77,16 +90,16 @@ impl LlmEngine {
 
     // ===== Multi-turn chat =====
 
-    pub fn chat_add_user(&self, message: &str) -> anyhow::Result<()> {
-        let c_msg = CString::new(message)?;
+    pub fn chat_add_user(&self, message: &str) -> Result<()> {
+        let c_msg = CString::new(message).map_err(MofaError::llm)?;
         unsafe { llm_chat_add_user(self.ctx, c_msg.as_ptr()) };
         Ok(())
     }
 
-    pub fn chat_respond(&self, max_tokens: i32, temperature: f32) -> anyhow::Result<String> {
+    pub fn chat_respond(&self, max_tokens: i32, temperature: f32) -> Result<String> {
         let result = unsafe { llm_chat_respond(self.ctx, max_tokens, temperature) };
         if result.is_null() {
-            return Err(anyhow::anyhow!("Chat response failed"));
+            return Err(MofaError::Llm("chat response failed".into()));
         }
         let s = unsafe { CStr::from_ptr(result).to_string_lossy().into_owned() };
         unsafe { llm_free_string(result) };
@@ -116,6 +129,54 @@ impl LlmEngine {
     pub fn kv_count(&self) -> i32 {
         unsafe { llm_kv_count(self.ctx) }
     }
+
+    pub fn n_ctx(&self) -> i32 {
+        unsafe { llm_n_ctx(self.ctx) }
+    }
+
+    // ===== Tokenizer =====
+
+    pub fn tokenize(&self, text: &str) -> Result<Vec<i32>> {
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        let c_text = CString::new(text).map_err(MofaError::llm)?;
+        let mut tokens = vec![0i32; text.len() + 8];
+        let mut n = unsafe {
+            llm_tokenize(
+                self.ctx,
+                c_text.as_ptr(),
+                tokens.as_mut_ptr(),
+                tokens.len() as c_int,
+            )
+        };
+        if n < 0 {
+            tokens.resize((-n) as usize, 0);
+            n = unsafe {
+                llm_tokenize(
+                    self.ctx,
+                    c_text.as_ptr(),
+                    tokens.as_mut_ptr(),
+                    tokens.len() as c_int,
+                )
+            };
+            if n < 0 {
+                return Err(MofaError::Llm("tokenization failed".into()));
+            }
+        }
+        tokens.truncate(n as usize);
+        Ok(tokens)
+    }
+
+    pub fn detokenize(&self, tokens: &[i32]) -> Result<String> {
+        let result = unsafe { llm_detokenize(self.ctx, tokens.as_ptr(), tokens.len() as c_int) };
+        if result.is_null() {
+            return Err(MofaError::Llm("detokenization failed".into()));
+        }
+        let s = unsafe { CStr::from_ptr(result).to_string_lossy().into_owned() };
+        unsafe { llm_free_string(result) };
+        Ok(s)
+    }
 }
 
 impl Drop for LlmEngine {
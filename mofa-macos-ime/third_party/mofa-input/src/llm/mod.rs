@@ -3,6 +3,8 @@ pub mod ffi;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+use crate::error::{MofaError, Result};
+
 /// Thread-safe wrapper for multi-turn conversations
 #[derive(Clone)]
 pub struct ChatSession {
@@ -10,7 +12,10 @@ pub struct ChatSession {
 }
 
 impl ChatSession {
-    pub fn new(model_path: &Path) -> anyhow::Result<Self> {
+    pub fn new(model_path: &Path) -> Result<Self> {
+        if !model_path.exists() {
+            return Err(MofaError::ModelNotFound(model_path.to_path_buf()));
+        }
         let engine = ffi::LlmEngine::new(model_path)?;
         Ok(Self {
             engine: Arc::new(Mutex::new(engine)),
@@ -18,7 +23,7 @@ impl ChatSession {
     }
 
     /// Send message and get complete response
-    pub fn send(&self, message: &str, max_tokens: i32, temperature: f32) -> anyhow::Result<String> {
+    pub fn send(&self, message: &str, max_tokens: i32, temperature: f32) -> Result<String> {
         let engine = self.engine.lock().unwrap();
         engine.chat_add_user(message)?;
         engine.chat_respond(max_tokens, temperature)
@@ -45,4 +50,59 @@ impl ChatSession {
         let engine = self.engine.lock().unwrap();
         engine.kv_count()
     }
+
+    /// Context window size in tokens (prompt + response); 0 if unknown
+    pub fn context_size(&self) -> usize {
+        let engine = self.engine.lock().unwrap();
+        usize::try_from(engine.n_ctx()).unwrap_or(0)
+    }
+
+    /// Count tokens of an arbitrary string (no BOS/EOS); 0 on failure
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.tokenize(text).map(|t| t.len()).unwrap_or(0)
+    }
+
+    /// Tokenize with the model's tokenizer
+    pub fn tokenize(&self, text: &str) -> Result<Vec<i32>> {
+        let engine = self.engine.lock().unwrap();
+        engine.tokenize(text)
+    }
+
+    /// Convert tokens back to text
+    pub fn detokenize(&self, tokens: &[i32]) -> Result<String> {
+        let engine = self.engine.lock().unwrap();
+        engine.detokenize(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Needs `~/.mofa/models/qwen2.5-0.5b-q4_k_m.gguf`; skipped when it is not installed.
+    fn small_model() -> Option<ChatSession> {
+        let path = dirs::home_dir()?.join(".mofa/models/qwen2.5-0.5b-q4_k_m.gguf");
+        path.exists().then(|| ChatSession::new(&path).unwrap())
+    }
+
+    #[test]
+    fn empty_string_has_no_tokens() {
+        let Some(chat) = small_model() else {
+            return;
+        };
+        assert_eq!(chat.count_tokens(""), 0);
+        assert!(chat.tokenize("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn token_counts_are_stable_for_fixed_input() {
+        let Some(chat) = small_model() else {
+            return;
+        };
+        let text = "把这句话润色一下, please.";
+        let first = chat.tokenize(text).unwrap();
+        assert!(!first.is_empty());
+        assert_eq!(chat.tokenize(text).unwrap(), first);
+        assert_eq!(chat.count_tokens(text), first.len());
+    }
 }
@@ -0,0 +1,391 @@
+//! Text post-processing helpers shared by the IME pipeline
+
+mod pinyin;
+
+pub use pinyin::{char_pinyin, to_pinyin};
+
+/// Spoken phrase -> literal symbol substitutions (coding dictation)
+#[derive(Clone, Debug, Default)]
+pub struct SymbolMap {
+    // Kept sorted longest phrase first so "左括号" wins over "括号".
+    entries: Vec<(String, String)>,
+}
+
+impl SymbolMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Built-in Chinese/English spoken symbols
+    pub fn builtin() -> Self {
+        let mut map = Self::new();
+        for (phrase, symbol) in [
+            ("左括号", "("),
+            ("右括号", ")"),
+            ("左方括号", "["),
+            ("右方括号", "]"),
+            ("左花括号", "{"),
+            ("右花括号", "}"),
+            ("左大括号", "{"),
+            ("右大括号", "}"),
+            ("等于号", "="),
+            ("双等号", "=="),
+            ("不等于", "!="),
+            ("分号", ";"),
+            ("冒号", ":"),
+            ("逗号", ","),
+            ("点号", "."),
+            ("下划线", "_"),
+            ("减号", "-"),
+            ("加号", "+"),
+            ("星号", "*"),
+            ("斜杠", "/"),
+            ("反斜杠", "\\"),
+            ("竖线", "|"),
+            ("井号", "#"),
+            ("箭头", "->"),
+            ("open paren", "("),
+            ("close paren", ")"),
+            ("open bracket", "["),
+            ("close bracket", "]"),
+            ("open brace", "{"),
+            ("close brace", "}"),
+            ("double equals", "=="),
+            ("not equals", "!="),
+            ("equals", "="),
+            ("semicolon", ";"),
+            ("colon", ":"),
+            ("comma", ","),
+            ("underscore", "_"),
+            ("plus", "+"),
+            ("minus", "-"),
+            ("asterisk", "*"),
+            ("slash", "/"),
+            ("backslash", "\\"),
+            ("pipe", "|"),
+            ("hash", "#"),
+            ("arrow", "->"),
+        ] {
+            map.insert(phrase, symbol);
+        }
+        map
+    }
+
+    /// Parse `phrase=symbol` lines; blank lines and `#` comments are skipped
+    pub fn parse(content: &str) -> Self {
+        let mut map = Self::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((phrase, symbol)) = line.split_once('=') {
+                map.insert(phrase, symbol);
+            }
+        }
+        map
+    }
+
+    /// Insert or replace a phrase (matched case-insensitively for ASCII)
+    pub fn insert(&mut self, phrase: &str, symbol: &str) {
+        let phrase = phrase.trim().to_ascii_lowercase();
+        if phrase.is_empty() {
+            return;
+        }
+        let symbol = symbol.trim().to_string();
+        self.entries.retain(|(p, _)| *p != phrase);
+        let pos = self
+            .entries
+            .iter()
+            .position(|(p, _)| p.len() < phrase.len())
+            .unwrap_or(self.entries.len());
+        self.entries.insert(pos, (phrase, symbol));
+    }
+
+    /// Merge another map on top of this one
+    pub fn extend(&mut self, other: &SymbolMap) {
+        for (phrase, symbol) in &other.entries {
+            self.insert(phrase, symbol);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Spoken shortcut -> full text expansions (e.g. "my email" -> the address)
+///
+/// Unlike the ASR correction and symbol dictionaries this runs on the final,
+/// already polished text right before injection.
+#[derive(Clone, Debug, Default)]
+pub struct ExpansionMap {
+    // Same matching rules as symbols: longest phrase first, ASCII case-insensitive.
+    phrases: SymbolMap,
+}
+
+impl ExpansionMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `phrase => expansion` lines; blank lines and `#` comments are skipped
+    pub fn parse(content: &str) -> Self {
+        let mut map = Self::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((phrase, expansion)) = line.split_once("=>") {
+                map.insert(phrase, expansion);
+            }
+        }
+        map
+    }
+
+    pub fn insert(&mut self, phrase: &str, expansion: &str) {
+        self.phrases.insert(phrase, expansion);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.phrases.is_empty()
+    }
+}
+
+/// Expand whole spoken phrases; text without a match passes through unchanged.
+///
+/// ASCII phrases only match on word boundaries, so "my email" does not fire
+/// inside "summy emails".
+pub fn apply_expansions(text: &str, map: &ExpansionMap) -> String {
+    // Chinese expansions are long, distinctive phrases spoken mid-sentence,
+    // so they match anywhere.
+    replace_phrases(text, &map.phrases, false)
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_'
+}
+
+/// Replace spoken symbol phrases with literal symbols.
+///
+/// Longest phrase wins. ASCII phrases only match on word boundaries, so
+/// "plus" inside "surplus" is left alone. Chinese has no word breaks, so a
+/// CJK phrase with CJK text directly on both sides is treated as part of a
+/// longer word ("增加号码" keeps its "加号"); at the start or end of the
+/// text, next to punctuation, whitespace, Latin, digits or another symbol it
+/// still matches.
+pub fn apply_symbol_map(text: &str, map: &SymbolMap) -> String {
+    replace_phrases(text, map, true)
+}
+
+fn replace_phrases(text: &str, map: &SymbolMap, cjk_boundaries: bool) -> String {
+    if map.is_empty() {
+        return text.to_string();
+    }
+
+    let lower = text.to_ascii_lowercase();
+    let mut out = String::with_capacity(text.len());
+    let mut prev: Option<char> = None;
+    let mut i = 0usize;
+
+    'scan: while i < text.len() {
+        for (phrase, symbol) in &map.entries {
+            if !lower[i..].starts_with(phrase.as_str()) {
+                continue;
+            }
+            let end = i + phrase.len();
+            let next = text[end..].chars().next();
+            let starts_word = phrase.chars().next().is_some_and(is_word_char);
+            let ends_word = phrase.chars().last().is_some_and(is_word_char);
+            if (starts_word && prev.is_some_and(is_word_char))
+                || (ends_word && next.is_some_and(is_word_char))
+            {
+                continue;
+            }
+            let embedded_cjk = phrase.chars().next().is_some_and(is_cjk)
+                && phrase.chars().last().is_some_and(is_cjk)
+                && prev.is_some_and(is_cjk)
+                && next.is_some_and(is_cjk);
+            if cjk_boundaries && embedded_cjk {
+                continue;
+            }
+            out.push_str(symbol);
+            prev = symbol.chars().last().or(prev);
+            i = end;
+            continue 'scan;
+        }
+
+        let ch = text[i..].chars().next().unwrap_or(' ');
+        out.push(ch);
+        prev = Some(ch);
+        i += ch.len_utf8();
+    }
+
+    out
+}
+
+/// Han, kana and hangul; CJK punctuation is excluded so "，Rust" stays tight.
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+/// Byte length of a span that must not be spaced: a `code` run or a URL up
+/// to the next whitespace.
+fn protected_span_len(rest: &str) -> Option<usize> {
+    if let Some(body) = rest.strip_prefix('`') {
+        return body.find('`').map(|end| end + 2);
+    }
+    let lower = rest.get(..8).unwrap_or(rest).to_ascii_lowercase();
+    if ["http://", "https://", "www."]
+        .iter()
+        .any(|scheme| lower.starts_with(scheme))
+    {
+        return Some(rest.find(char::is_whitespace).unwrap_or(rest.len()));
+    }
+    None
+}
+
+/// Insert a space wherever CJK text directly touches ASCII letters or digits,
+/// e.g. "使用Rust开发" -> "使用 Rust 开发".
+///
+/// Existing spaces are kept as is, and nothing is inserted inside `code`
+/// spans or URLs (a span may still be spaced off from the text before it).
+pub fn space_cjk_latin(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + text.len() / 4);
+    let mut prev: Option<char> = None;
+    let mut span = 0..0;
+
+    for (i, ch) in text.char_indices() {
+        if i >= span.end {
+            if let Some(len) = protected_span_len(&text[i..]) {
+                span = i..i + len;
+            }
+        }
+        // Only the first character of a span may get a leading space.
+        let interior = span.start < i && i < span.end;
+        if let Some(p) = prev {
+            let boundary = (is_cjk(p) && ch.is_ascii_alphanumeric())
+                || (p.is_ascii_alphanumeric() && is_cjk(ch));
+            if boundary && !interior {
+                out.push(' ');
+            }
+        }
+        out.push(ch);
+        prev = Some(ch);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_word_symbol_phrases_are_replaced() {
+        let map = SymbolMap::builtin();
+        assert_eq!(
+            apply_symbol_map("foo open paren x close paren", &map),
+            "foo ( x )"
+        );
+        assert_eq!(apply_symbol_map("a double equals b", &map), "a == b");
+        assert_eq!(apply_symbol_map("a Not Equals b", &map), "a != b");
+    }
+
+    #[test]
+    fn longest_chinese_phrase_wins() {
+        let map = SymbolMap::builtin();
+        assert_eq!(apply_symbol_map("左方括号零右方括号", &map), "[零]");
+        assert_eq!(apply_symbol_map("x等于号1分号", &map), "x=1;");
+    }
+
+    #[test]
+    fn chinese_phrases_inside_longer_words_are_kept() {
+        let map = SymbolMap::builtin();
+        assert_eq!(apply_symbol_map("增加号码", &map), "增加号码");
+        assert_eq!(apply_symbol_map("区分号码", &map), "区分号码");
+        assert_eq!(apply_symbol_map("然后写分号", &map), "然后写;");
+        assert_eq!(apply_symbol_map("a加号b，再加逗号", &map), "a+b，再加,");
+    }
+
+    #[test]
+    fn ascii_phrases_respect_word_boundaries() {
+        let map = SymbolMap::builtin();
+        assert_eq!(apply_symbol_map("surplus", &map), "surplus");
+        assert_eq!(apply_symbol_map("hashmap commas", &map), "hashmap commas");
+        assert_eq!(apply_symbol_map("a plus b", &map), "a + b");
+    }
+
+    #[test]
+    fn parsed_entries_override_builtin() {
+        let mut map = SymbolMap::builtin();
+        map.extend(&SymbolMap::parse("# comment\n\narrow = =>\n"));
+        assert_eq!(apply_symbol_map("x arrow y", &map), "x => y");
+    }
+
+    #[test]
+    fn multi_word_phrases_expand() {
+        let map = ExpansionMap::parse(
+            "# personal\nmy email => me@example.com\nmy address => 北京市海淀区 1 号\n",
+        );
+        assert_eq!(
+            apply_expansions("Send it to My Email please", &map),
+            "Send it to me@example.com please"
+        );
+        assert_eq!(
+            apply_expansions("寄到my address吧", &map),
+            "寄到北京市海淀区 1 号吧"
+        );
+    }
+
+    #[test]
+    fn unmatched_text_passes_through() {
+        let map = ExpansionMap::parse("my email => me@example.com\n");
+        assert_eq!(apply_expansions("summy emails", &map), "summy emails");
+        assert_eq!(
+            apply_expansions("no shortcut here", &map),
+            "no shortcut here"
+        );
+        assert_eq!(
+            apply_expansions("my email", &ExpansionMap::new()),
+            "my email"
+        );
+    }
+
+    #[test]
+    fn cjk_latin_boundaries_get_a_space() {
+        assert_eq!(space_cjk_latin("使用Rust开发"), "使用 Rust 开发");
+        assert_eq!(space_cjk_latin("用macOS的IME"), "用 macOS 的 IME");
+        assert_eq!(space_cjk_latin("已有 Rust 空格"), "已有 Rust 空格");
+        assert_eq!(space_cjk_latin("你好，Rust。"), "你好，Rust。");
+    }
+
+    #[test]
+    fn digits_are_spaced_like_letters() {
+        assert_eq!(space_cjk_latin("共3个文件"), "共 3 个文件");
+        assert_eq!(space_cjk_latin("2024年"), "2024 年");
+        assert_eq!(space_cjk_latin("版本1.2发布"), "版本 1.2 发布");
+    }
+
+    #[test]
+    fn urls_and_code_spans_are_not_spaced_inside() {
+        assert_eq!(
+            space_cjk_latin("打开https://例子.com/a页面"),
+            "打开 https://例子.com/a页面"
+        );
+        assert_eq!(
+            space_cjk_latin("运行`cargo测试`命令"),
+            "运行`cargo测试`命令"
+        );
+        assert_eq!(space_cjk_latin("未闭合`code中文"), "未闭合`code 中文");
+    }
+}